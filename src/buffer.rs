@@ -0,0 +1,641 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::LineString;
+use super::overlay::positive_region;
+use super::points::*;
+use super::polygons::{MultiPolygon, Polygon, shoelace};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// How the offset lines of two consecutive segments are joined at a vertex
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JoinStyle {
+    /// Circular arc around the vertex
+    Round,
+    /// Extend the offset lines until they meet, up to the mitre limit
+    Mitre,
+    /// Straight line between the ends of the offset lines
+    Bevel,
+}
+
+/// How the ends of a linestring's buffer are shaped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CapStyle {
+    /// Half circle around the endpoint
+    Round,
+    /// The buffer ends at the endpoint
+    Flat,
+    /// Square extending the buffer distance beyond the endpoint
+    Square,
+}
+
+/// Options to compute a buffer
+#[derive(Clone, Debug)]
+pub struct BufferOptions {
+    pub join_style: JoinStyle,
+    pub cap_style: CapStyle,
+    /// Number of segments used to approximate a quarter circle
+    pub quadrant_segments: usize,
+    /// Maximum distance from a vertex to its mitre join, as a multiple of the buffer distance.
+    /// Longer mitres are replaced by bevels.
+    pub mitre_limit: f64,
+}
+
+impl Default for BufferOptions {
+    fn default() -> Self {
+        Self {
+            join_style: JoinStyle::Round,
+            cap_style: CapStyle::Round,
+            quadrant_segments: 8,
+            mitre_limit: 5.0,
+        }
+    }
+}
+
+/// Geometries that can be buffered: expanded (or shrunk) by a given distance.
+///
+/// The buffer of a geometry is the region of points within the given distance of it. For
+/// polygons a negative distance shrinks the polygon instead. The result is always given as a
+/// multipolygon, since buffers can be split into several parts or be empty.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Buffer, BufferOptions, GeometricObject, LineString, Point};
+/// let road = LineString::new(vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)]).unwrap();
+///
+/// let options = BufferOptions::default();
+/// let area = road.buffer(1.0, &options).unwrap();
+/// assert_eq!(area.polygons.len(), 1);
+/// assert!((area.area() - (20.0 + std::f64::consts::PI)).abs() < 0.1);
+/// ```
+pub trait Buffer {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon>;
+}
+
+impl Buffer for Point {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon> {
+        check_options(distance, options)?;
+        if distance <= 0.0 {
+            return Ok(MultiPolygon::new(Vec::new()));
+        }
+        Ok(MultiPolygon::new(vec![Polygon::new(circle(
+            self,
+            distance,
+            options.quadrant_segments,
+        ))?]))
+    }
+}
+
+impl Buffer for MultiPoint {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon> {
+        check_options(distance, options)?;
+        if distance <= 0.0 {
+            return Ok(MultiPolygon::new(Vec::new()));
+        }
+        let circles: Vec<Vec<Point>> = self
+            .points
+            .iter()
+            .map(|pt| circle(pt, distance, options.quadrant_segments))
+            .collect();
+        Ok(MultiPolygon::new(positive_region(&circles)))
+    }
+}
+
+impl Buffer for LineString {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon> {
+        check_options(distance, options)?;
+        if distance <= 0.0 {
+            return Ok(MultiPolygon::new(Vec::new()));
+        }
+        let pieces = line_pieces(&self.points, distance, options);
+        Ok(MultiPolygon::new(positive_region(&pieces)))
+    }
+}
+
+impl Buffer for Polygon {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon> {
+        check_options(distance, options)?;
+        let rings = polygon_rings(std::slice::from_ref(self), distance, options);
+        Ok(MultiPolygon::new(positive_region(&rings)))
+    }
+}
+
+impl Buffer for MultiPolygon {
+    fn buffer(&self, distance: f64, options: &BufferOptions) -> GeomResult<MultiPolygon> {
+        check_options(distance, options)?;
+        let rings = polygon_rings(&self.polygons, distance, options);
+        Ok(MultiPolygon::new(positive_region(&rings)))
+    }
+}
+
+/// Verify that the buffer parameters are valid
+fn check_options(distance: f64, options: &BufferOptions) -> GeomResult<()> {
+    if !distance.is_finite() {
+        Err(GeometryError::ParameterError(format!(
+            "Buffer distance must be finite, got {distance}"
+        )))
+    } else if options.quadrant_segments == 0 {
+        Err(GeometryError::ParameterError(String::from(
+            "At least one segment per quadrant is required",
+        )))
+    } else if options.mitre_limit.is_nan() || options.mitre_limit <= 0.0 {
+        Err(GeometryError::ParameterError(format!(
+            "Mitre limit must be positive, got {}",
+            options.mitre_limit
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Rings whose positive winding region is the buffer of the given polygons.
+///
+/// The polygons' rings are oriented so that their interiors have winding number one. The
+/// pieces covering the area within the distance of their boundaries are added counter-clockwise
+/// for positive distances and clockwise for negative ones, which removes them from the interior.
+fn polygon_rings(polygons: &[Polygon], distance: f64, options: &BufferOptions) -> Vec<Vec<Point>> {
+    let mut rings = Vec::new();
+    let mut pieces = Vec::new();
+    for poly in polygons {
+        for (i, ring) in poly.rings().enumerate() {
+            let mut oriented = ring.clone();
+            // Outer rings counter-clockwise, holes clockwise
+            if (i == 0) == (shoelace(&oriented) > 0.0) {
+                oriented.reverse();
+            }
+            if distance != 0.0 {
+                pieces.extend(ring_pieces(&oriented, distance.abs(), options));
+            }
+            rings.push(oriented);
+        }
+    }
+    if distance < 0.0 {
+        for piece in pieces.iter_mut() {
+            piece.reverse();
+        }
+    }
+    rings.append(&mut pieces);
+    rings
+}
+
+/// Offset points of a segment: start and end points shifted to the left and right
+struct SegmentOffsets {
+    left: (Point, Point),
+    right: (Point, Point),
+    /// Unit direction of the segment
+    dir: (f64, f64),
+}
+
+impl SegmentOffsets {
+    fn new(start: &Point, end: &Point, distance: f64) -> Self {
+        let (x1, y1) = start.coords();
+        let (x2, y2) = end.coords();
+        let len = start.l2_distance(end);
+        let (ux, uy) = ((x2 - x1) / len, (y2 - y1) / len);
+        let (nx, ny) = (-uy * distance, ux * distance);
+        Self {
+            left: (Point::new(x1 + nx, y1 + ny), Point::new(x2 + nx, y2 + ny)),
+            right: (Point::new(x1 - nx, y1 - ny), Point::new(x2 - nx, y2 - ny)),
+            dir: (ux, uy),
+        }
+    }
+
+    /// Counter-clockwise rectangle covering the points within the distance of the segment's
+    /// interior.
+    fn rectangle(&self) -> Vec<Point> {
+        vec![
+            self.right.0.clone(),
+            self.right.1.clone(),
+            self.left.1.clone(),
+            self.left.0.clone(),
+            self.right.0.clone(),
+        ]
+    }
+}
+
+/// Remove consecutive duplicate points from a sequence
+fn dedup_points(points: &[Point]) -> Vec<Point> {
+    let mut pts: Vec<Point> = Vec::with_capacity(points.len());
+    for pt in points {
+        if pts.last().is_none_or(|last| last.coords() != pt.coords()) {
+            pts.push(pt.clone());
+        }
+    }
+    pts
+}
+
+/// Counter-clockwise pieces whose union is the buffer of a linestring
+fn line_pieces(points: &[Point], distance: f64, options: &BufferOptions) -> Vec<Vec<Point>> {
+    let pts = dedup_points(points);
+    if pts.len() == 1 {
+        // Degenerate linestring: buffer its single point according to the cap style
+        return match options.cap_style {
+            CapStyle::Round => vec![circle(&pts[0], distance, options.quadrant_segments)],
+            CapStyle::Square => {
+                let (x, y) = pts[0].coords();
+                vec![square_ring(x - distance, y - distance, 2.0 * distance)]
+            }
+            CapStyle::Flat => Vec::new(),
+        };
+    }
+    if pts.len() > 3 && pts[0].coords() == pts[pts.len() - 1].coords() {
+        // Closed linestrings are buffered as rings, without caps
+        return ring_pieces(&pts, distance, options);
+    }
+
+    let offsets: Vec<SegmentOffsets> = pts
+        .windows(2)
+        .map(|pair| SegmentOffsets::new(&pair[0], &pair[1], distance))
+        .collect();
+    let mut pieces: Vec<Vec<Point>> = offsets.iter().map(|off| off.rectangle()).collect();
+    for (i, pair) in offsets.windows(2).enumerate() {
+        pieces.extend(join_piece(
+            &pts[i + 1],
+            &pair[0],
+            &pair[1],
+            distance,
+            options,
+        ));
+    }
+
+    let first = &offsets[0];
+    let last = &offsets[offsets.len() - 1];
+    let quad = options.quadrant_segments;
+    match options.cap_style {
+        CapStyle::Round => {
+            pieces.push(fan(&pts[0], &first.left.0, PI, quad));
+            pieces.push(fan(&pts[pts.len() - 1], &last.right.1, PI, quad));
+        }
+        CapStyle::Square => {
+            let (ux, uy) = first.dir;
+            let (x, y) = pts[0].coords();
+            let back = Point::new(x - ux * distance, y - uy * distance);
+            pieces.push(SegmentOffsets::new(&back, &pts[0], distance).rectangle());
+
+            let (ux, uy) = last.dir;
+            let (x, y) = pts[pts.len() - 1].coords();
+            let ahead = Point::new(x + ux * distance, y + uy * distance);
+            pieces.push(SegmentOffsets::new(&pts[pts.len() - 1], &ahead, distance).rectangle());
+        }
+        CapStyle::Flat => (),
+    }
+    pieces
+}
+
+/// Counter-clockwise pieces whose union is the region within the distance of a closed ring
+fn ring_pieces(ring: &[Point], distance: f64, options: &BufferOptions) -> Vec<Vec<Point>> {
+    let mut pts = dedup_points(ring);
+    if pts.len() > 1 && pts[0].coords() == pts[pts.len() - 1].coords() {
+        pts.pop();
+    }
+    if pts.len() < 2 {
+        return Vec::new();
+    }
+
+    let total = pts.len();
+    let offsets: Vec<SegmentOffsets> = (0..total)
+        .map(|i| SegmentOffsets::new(&pts[i], &pts[(i + 1) % total], distance))
+        .collect();
+    let mut pieces: Vec<Vec<Point>> = offsets.iter().map(|off| off.rectangle()).collect();
+    for i in 0..total {
+        let incoming = &offsets[(i + total - 1) % total];
+        pieces.extend(join_piece(
+            &pts[i],
+            incoming,
+            &offsets[i],
+            distance,
+            options,
+        ));
+    }
+    pieces
+}
+
+/// Piece filling the gap between the rectangles of two consecutive segments at a vertex, on
+/// the outer side of the turn.
+fn join_piece(
+    vertex: &Point,
+    incoming: &SegmentOffsets,
+    outgoing: &SegmentOffsets,
+    distance: f64,
+    options: &BufferOptions,
+) -> Option<Vec<Point>> {
+    let (ux1, uy1) = incoming.dir;
+    let (ux2, uy2) = outgoing.dir;
+    let cross = ux1 * uy2 - uy1 * ux2;
+    let dot = ux1 * ux2 + uy1 * uy2;
+    let angle = cross.atan2(dot);
+    if angle.abs() < 1e-12 {
+        // Straight continuation: the rectangles already meet
+        return None;
+    }
+
+    // Offset points on the outer side of the turn. The piece is built counter-clockwise
+    // around the vertex, so for right turns it goes from the outgoing to the incoming offset.
+    let left_turn = angle > 0.0;
+    let (from, to) = if left_turn {
+        (&incoming.right.1, &outgoing.right.0)
+    } else {
+        (&outgoing.left.0, &incoming.left.1)
+    };
+    let sweep = angle.abs();
+
+    match options.join_style {
+        JoinStyle::Round => Some(fan(vertex, from, sweep, options.quadrant_segments)),
+        JoinStyle::Bevel => Some(vec![
+            vertex.clone(),
+            from.clone(),
+            to.clone(),
+            vertex.clone(),
+        ]),
+        JoinStyle::Mitre => {
+            let half = sweep / 2.0;
+            let mitre_len = distance / half.cos();
+            if half >= FRAC_PI_2 - 1e-12 || mitre_len > options.mitre_limit * distance {
+                return Some(vec![
+                    vertex.clone(),
+                    from.clone(),
+                    to.clone(),
+                    vertex.clone(),
+                ]);
+            }
+            // Bisector of the offset points, extended to the mitre length
+            let (vx, vy) = vertex.coords();
+            let (bx, by) = from.rotate_around(vertex, half).coords();
+            let scale = mitre_len / distance;
+            let tip = Point::new(vx + (bx - vx) * scale, vy + (by - vy) * scale);
+            Some(vec![
+                vertex.clone(),
+                from.clone(),
+                tip,
+                to.clone(),
+                vertex.clone(),
+            ])
+        }
+    }
+}
+
+/// Counter-clockwise circular sector ("fan") around `center`, starting at `start` and
+/// sweeping the given angle.
+fn fan(center: &Point, start: &Point, sweep: f64, quadrant_segments: usize) -> Vec<Point> {
+    let steps = ((sweep / (FRAC_PI_2 / quadrant_segments as f64)).ceil() as usize).max(1);
+    let mut pts = Vec::with_capacity(steps + 3);
+    pts.push(center.clone());
+    pts.push(start.clone());
+    for i in 1..=steps {
+        pts.push(start.rotate_around(center, sweep * i as f64 / steps as f64));
+    }
+    pts.push(center.clone());
+    pts
+}
+
+/// Counter-clockwise polygon approximating a circle
+pub(crate) fn circle(center: &Point, radius: f64, quadrant_segments: usize) -> Vec<Point> {
+    let (x, y) = center.coords();
+    let total = 4 * quadrant_segments;
+    let mut pts: Vec<Point> = (0..total)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / total as f64;
+            Point::new(x + radius * angle.cos(), y + radius * angle.sin())
+        })
+        .collect();
+    pts.push(pts[0].clone());
+    pts
+}
+
+/// Counter-clockwise square ring with the given lower left corner
+fn square_ring(x: f64, y: f64, size: f64) -> Vec<Point> {
+    vec![
+        Point::new(x, y),
+        Point::new(x + size, y),
+        Point::new(x + size, y + size),
+        Point::new(x, y + size),
+        Point::new(x, y),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::GeometricObject;
+    use super::*;
+
+    fn unit_square() -> Polygon {
+        Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap()
+    }
+
+    fn options(join_style: JoinStyle, cap_style: CapStyle) -> BufferOptions {
+        BufferOptions {
+            join_style,
+            cap_style,
+            quadrant_segments: 16,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_point_buffer() {
+        let buf = Point::new(1.0, 2.0)
+            .buffer(2.0, &BufferOptions::default())
+            .unwrap();
+        assert_eq!(buf.polygons.len(), 1);
+        assert!((buf.area() - 4.0 * PI).abs() < 0.1);
+        assert!(buf.contains(&Point::new(2.9, 2.0)));
+        assert!(!buf.contains(&Point::new(3.1, 2.0)));
+
+        let empty = Point::new(1.0, 2.0)
+            .buffer(-1.0, &BufferOptions::default())
+            .unwrap();
+        assert!(empty.polygons.is_empty());
+    }
+
+    #[test]
+    fn test_multipoint_buffer() {
+        let pts = MultiPoint::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(10.0, 0.0),
+        ]);
+        let buf = pts.buffer(1.0, &BufferOptions::default()).unwrap();
+        assert_eq!(buf.polygons.len(), 2);
+    }
+
+    #[test]
+    fn test_line_buffer_caps() {
+        let line = LineString::new(vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)]).unwrap();
+
+        let flat = line
+            .buffer(1.0, &options(JoinStyle::Round, CapStyle::Flat))
+            .unwrap();
+        assert!((flat.area() - 8.0).abs() < 1e-9);
+
+        let square = line
+            .buffer(1.0, &options(JoinStyle::Round, CapStyle::Square))
+            .unwrap();
+        assert!((square.area() - 12.0).abs() < 1e-9);
+        assert!(square.contains(&Point::new(-0.9, 0.9)));
+
+        let round = line
+            .buffer(1.0, &options(JoinStyle::Round, CapStyle::Round))
+            .unwrap();
+        assert!((round.area() - (8.0 + PI)).abs() < 0.05);
+        assert!(!round.contains(&Point::new(-0.9, 0.9)));
+    }
+
+    #[test]
+    fn test_line_buffer_joins() {
+        // Right angle
+        let line = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+        ])
+        .unwrap();
+
+        let mitre = line
+            .buffer(1.0, &options(JoinStyle::Mitre, CapStyle::Flat))
+            .unwrap();
+        assert_eq!(mitre.polygons.len(), 1);
+        assert!((mitre.area() - 16.0).abs() < 1e-9);
+        assert!(mitre.contains(&Point::new(4.9, -0.9)));
+
+        let bevel = line
+            .buffer(1.0, &options(JoinStyle::Bevel, CapStyle::Flat))
+            .unwrap();
+        assert!((bevel.area() - 15.5).abs() < 1e-9);
+
+        let round = line
+            .buffer(1.0, &options(JoinStyle::Round, CapStyle::Flat))
+            .unwrap();
+        assert!((round.area() - (15.0 + PI / 4.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_closed_line_buffer() {
+        // Buffer of a closed linestring has a hole
+        let ring = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 10.0),
+            Point::new(10.0, 10.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let buf = ring
+            .buffer(1.0, &options(JoinStyle::Mitre, CapStyle::Round))
+            .unwrap();
+        assert_eq!(buf.polygons.len(), 1);
+        assert_eq!(buf.polygons[0].inner.len(), 1);
+        assert!((buf.area() - (144.0 - 64.0)).abs() < 1e-9);
+        assert!(!buf.contains(&Point::new(5.0, 5.0)));
+    }
+
+    #[test]
+    fn test_self_overlapping_line() {
+        // Line doubling back over itself
+        let line = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(5.0, 0.0),
+        ])
+        .unwrap();
+        let buf = line
+            .buffer(1.0, &options(JoinStyle::Round, CapStyle::Flat))
+            .unwrap();
+        assert_eq!(buf.polygons.len(), 1);
+        assert!((buf.area() - (20.0 + PI / 2.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_polygon_buffer() {
+        let square = unit_square();
+        let grown = square
+            .buffer(1.0, &options(JoinStyle::Mitre, CapStyle::Round))
+            .unwrap();
+        assert!((grown.area() - 9.0).abs() < 1e-9);
+
+        let round = square.buffer(1.0, &BufferOptions::default()).unwrap();
+        assert!((round.area() - (5.0 + PI)).abs() < 0.05);
+
+        let shrunk = square
+            .buffer(-0.25, &options(JoinStyle::Mitre, CapStyle::Round))
+            .unwrap();
+        assert!((shrunk.area() - 0.25).abs() < 1e-9);
+        assert!(shrunk.contains(&Point::new(0.5, 0.5)));
+        assert!(!shrunk.contains(&Point::new(0.1, 0.5)));
+
+        let vanished = square.buffer(-0.6, &BufferOptions::default()).unwrap();
+        assert!(vanished.polygons.is_empty());
+    }
+
+    #[test]
+    fn test_negative_buffer_splits() {
+        // Two squares joined by a thin corridor: shrinking removes the corridor
+        let dumbbell = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 4.0),
+            Point::new(4.0, 4.0),
+            Point::new(4.0, 2.2),
+            Point::new(6.0, 2.2),
+            Point::new(6.0, 4.0),
+            Point::new(10.0, 4.0),
+            Point::new(10.0, 0.0),
+            Point::new(6.0, 0.0),
+            Point::new(6.0, 1.8),
+            Point::new(4.0, 1.8),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let shrunk = dumbbell.buffer(-0.5, &BufferOptions::default()).unwrap();
+        assert_eq!(shrunk.polygons.len(), 2);
+        assert!((shrunk.area() - 18.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_buffer_polygon_with_hole() {
+        let poly = Polygon::with_holes(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 10.0),
+                Point::new(10.0, 10.0),
+                Point::new(10.0, 0.0),
+                Point::new(0.0, 0.0),
+            ],
+            vec![vec![
+                Point::new(4.0, 4.0),
+                Point::new(6.0, 4.0),
+                Point::new(6.0, 6.0),
+                Point::new(4.0, 6.0),
+                Point::new(4.0, 4.0),
+            ]],
+        )
+        .unwrap();
+        let opts = options(JoinStyle::Mitre, CapStyle::Round);
+
+        // The hole shrinks when growing the polygon and vanishes for large distances
+        let grown = poly.buffer(0.5, &opts).unwrap();
+        assert_eq!(grown.polygons[0].inner.len(), 1);
+        assert!((grown.area() - (121.0 - 1.0)).abs() < 1e-9);
+        let filled = poly.buffer(1.5, &opts).unwrap();
+        assert!(filled.polygons[0].inner.is_empty());
+
+        let shrunk = poly.buffer(-0.5, &opts).unwrap();
+        assert_eq!(shrunk.polygons[0].inner.len(), 1);
+        assert!((shrunk.area() - (81.0 - 9.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let pt = Point::new(0.0, 0.0);
+        assert!(pt.buffer(f64::NAN, &BufferOptions::default()).is_err());
+        let opts = BufferOptions {
+            quadrant_segments: 0,
+            ..Default::default()
+        };
+        assert!(pt.buffer(1.0, &opts).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use super::ops::convex_hull;
+use super::points::Point;
+use super::polygons::{Orientation, Polygon};
+
+/// Compute the minimum-area rectangle enclosing a set of points.
+///
+/// The rectangle is found with the rotating calipers over the convex hull of the points: one of
+/// its sides lies on an edge of the hull. Returns None if the convex hull can't be computed,
+/// e.g. if the points are collinear.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, GeometricObject, Point};
+/// // A 2x1 rectangle rotated by 45 degrees
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(2.0, 2.0),
+///     Point::new(1.0, 3.0),
+///     Point::new(-1.0, 1.0),
+///     Point::new(0.5, 1.5),
+/// ];
+/// let rectangle = geomlib::min_area_rectangle(&points).unwrap();
+/// assert!(geomlib::approx(rectangle.area(), 4.0));
+/// ```
+pub fn min_area_rectangle(points: &[Point]) -> Option<Polygon> {
+    let hull = hull_vertices(points)?;
+    let best = calipers(&hull)
+        .into_iter()
+        .min_by(|a, b| a.area().total_cmp(&b.area()))?;
+    Some(best.to_polygon(&hull))
+}
+
+/// Compute the minimum-width rectangle enclosing a set of points.
+///
+/// Its height is the width of the set of points, and one of its sides lies on an edge of their
+/// convex hull. Returns None if the convex hull can't be computed, e.g. if the points are
+/// collinear.
+pub fn min_width_rectangle(points: &[Point]) -> Option<Polygon> {
+    let hull = hull_vertices(points)?;
+    let best = calipers(&hull)
+        .into_iter()
+        .min_by(|a, b| a.height.total_cmp(&b.height))?;
+    Some(best.to_polygon(&hull))
+}
+
+/// Compute the width of a set of points: the minimum distance between two parallel lines
+/// enclosing them. Collinear points have a width of 0 and an empty set has no width.
+pub fn width(points: &[Point]) -> Option<f64> {
+    if points.is_empty() {
+        return None;
+    }
+    let Some(hull) = hull_vertices(points) else {
+        return Some(0.0);
+    };
+    calipers(&hull)
+        .into_iter()
+        .map(|caliper| caliper.height)
+        .min_by(f64::total_cmp)
+}
+
+/// Compute the diameter of a set of points: the largest distance between two of them.
+/// An empty set has no diameter.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(3.0, 0.0),
+///     Point::new(3.0, 4.0),
+///     Point::new(1.0, 1.0),
+/// ];
+/// assert!(geomlib::approx(geomlib::diameter(&points).unwrap(), 5.0));
+/// ```
+pub fn diameter(points: &[Point]) -> Option<f64> {
+    let Some(hull) = hull_vertices(points) else {
+        // Collinear points: the extreme ones are the furthest apart
+        let first = points.iter().min_by(|a, b| lex_order(a, b))?;
+        let last = points.iter().max_by(|a, b| lex_order(a, b))?;
+        return Some(first.l2_distance(last));
+    };
+
+    // The diameter is reached between antipodal vertices. The vertex shared by two successive
+    // edges is antipodal to all the vertices between the furthest ones from these edges.
+    let total = hull.len();
+    let mut best = hull
+        .iter()
+        .map(|pt| pt.l2_distance(&hull[0]))
+        .fold(0.0, f64::max);
+    let mut far = furthest_from_edge(&hull, 0, 1);
+    for i in 1..total {
+        best = best.max(hull[i].l2_distance(&hull[far]));
+        let next = furthest_from_edge(&hull, i, far);
+        while far != next {
+            far = (far + 1) % total;
+            best = best.max(hull[i].l2_distance(&hull[far]));
+        }
+    }
+    Some(best)
+}
+
+/// Lexicographic ordering of two points
+fn lex_order(a: &Point, b: &Point) -> std::cmp::Ordering {
+    let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+    ax.total_cmp(&bx).then(ay.total_cmp(&by))
+}
+
+/// Vertices of the convex hull of the points, counter-clockwise and without repeating the first
+fn hull_vertices(points: &[Point]) -> Option<Vec<Point>> {
+    let hull = convex_hull(points)?;
+    let clockwise = hull.orientation() == Orientation::Clockwise;
+    let mut vertices = hull.outer;
+    vertices.pop();
+    if clockwise {
+        vertices.reverse();
+    }
+    Some(vertices)
+}
+
+/// Rectangle enclosing a convex polygon, with a side on one of the polygon's edges
+struct Caliper {
+    /// Index of the edge, from the vertex with this index to the next one
+    edge: usize,
+    /// Extent along the edge, relative to its first vertex
+    start: f64,
+    end: f64,
+    /// Extent across the edge, towards the polygon
+    height: f64,
+}
+
+impl Caliper {
+    fn area(&self) -> f64 {
+        (self.end - self.start) * self.height
+    }
+
+    /// Rectangle as a clockwise polygon, like the convex hull
+    fn to_polygon(&self, hull: &[Point]) -> Polygon {
+        let (ox, oy) = hull[self.edge].coords();
+        let ((ux, uy), (nx, ny)) = edge_frame(hull, self.edge);
+        let corner = |along: f64, across: f64| {
+            Point::new(ox + along * ux + across * nx, oy + along * uy + across * ny)
+        };
+        Polygon {
+            outer: vec![
+                corner(self.start, 0.0),
+                corner(self.start, self.height),
+                corner(self.end, self.height),
+                corner(self.end, 0.0),
+                corner(self.start, 0.0),
+            ],
+            inner: Vec::new(),
+        }
+    }
+}
+
+/// Unit vectors along an edge of a counter-clockwise polygon and across it, towards the inside
+fn edge_frame(hull: &[Point], edge: usize) -> ((f64, f64), (f64, f64)) {
+    let (a, b) = (&hull[edge], &hull[(edge + 1) % hull.len()]);
+    let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+    let len = a.l2_distance(b);
+    let (ux, uy) = ((bx - ax) / len, (by - ay) / len);
+    ((ux, uy), (-uy, ux))
+}
+
+/// Projection of a vertex of the polygon on the frame of an edge, relative to its first vertex
+fn project(hull: &[Point], edge: usize, vertex: usize) -> (f64, f64) {
+    let ((ux, uy), (nx, ny)) = edge_frame(hull, edge);
+    let ((ox, oy), (px, py)) = (hull[edge].coords(), hull[vertex].coords());
+    let (dx, dy) = (px - ox, py - oy);
+    (dx * ux + dy * uy, dx * nx + dy * ny)
+}
+
+/// Index of the vertex furthest from an edge, searching forward from `start`. The distance to
+/// the edge is unimodal along the polygon, so the search can start from the furthest vertex of
+/// the previous edge.
+fn furthest_from_edge(hull: &[Point], edge: usize, start: usize) -> usize {
+    advance(hull.len(), start, |k| project(hull, edge, k).1)
+}
+
+/// Move forward from `start` along a polygon with `total` vertices while the value increases
+fn advance(total: usize, start: usize, value: impl Fn(usize) -> f64) -> usize {
+    let mut k = start;
+    for _ in 0..total {
+        let next = (k + 1) % total;
+        if value(next) > value(k) {
+            k = next;
+        } else {
+            break;
+        }
+    }
+    k
+}
+
+/// Rotating calipers: the enclosing rectangle with a side on each edge of a counter-clockwise
+/// convex polygon. The extreme vertices along and across the edges only move forward as the
+/// edges rotate, so that all rectangles are found in linear time.
+fn calipers(hull: &[Point]) -> Vec<Caliper> {
+    let total = hull.len();
+    let (mut far, mut front, mut back) = (0, 0, 0);
+    let mut out = Vec::with_capacity(total);
+    for edge in 0..total {
+        if edge == 0 {
+            // Initial extremes by exhaustive search
+            let argmax = |value: &dyn Fn(usize) -> f64| {
+                (0..total)
+                    .max_by(|&a, &b| value(a).total_cmp(&value(b)))
+                    .unwrap()
+            };
+            far = argmax(&|k| project(hull, 0, k).1);
+            front = argmax(&|k| project(hull, 0, k).0);
+            back = argmax(&|k| -project(hull, 0, k).0);
+        } else {
+            far = advance(total, far, |k| project(hull, edge, k).1);
+            front = advance(total, front, |k| project(hull, edge, k).0);
+            back = advance(total, back, |k| -project(hull, edge, k).0);
+        }
+        out.push(Caliper {
+            edge,
+            start: project(hull, edge, back).0,
+            end: project(hull, edge, front).0,
+            height: project(hull, edge, far).1,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::super::test_utils::random_points;
+    use super::*;
+
+    /// Exhaustive computation of the calipers, to compare with the rotating ones
+    fn brute_force(hull: &[Point]) -> Vec<(f64, f64)> {
+        (0..hull.len())
+            .map(|edge| {
+                let proj: Vec<(f64, f64)> =
+                    (0..hull.len()).map(|k| project(hull, edge, k)).collect();
+                let lo = proj.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+                let hi = proj.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+                let height = proj.iter().map(|p| p.1).fold(0.0, f64::max);
+                ((hi - lo) * height, height)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rotated_rectangle() {
+        let angle = 0.3_f64;
+        let origin = Point::new(1.0, -2.0);
+        let mut points = Vec::new();
+        for i in 0..=4 {
+            for j in 0..=2 {
+                let pt = Point::new(1.0 + i as f64, -2.0 + j as f64);
+                points.push(pt.rotate_around(&origin, angle));
+            }
+        }
+
+        let rectangle = min_area_rectangle(&points).unwrap();
+        assert!(core::approx(rectangle.area(), 8.0));
+        assert_eq!(rectangle.orientation(), Orientation::Clockwise);
+        for pt in &points {
+            assert!(rectangle.contains(pt));
+        }
+        let rectangle = min_width_rectangle(&points).unwrap();
+        assert!(core::approx(rectangle.area(), 8.0));
+
+        assert!(core::approx(width(&points).unwrap(), 2.0));
+        assert!(core::approx(diameter(&points).unwrap(), 20.0_f64.sqrt()));
+    }
+
+    #[test]
+    fn test_random_calipers() {
+        for _ in 0..20 {
+            let points = random_points(50, -3.0..5.0, -1.0..2.0);
+            let hull = hull_vertices(&points).unwrap();
+            let expected = brute_force(&hull);
+            let min_area = expected.iter().map(|e| e.0).fold(f64::INFINITY, f64::min);
+            let min_width = expected.iter().map(|e| e.1).fold(f64::INFINITY, f64::min);
+
+            let rectangle = min_area_rectangle(&points).unwrap();
+            assert!(core::approx(rectangle.area(), min_area));
+            let rectangle = min_width_rectangle(&points).unwrap();
+            assert!(rectangle.area() >= min_area - 1e-9);
+            assert!(core::approx(width(&points).unwrap(), min_width));
+            for pt in &points {
+                assert!(rectangle.contains(pt));
+            }
+
+            let max_distance = points
+                .iter()
+                .flat_map(|a| points.iter().map(|b| a.l2_distance(b)))
+                .fold(0.0, f64::max);
+            assert!(core::approx(diameter(&points).unwrap(), max_distance));
+        }
+    }
+
+    #[test]
+    fn test_degenerate_calipers() {
+        let line: Vec<Point> = (0..4)
+            .map(|i| Point::new(i as f64, 2.0 * i as f64))
+            .collect();
+        assert!(min_area_rectangle(&line).is_none());
+        assert!(min_width_rectangle(&line).is_none());
+        assert_eq!(width(&line), Some(0.0));
+        assert!(core::approx(diameter(&line).unwrap(), 45.0_f64.sqrt()));
+
+        assert!(diameter(&[]).is_none());
+        assert!(width(&[]).is_none());
+    }
+}
@@ -0,0 +1,407 @@
+use super::buffer;
+use super::core::{self, GeomResult, GeometryError};
+use super::points::{Point, circumcenter};
+use super::polygons::{Polygon, ring_centroid};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f64::consts::PI;
+
+/// A circle on the plane, given by its center and radius
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Circle, GeometricObject, Point};
+/// let disc = Circle::new(Point::new(1.0, 1.0), 2.0);
+/// assert!(disc.contains(&Point::new(2.0, 2.0)));
+///
+/// // Polygon approximation with 16 segments per quadrant
+/// let approx = disc.to_polygon(16).unwrap();
+/// assert!((approx.area() - disc.area()).abs() < 0.1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: f64,
+}
+
+impl Circle {
+    /// Instantiate a new circle
+    pub fn new(center: Point, radius: f64) -> Self {
+        Self { center, radius }
+    }
+
+    /// Area of the disc bounded by the circle
+    pub fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    /// Determine whether a point lies in the disc bounded by the circle, or approximately on
+    /// the circle itself.
+    pub fn contains(&self, pt: &Point) -> bool {
+        let distance = self.center.l2_distance(pt);
+        distance <= self.radius || core::approx(distance, self.radius)
+    }
+
+    /// Approximate the circle with a regular polygon inscribed in it, with the given number of
+    /// segments per quarter circle. The polygon's vertices are counter-clockwise.
+    pub fn to_polygon(&self, quadrant_segments: usize) -> GeomResult<Polygon> {
+        if quadrant_segments == 0 {
+            return Err(GeometryError::ParameterError(String::from(
+                "At least one segment per quadrant is required",
+            )));
+        }
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            return Err(GeometryError::ParameterError(format!(
+                "Cannot approximate a circle of radius {} with a polygon",
+                self.radius
+            )));
+        }
+        Polygon::new(buffer::circle(&self.center, self.radius, quadrant_segments))
+    }
+
+    /// Smallest circle through two points: they are diametrically opposite
+    fn diametral(a: &Point, b: &Point) -> Self {
+        let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+        let center = Point::new((ax + bx) / 2.0, (ay + by) / 2.0);
+        let radius = center.l2_distance(a).max(center.l2_distance(b));
+        Self { center, radius }
+    }
+
+    /// Smallest circle through three points on its boundary, or enclosing them if collinear
+    fn through(a: &Point, b: &Point, c: &Point) -> Self {
+        match circumcenter(a, b, c) {
+            Some(center) => {
+                let radius = [a, b, c]
+                    .iter()
+                    .map(|pt| center.l2_distance(pt))
+                    .fold(0.0, f64::max);
+                Self { center, radius }
+            }
+            None => [(a, b), (b, c), (a, c)]
+                .into_iter()
+                .map(|(p, q)| Self::diametral(p, q))
+                .max_by(|x, y| x.radius.total_cmp(&y.radius))
+                .unwrap(),
+        }
+    }
+}
+
+/// Compute the smallest circle enclosing a set of points, with Welzl's algorithm.
+///
+/// The points are processed in a shuffled order, so that the expected running time is linear.
+/// Returns None if there are no points.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(2.0, 0.0),
+///     Point::new(1.0, 0.5),
+///     Point::new(1.0, -0.5),
+/// ];
+/// let circle = geomlib::min_enclosing_circle(&points).unwrap();
+/// assert!(circle.center.is_close(&Point::new(1.0, 0.0)));
+/// assert!(geomlib::approx(circle.radius, 1.0));
+/// ```
+pub fn min_enclosing_circle(points: &[Point]) -> Option<Circle> {
+    let pts = shuffled(points);
+    let mut circle = Circle::new((*pts.first()?).clone(), 0.0);
+
+    // Iterative form of the recursion: whenever a point is outside of the circle of the
+    // previous ones, it is on the boundary of the circle enclosing them all.
+    for i in 1..pts.len() {
+        if circle.contains(pts[i]) {
+            continue;
+        }
+        circle = Circle::new(pts[i].clone(), 0.0);
+        for j in 0..i {
+            if circle.contains(pts[j]) {
+                continue;
+            }
+            circle = Circle::diametral(pts[i], pts[j]);
+            for k in 0..j {
+                if !circle.contains(pts[k]) {
+                    circle = Circle::through(pts[i], pts[j], pts[k]);
+                }
+            }
+        }
+    }
+    Some(circle)
+}
+
+/// Points in a pseudo-random order, deterministic for reproducible results
+fn shuffled(points: &[Point]) -> Vec<&Point> {
+    let mut out: Vec<&Point> = points.iter().collect();
+    // Fisher-Yates shuffle with a xorshift generator
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15 ^ points.len() as u64;
+    for i in (1..out.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        out.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    out
+}
+
+impl Polygon {
+    /// Compute the largest circle inscribed in the polygon, whose center is its "pole of
+    /// inaccessibility": the point inside of the polygon furthest from its boundary (including
+    /// the holes' boundaries).
+    ///
+    /// Uses the polylabel algorithm: square cells covering the polygon are recursively split,
+    /// skipping those that can't contain a better center than the best one found so far. The
+    /// radius of the result is within `tolerance` of the optimal one.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{self, Point, Polygon};
+    /// let rectangle = Polygon::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(4.0, 0.0),
+    ///     Point::new(4.0, 2.0),
+    ///     Point::new(0.0, 2.0),
+    ///     Point::new(0.0, 0.0),
+    /// ]).unwrap();
+    /// let circle = rectangle.largest_inscribed_circle(1e-6).unwrap();
+    /// assert!((circle.radius - 1.0).abs() < 1e-6);
+    /// ```
+    pub fn largest_inscribed_circle(&self, tolerance: f64) -> GeomResult<Circle> {
+        if !(tolerance > 0.0 && tolerance.is_finite()) {
+            return Err(GeometryError::ParameterError(format!(
+                "Tolerance must be positive, got {tolerance}"
+            )));
+        }
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for pt in &self.outer {
+            let (x, y) = pt.coords();
+            (min_x, min_y) = (min_x.min(x), min_y.min(y));
+            (max_x, max_y) = (max_x.max(x), max_y.max(y));
+        }
+        let size = (max_x - min_x).min(max_y - min_y);
+        let new_cell = |x: f64, y: f64, half: f64| {
+            let center = Point::new(x, y);
+            let distance = self.signed_distance(&center);
+            Cell {
+                center,
+                half,
+                distance,
+                potential: distance + half * 2.0_f64.sqrt(),
+            }
+        };
+
+        // Initial guesses: the centroid and the center of the bounding box
+        let mut best = new_cell((min_x + max_x) / 2.0, (min_y + max_y) / 2.0, 0.0);
+        if let Some(centroid) = ring_centroid(&self.outer) {
+            let (x, y) = centroid.coords();
+            let cell = new_cell(x, y, 0.0);
+            if cell.distance > best.distance {
+                best = cell;
+            }
+        }
+        if size <= 0.0 {
+            return Ok(Circle::new(best.center, best.distance.max(0.0)));
+        }
+
+        let mut queue = BinaryHeap::new();
+        let half = size / 2.0;
+        let mut x = min_x;
+        while x < max_x {
+            let mut y = min_y;
+            while y < max_y {
+                queue.push(new_cell(x + half, y + half, half));
+                y += size;
+            }
+            x += size;
+        }
+
+        while let Some(cell) = queue.pop() {
+            if cell.distance > best.distance {
+                best = cell.clone();
+            }
+            if cell.potential - best.distance <= tolerance {
+                // Cells are popped by decreasing potential: none can do better
+                break;
+            }
+            let (x, y) = cell.center.coords();
+            let quarter = cell.half / 2.0;
+            for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+                queue.push(new_cell(x + dx * quarter, y + dy * quarter, quarter));
+            }
+        }
+        Ok(Circle::new(best.center, best.distance.max(0.0)))
+    }
+
+    /// Distance from a point to the boundary of the polygon, positive inside of the polygon
+    /// and negative outside of it.
+    fn signed_distance(&self, pt: &Point) -> f64 {
+        let distance = self
+            .rings()
+            .flat_map(|ring| ring.iter().zip(&ring[1..]))
+            .map(|(start, end)| pt.distance_to_segment(start, end))
+            .fold(f64::INFINITY, f64::min);
+        if self.contains(pt) {
+            distance
+        } else {
+            -distance
+        }
+    }
+}
+
+/// Square cell searched for the pole of inaccessibility of a polygon
+#[derive(Clone)]
+struct Cell {
+    center: Point,
+    /// Half of the cell's size
+    half: f64,
+    /// Signed distance from the center to the polygon's boundary
+    distance: f64,
+    /// Upper bound of the distance to the boundary within the cell
+    potential: f64,
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    /// The binary heap pops the cell with the highest potential first
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.potential.total_cmp(&other.potential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::GeometricObject;
+    use super::*;
+    use rand::{Rng, rng};
+
+    #[test]
+    fn test_circle() {
+        let circle = Circle::new(Point::new(1.0, -1.0), 2.0);
+        assert!(circle.contains(&Point::new(3.0, -1.0)));
+        assert!(!circle.contains(&Point::new(3.0, 0.0)));
+
+        let polygon = circle.to_polygon(8).unwrap();
+        assert_eq!(polygon.outer.len(), 33);
+        assert!(polygon.area() < circle.area());
+        assert!((polygon.area() - circle.area()).abs() < 0.05 * circle.area());
+        for pt in &polygon.outer {
+            assert!(core::approx(pt.l2_distance(&circle.center), 2.0));
+        }
+
+        assert!(circle.to_polygon(0).is_err());
+        assert!(
+            Circle::new(Point::new(0.0, 0.0), 0.0)
+                .to_polygon(4)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_min_enclosing_circle() {
+        let mut random = rng();
+        for total in [1, 2, 3, 10, 200] {
+            let points: Vec<Point> = (0..total)
+                .map(|_| Point::new(random.random_range(-5.0..5.0), random.random()))
+                .collect();
+            let circle = min_enclosing_circle(&points).unwrap();
+            assert!(points.iter().all(|pt| circle.contains(pt)));
+
+            // At least two points on the boundary, unless it's a single point
+            let on_boundary = points
+                .iter()
+                .filter(|pt| core::approx(pt.l2_distance(&circle.center), circle.radius))
+                .count();
+            assert!(on_boundary >= total.min(2));
+        }
+
+        // Right triangle: the hypotenuse is a diameter
+        let triangle = [
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 3.0),
+        ];
+        let circle = min_enclosing_circle(&triangle).unwrap();
+        assert!(circle.center.is_close(&Point::new(2.0, 1.5)));
+        assert!(core::approx(circle.radius, 2.5));
+
+        // Collinear points
+        let line: Vec<Point> = (0..5).map(|i| Point::new(i as f64, i as f64)).collect();
+        let circle = min_enclosing_circle(&line).unwrap();
+        assert!(circle.center.is_close(&Point::new(2.0, 2.0)));
+        assert!(core::approx(circle.radius, 8.0_f64.sqrt()));
+
+        assert!(min_enclosing_circle(&[]).is_none());
+    }
+
+    #[test]
+    fn test_largest_inscribed_circle() {
+        // L shape: the largest circle fits in the corner square
+        let shape = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 4.0),
+            Point::new(4.0, 4.0),
+            Point::new(4.0, 10.0),
+            Point::new(0.0, 10.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let circle = shape.largest_inscribed_circle(1e-4).unwrap();
+        assert!(shape.contains(&circle.center));
+        // Optimal radius: the circle tangent to the outer sides and the reflex corner
+        let expected = 4.0 * (2.0 - 2.0_f64.sqrt());
+        assert!(circle.radius <= expected + 1e-9);
+        assert!(circle.radius >= expected - 1e-4);
+
+        assert!(shape.largest_inscribed_circle(0.0).is_err());
+    }
+
+    #[test]
+    fn test_inscribed_circle_holes() {
+        // Square with a hole in its center: the circle fits around it
+        let shape = Polygon::with_holes(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 10.0),
+                Point::new(0.0, 10.0),
+                Point::new(0.0, 0.0),
+            ],
+            vec![vec![
+                Point::new(4.0, 4.0),
+                Point::new(6.0, 4.0),
+                Point::new(6.0, 6.0),
+                Point::new(4.0, 6.0),
+                Point::new(4.0, 4.0),
+            ]],
+        )
+        .unwrap();
+        let circle = shape.largest_inscribed_circle(1e-6).unwrap();
+        assert!(shape.contains(&circle.center));
+        assert!(
+            !(Polygon {
+                outer: shape.inner[0].clone(),
+                inner: Vec::new()
+            })
+            .contains(&circle.center)
+        );
+
+        // Largest circle in a corner, tangent to two sides and the hole's corner
+        let expected = 4.0 * 2.0_f64.sqrt() / (1.0 + 2.0_f64.sqrt());
+        assert!((circle.radius - expected).abs() < 1e-5);
+    }
+}
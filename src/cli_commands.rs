@@ -42,6 +42,9 @@ pub fn parse_show_detail(input: String) -> GeomResult<()> {
                 "The polygon contains {} total vertices.",
                 poly.outer.len() - 1
             );
+            if !poly.inner.is_empty() {
+                println!("The polygon has {} holes.", poly.inner.len());
+            }
             println!("The polygon's area is {}", poly.area());
             println!(
                 "The polygon's vertices are oriented: {:?}",
@@ -52,11 +55,25 @@ pub fn parse_show_detail(input: String) -> GeomResult<()> {
             }
             Ok(())
         }
+        Ok(GeomWrapper::MultiPolygon(mp)) => {
+            println!("Parsed a Geometry of Type MultiPolygon!");
+            println!(
+                "The multipolygon contains {} total polygons.",
+                mp.polygons.len()
+            );
+            println!("The multipolygon's area is {}", mp.area());
+            Ok(())
+        }
     }
 }
 
-/// Parse the given input string, compute its convex hull, and optionally save the result
-pub fn compute_convex_hull(input: String, output_path: Option<&str>) -> GeomResult<()> {
+/// Parse the given input string, compute its convex hull (or its concave hull with the given
+/// ratio), and optionally save the result
+pub fn compute_convex_hull(
+    input: String,
+    concave: Option<f64>,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
     let points = match parse_wkt(input)? {
         GeomWrapper::Point(_) => {
             return Err(GeometryError::ParameterError(String::from(
@@ -69,15 +86,26 @@ pub fn compute_convex_hull(input: String, output_path: Option<&str>) -> GeomResu
             poly.outer
         }
         GeomWrapper::LineString(ls) => ls.points,
+        GeomWrapper::MultiPolygon(mp) => mp
+            .polygons
+            .into_iter()
+            .flat_map(|poly| poly.outer)
+            .collect(),
+    };
+    let (hull, kind) = match concave {
+        Some(ratio) => (Some(concave_hull(&points, ratio)?), "Concave"),
+        None => (convex_hull(&points), "Convex"),
     };
-    let hull = convex_hull(&points);
     match (hull, output_path) {
         (None, _) => Err(GeometryError::OperationError(String::from(
             "Unable to compute convex hull",
         ))),
         (Some(poly), None) => {
-            println!("Computed convex hull of the given geometry!");
-            println!("Convex hull: {}", poly);
+            println!(
+                "Computed {} hull of the given geometry!",
+                kind.to_lowercase()
+            );
+            println!("{kind} hull: {}", poly);
             Ok(())
         }
         (Some(poly), Some(ref fp)) => {
@@ -134,3 +162,230 @@ pub fn compute_clip_polygon(
 
     Ok(())
 }
+
+/// Parse the given geometry, simplify it and optionally save the result
+pub fn compute_simplify(
+    input: String,
+    method: SimplifyMethod,
+    tolerance: f64,
+    preserve_topology: bool,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
+    let (simplified, before, after) = match parse_wkt(input)? {
+        GeomWrapper::LineString(ls) => {
+            let simple = if preserve_topology {
+                ls.simplify_preserve_topology(method, tolerance)?
+            } else {
+                ls.simplify(method, tolerance)?
+            };
+            let after = simple.total_vertices();
+            (simple.wkt(), ls.total_vertices(), after)
+        }
+        GeomWrapper::Polygon(poly) => {
+            let simple = if preserve_topology {
+                poly.simplify_preserve_topology(method, tolerance)?
+            } else {
+                poly.simplify(method, tolerance)?
+            };
+            let after = simple.outer.len() - 1;
+            (simple.wkt(), poly.outer.len() - 1, after)
+        }
+        _ => {
+            return Err(GeometryError::ParameterError(String::from(
+                "Only LineStrings and Polygons can be simplified!",
+            )));
+        }
+    };
+
+    println!("Simplified geometry from {before} to {after} vertices");
+    match output_path {
+        None => println!("Simplified geometry: {simplified}"),
+        Some(fp) => {
+            let mut file = File::create(fp).map_err(wrap_io_error)?;
+            file.write_all(simplified.as_bytes())
+                .map_err(wrap_io_error)?;
+            println!("Wrote simplified geometry to {fp}");
+        }
+    }
+    Ok(())
+}
+
+/// Parse the given geometry, apply the linear part of a transformation (about its centroid or
+/// the origin) followed by a translation, and optionally save the result
+pub fn compute_transform(
+    input: String,
+    linear: AffineTransform,
+    about_centroid: bool,
+    translation: Option<(f64, f64)>,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
+    let geom = parse_wkt(input)?;
+    let mut transform = linear;
+    if about_centroid {
+        let center = geom.centroid().ok_or_else(|| {
+            GeometryError::OperationError(String::from("The geometry has no centroid"))
+        })?;
+        let (x, y) = center.coords();
+        transform = AffineTransform::translation(-x, -y)
+            .compose(&linear)
+            .translate(x, y);
+    }
+    if let Some((dx, dy)) = translation {
+        transform = transform.translate(dx, dy);
+    }
+
+    let transformed = match geom.transform(&transform) {
+        GeomWrapper::Point(pt) => pt.wkt(),
+        GeomWrapper::MultiPoint(mp) => mp.wkt(),
+        GeomWrapper::LineString(ls) => ls.wkt(),
+        GeomWrapper::Polygon(poly) => poly.wkt(),
+        GeomWrapper::MultiPolygon(mp) => mp.wkt(),
+    };
+    match output_path {
+        None => println!("Transformed geometry: {transformed}"),
+        Some(fp) => {
+            let mut file = File::create(fp).map_err(wrap_io_error)?;
+            file.write_all(transformed.as_bytes())
+                .map_err(wrap_io_error)?;
+            println!("Wrote transformed geometry to {fp}");
+        }
+    }
+    Ok(())
+}
+
+/// Parse the given geometry, convert it between coordinate systems (to its UTM zone if no
+/// target is given) and optionally save the result
+pub fn compute_projection(
+    input: String,
+    from: CoordinateSystem,
+    to: Option<CoordinateSystem>,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
+    let geom = parse_wkt(input)?;
+    let (projected, to) = match to {
+        Some(to) => (geom.project(&from, &to)?, to),
+        None => geom.project_to_utm(&from)?,
+    };
+
+    let projected = match projected {
+        GeomWrapper::Point(pt) => pt.wkt(),
+        GeomWrapper::MultiPoint(mp) => mp.wkt(),
+        GeomWrapper::LineString(ls) => ls.wkt(),
+        GeomWrapper::Polygon(poly) => poly.wkt(),
+        GeomWrapper::MultiPolygon(mp) => mp.wkt(),
+    };
+    println!("Projected geometry from {from} to {to}");
+    match output_path {
+        None => println!("Projected geometry: {projected}"),
+        Some(fp) => {
+            let mut file = File::create(fp).map_err(wrap_io_error)?;
+            file.write_all(projected.as_bytes())
+                .map_err(wrap_io_error)?;
+            println!("Wrote projected geometry to {fp}");
+        }
+    }
+    Ok(())
+}
+
+/// Parse the given points, find the `k` nearest to the query point or those within `radius`,
+/// and optionally save them
+pub fn compute_neighbors(
+    input: String,
+    query: Point,
+    k: usize,
+    radius: Option<f64>,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
+    let points = match parse_wkt(input)? {
+        GeomWrapper::MultiPoint(mp) => mp.points,
+        GeomWrapper::Point(pt) => vec![pt],
+        _ => {
+            return Err(GeometryError::ParameterError(String::from(
+                "Only Points and MultiPoints can be searched for neighbours!",
+            )));
+        }
+    };
+    let tree = KdTree::new(points);
+    let found = match radius {
+        Some(radius) => tree.within_radius(&query, radius),
+        None => tree.k_nearest(&query, k),
+    };
+
+    println!("Found {} neighbours of {query}", found.len());
+    if found.is_empty() {
+        return Ok(());
+    }
+    for (index, distance) in &found {
+        println!("{index}: {} at distance {distance}", tree.points()[*index]);
+    }
+    if let Some(fp) = output_path {
+        let neighbors = MultiPoint::new(
+            found
+                .iter()
+                .map(|(index, _)| tree.points()[*index].clone())
+                .collect(),
+        );
+        let mut file = File::create(fp).map_err(wrap_io_error)?;
+        file.write_all(neighbors.wkt().as_bytes())
+            .map_err(wrap_io_error)?;
+        println!("Wrote neighbours to {fp}");
+    }
+    Ok(())
+}
+
+/// Parse geometries given as one WKT per line, skipping blank lines. Returns them with their
+/// line numbers.
+fn parse_wkt_lines(input: &str) -> GeomResult<(Vec<usize>, Vec<GeomWrapper>)> {
+    let mut numbers = Vec::new();
+    let mut geoms = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let geom = parse_wkt(line.trim().to_string()).map_err(|err| {
+            GeometryError::ParsingError(format!("Invalid geometry on line {}: {err}", i + 1))
+        })?;
+        numbers.push(i + 1);
+        geoms.push(geom);
+    }
+    Ok((numbers, geoms))
+}
+
+/// Parse two sets of geometries, one WKT per line, join them on the predicate and print or
+/// save the pairs of line numbers
+pub fn compute_join(
+    left: String,
+    right: String,
+    predicate: JoinPredicate,
+    output_path: Option<&str>,
+) -> GeomResult<()> {
+    let (left_lines, left) = parse_wkt_lines(&left)?;
+    let (right_lines, right) = parse_wkt_lines(&right)?;
+    let pairs = spatial_join(&left, &right, predicate)?;
+
+    println!(
+        "Found {} pairs out of {} left and {} right geometries",
+        pairs.len(),
+        left.len(),
+        right.len()
+    );
+    let lines: Vec<String> = pairs
+        .iter()
+        .map(|&(i, j)| format!("{} {}", left_lines[i], right_lines[j]))
+        .collect();
+    match output_path {
+        None => {
+            for line in &lines {
+                println!("{line}");
+            }
+        }
+        Some(fp) => {
+            let mut file = File::create(fp).map_err(wrap_io_error)?;
+            for line in &lines {
+                writeln!(file, "{line}").map_err(wrap_io_error)?;
+            }
+            println!("Wrote pairs to {fp}");
+        }
+    }
+    Ok(())
+}
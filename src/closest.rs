@@ -0,0 +1,298 @@
+use super::core::{GeomResult, GeometryError};
+use super::points::{MultiPoint, Point};
+
+/// Find the two closest points of a set and their distance, in O(n log n).
+///
+/// The points are sorted lexicographically and split recursively in halves by a vertical line.
+/// The closest pair is either in one of the halves or across the line, in a strip as wide as
+/// the smallest distance found in the halves, where each point only needs to be compared with
+/// the few following it by increasing y. Returns None if there are less than 2 points.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(5.0, 1.0),
+///     Point::new(2.0, 3.0),
+///     Point::new(5.5, 1.5),
+///     Point::new(9.0, 0.0),
+/// ];
+/// let (a, b, distance) = geomlib::closest_pair(&points).unwrap();
+/// assert_eq!((a.coords(), b.coords()), ((5.0, 1.0), (5.5, 1.5)));
+/// assert!(geomlib::approx(distance, 0.5_f64.sqrt()));
+/// ```
+pub fn closest_pair(points: &[Point]) -> Option<(Point, Point, f64)> {
+    if points.len() < 2 {
+        return None;
+    }
+    let sorted = sorted_lex(points);
+    let mut closest = Closest {
+        distance: f64::INFINITY,
+        pair: (0, 1),
+    };
+    divide(&sorted, 0, sorted.len(), &mut closest);
+    let (i, j) = closest.pair;
+    Some((sorted[i].clone(), sorted[j].clone(), closest.distance))
+}
+
+/// Find all the pairs of points at most at distance `epsilon` from each other, e.g. to find
+/// near-duplicates.
+///
+/// Uses the same divide and conquer as `closest_pair`, with strips of fixed width `epsilon`.
+/// The pairs are sorted lexicographically, and the first point of each pair is
+/// lexicographically smaller than the second.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(1.0, 1.0),
+///     Point::new(0.0, 1e-6),
+///     Point::new(1.0, 1.0),
+/// ];
+/// let pairs = geomlib::pairs_within_distance(&points, 1e-3).unwrap();
+/// assert_eq!(pairs.len(), 2);
+/// assert_eq!(pairs[1].0.coords(), (1.0, 1.0));
+/// assert_eq!(pairs[1].2, 0.0);
+/// ```
+pub fn pairs_within_distance(
+    points: &[Point],
+    epsilon: f64,
+) -> GeomResult<Vec<(Point, Point, f64)>> {
+    if epsilon.is_nan() || epsilon < 0.0 {
+        return Err(GeometryError::ParameterError(format!(
+            "The distance must be non-negative, got {epsilon}"
+        )));
+    }
+    let sorted = sorted_lex(points);
+    let mut within = Within {
+        epsilon,
+        pairs: Vec::new(),
+    };
+    divide(&sorted, 0, sorted.len(), &mut within);
+    within.pairs.sort_by_key(|&(i, j, _)| (i, j));
+    Ok(within
+        .pairs
+        .into_iter()
+        .map(|(i, j, d)| (sorted[i].clone(), sorted[j].clone(), d))
+        .collect())
+}
+
+impl MultiPoint {
+    /// Find the two closest points and their distance, see `closest_pair`
+    pub fn closest_pair(&self) -> Option<(Point, Point, f64)> {
+        closest_pair(&self.points)
+    }
+
+    /// Find all the pairs of points at most at distance `epsilon` from each other, see
+    /// `pairs_within_distance`
+    pub fn pairs_within_distance(&self, epsilon: f64) -> GeomResult<Vec<(Point, Point, f64)>> {
+        pairs_within_distance(&self.points, epsilon)
+    }
+}
+
+/// Pairs of points looked for by the divide and conquer, given by their indices in the
+/// lexicographically sorted points
+trait PairSearch {
+    /// Width of the strips around the dividing lines, on each side
+    fn reach(&self) -> f64;
+
+    /// Whether points `gap` apart in y are too far from each other to be visited
+    fn out_of_reach(&self, gap: f64) -> bool;
+
+    fn visit(&mut self, i: usize, j: usize, distance: f64);
+}
+
+struct Closest {
+    distance: f64,
+    pair: (usize, usize),
+}
+
+impl PairSearch for Closest {
+    fn reach(&self) -> f64 {
+        self.distance
+    }
+
+    fn out_of_reach(&self, gap: f64) -> bool {
+        // Only strictly closer pairs matter, e.g. nothing once duplicates are found
+        gap >= self.distance
+    }
+
+    fn visit(&mut self, i: usize, j: usize, distance: f64) {
+        if distance < self.distance {
+            self.distance = distance;
+            self.pair = (i.min(j), i.max(j));
+        }
+    }
+}
+
+struct Within {
+    epsilon: f64,
+    pairs: Vec<(usize, usize, f64)>,
+}
+
+impl PairSearch for Within {
+    fn reach(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn out_of_reach(&self, gap: f64) -> bool {
+        gap > self.epsilon
+    }
+
+    fn visit(&mut self, i: usize, j: usize, distance: f64) {
+        if distance <= self.epsilon {
+            self.pairs.push((i.min(j), i.max(j), distance));
+        }
+    }
+}
+
+/// Sort the points lexicographically, in O(n log n) even with many duplicates
+fn sorted_lex(points: &[Point]) -> Vec<Point> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| {
+        let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+        ax.total_cmp(&bx).then(ay.total_cmp(&by))
+    });
+    sorted
+}
+
+/// Visit the pairs of the sorted points in `lo..hi` which may be within reach of each other,
+/// and return the indices of these points sorted by y
+fn divide(sorted: &[Point], lo: usize, hi: usize, search: &mut impl PairSearch) -> Vec<usize> {
+    let y = |i: &usize| sorted[*i].coords().1;
+    if hi - lo <= 3 {
+        for i in lo..hi {
+            for j in i + 1..hi {
+                search.visit(i, j, sorted[i].l2_distance(&sorted[j]));
+            }
+        }
+        let mut by_y: Vec<usize> = (lo..hi).collect();
+        by_y.sort_by(|a, b| y(a).total_cmp(&y(b)));
+        return by_y;
+    }
+
+    let mid = (lo + hi) / 2;
+    let left = divide(sorted, lo, mid, search);
+    let right = divide(sorted, mid, hi, search);
+
+    // Merge the halves by y
+    let mut by_y = Vec::with_capacity(hi - lo);
+    let (mut l, mut r) = (0, 0);
+    while l < left.len() || r < right.len() {
+        if r == right.len() || (l < left.len() && y(&left[l]) <= y(&right[r])) {
+            by_y.push(left[l]);
+            l += 1;
+        } else {
+            by_y.push(right[r]);
+            r += 1;
+        }
+    }
+
+    // Pairs across the dividing line, within the strip around it
+    let mid_x = sorted[mid].coords().0;
+    let strip: Vec<usize> = by_y
+        .iter()
+        .copied()
+        .filter(|&i| (sorted[i].coords().0 - mid_x).abs() <= search.reach())
+        .collect();
+    for (k, &i) in strip.iter().enumerate() {
+        for &j in &strip[k + 1..] {
+            if search.out_of_reach(y(&j) - y(&i)) {
+                break;
+            }
+            if (i < mid) != (j < mid) {
+                search.visit(i, j, sorted[i].l2_distance(&sorted[j]));
+            }
+        }
+    }
+    by_y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::random_points;
+    use super::*;
+
+    /// All the pairs of points by brute force, with their distances
+    fn all_pairs(points: &[Point]) -> Vec<(Point, Point, f64)> {
+        let sorted = sorted_lex(points);
+        let mut pairs = Vec::new();
+        for i in 0..sorted.len() {
+            for j in i + 1..sorted.len() {
+                let distance = sorted[i].l2_distance(&sorted[j]);
+                pairs.push((sorted[i].clone(), sorted[j].clone(), distance));
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn test_random_closest_pair() {
+        for total in [2, 3, 4, 7, 100, 1000] {
+            let points = random_points(total, 0.0..10.0, 0.0..1.0);
+            let expected = all_pairs(&points)
+                .iter()
+                .map(|p| p.2)
+                .fold(f64::INFINITY, f64::min);
+            let (a, b, distance) = closest_pair(&points).unwrap();
+            assert_eq!(distance, expected);
+            assert_eq!(a.l2_distance(&b), distance);
+            assert!(a.lt_lex(&b));
+        }
+    }
+
+    #[test]
+    fn test_random_pairs_within() {
+        let points = MultiPoint::new(random_points(1000, 0.0..10.0, 0.0..1.0));
+        let found = points.pairs_within_distance(0.02).unwrap();
+        let expected: Vec<(Point, Point, f64)> = all_pairs(&points.points)
+            .into_iter()
+            .filter(|p| p.2 <= 0.02)
+            .collect();
+        assert_eq!(found.len(), expected.len());
+        for (f, e) in found.iter().zip(&expected) {
+            assert_eq!(
+                (f.0.coords(), f.1.coords(), f.2),
+                (e.0.coords(), e.1.coords(), e.2)
+            );
+        }
+    }
+
+    #[test]
+    fn test_degenerate_pairs() {
+        assert!(closest_pair(&[]).is_none());
+        assert!(closest_pair(&[Point::new(1.0, 1.0)]).is_none());
+
+        // Points on a vertical line, with duplicates
+        let points: Vec<Point> = (0..20).map(|i| Point::new(3.0, (i / 2) as f64)).collect();
+        let (_, _, distance) = MultiPoint::new(points.clone()).closest_pair().unwrap();
+        assert_eq!(distance, 0.0);
+        assert_eq!(pairs_within_distance(&points, 0.0).unwrap().len(), 10);
+        assert_eq!(
+            pairs_within_distance(&points, 1.0).unwrap().len(),
+            10 + 4 * 9
+        );
+        assert!(pairs_within_distance(&points, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_duplicated_pairs() {
+        let mut points = vec![Point::new(1.0, 2.0); 100_000];
+        points.push(Point::new(5.0, 5.0));
+        let (a, b, distance) = closest_pair(&points).unwrap();
+        assert_eq!(
+            (a.coords(), b.coords(), distance),
+            ((1.0, 2.0), (1.0, 2.0), 0.0)
+        );
+
+        // Few distinct points, each repeated many times
+        let points: Vec<Point> = (0..3_000)
+            .map(|i| Point::new((i % 3) as f64, 0.0))
+            .collect();
+        let pairs = pairs_within_distance(&points, 0.5).unwrap();
+        assert_eq!(pairs.len(), 3 * 1_000 * 999 / 2);
+    }
+}
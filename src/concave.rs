@@ -0,0 +1,244 @@
+use super::core::{GeomResult, GeometryError};
+use super::delaunay::delaunay_triangulation;
+use super::points::Point;
+use super::polygons::Polygon;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Compute the concave hull of a set of points with the chi-shape algorithm.
+///
+/// Starting from the Delaunay triangulation of the points, whose boundary is their convex hull,
+/// boundary edges are removed from the longest to the shortest as long as they are longer than
+/// a threshold length, as well as the triangle behind them. A triangle is only removed if its
+/// third vertex is not already on the boundary, so that the result stays a simple polygon
+/// containing all the points.
+///
+/// The threshold is `ratio` between the shortest (for 0) and the longest (for 1) edges of the
+/// triangulation: a ratio of 1 yields the convex hull, and smaller ratios yield more and more
+/// concave hulls. Returns an error if the ratio is not in [0, 1] or if the points are all
+/// collinear.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, GeometricObject, Point};
+/// let mut points = Vec::new();
+/// for i in 0..5 {
+///     for j in 0..5 {
+///         // L-shaped cluster of points
+///         if i < 2 || j < 2 {
+///             points.push(Point::new(i as f64, j as f64 + 0.01 * i as f64));
+///         }
+///     }
+/// }
+/// let convex = geomlib::convex_hull(&points).unwrap();
+/// let concave = geomlib::concave_hull(&points, 0.1).unwrap();
+/// assert!(concave.area() < convex.area());
+/// assert!(points.iter().all(|pt| concave.contains(pt)));
+/// ```
+pub fn concave_hull(points: &[Point], ratio: f64) -> GeomResult<Polygon> {
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(GeometryError::ParameterError(format!(
+            "The concave hull ratio must be between 0 and 1, got {ratio}"
+        )));
+    }
+    let mesh = delaunay_triangulation(points);
+    if mesh.triangles().is_empty() {
+        return Err(GeometryError::OperationError(String::from(
+            "Cannot compute the concave hull of collinear points",
+        )));
+    }
+
+    let length = |(a, b): (usize, usize)| mesh.vertices()[a].l2_distance(&mesh.vertices()[b]);
+    let edge = |t: usize, i: usize| {
+        let tri = mesh.triangles()[t];
+        (tri[i], tri[(i + 1) % 3])
+    };
+    let (min_len, max_len) = mesh
+        .edges()
+        .into_iter()
+        .map(length)
+        .fold((f64::INFINITY, 0.0_f64), |(lo, hi), len| {
+            (lo.min(len), hi.max(len))
+        });
+    let threshold = ratio * max_len + (1.0 - ratio) * min_len;
+
+    let total = mesh.triangles().len();
+    let mut removed = vec![false; total];
+    let mut on_boundary = vec![false; mesh.vertices().len()];
+    let mut heap = BinaryHeap::new();
+    for t in 0..total {
+        for i in 0..3 {
+            if mesh.triangle_neighbors(t)[i].is_none() {
+                let (a, b) = edge(t, i);
+                on_boundary[a] = true;
+                on_boundary[b] = true;
+                heap.push(BoundaryEdge {
+                    length: length((a, b)),
+                    triangle: t,
+                    index: i,
+                });
+            }
+        }
+    }
+
+    // Edges that can't be removed yet never become removable later, since the boundary only
+    // grows: they are simply dropped.
+    while let Some(BoundaryEdge {
+        length: len,
+        triangle,
+        index,
+    }) = heap.pop()
+    {
+        if len <= threshold {
+            break;
+        }
+        let opposite = mesh.triangles()[triangle][(index + 2) % 3];
+        if on_boundary[opposite] {
+            continue;
+        }
+        removed[triangle] = true;
+        on_boundary[opposite] = true;
+
+        // The two other edges of the triangle are now on the boundary
+        for i in [(index + 1) % 3, (index + 2) % 3] {
+            let Some(u) = mesh.triangle_neighbors(triangle)[i] else {
+                continue;
+            };
+            let j = (0..3)
+                .find(|&j| mesh.triangle_neighbors(u)[j] == Some(triangle))
+                .unwrap();
+            heap.push(BoundaryEdge {
+                length: length(edge(u, j)),
+                triangle: u,
+                index: j,
+            });
+        }
+    }
+
+    // Walk the boundary of the remaining triangles, which is a single ring
+    let mut next = vec![None; mesh.vertices().len()];
+    let mut start = 0;
+    for t in (0..total).filter(|&t| !removed[t]) {
+        for i in 0..3 {
+            if mesh.triangle_neighbors(t)[i].is_none_or(|u| removed[u]) {
+                let (a, b) = edge(t, i);
+                next[a] = Some(b);
+                start = a;
+            }
+        }
+    }
+    let mut ring = vec![mesh.vertices()[start].clone()];
+    let mut current = start;
+    while let Some(nxt) = next[current] {
+        ring.push(mesh.vertices()[nxt].clone());
+        if nxt == start || ring.len() > total + 2 {
+            break;
+        }
+        current = nxt;
+    }
+    // Clockwise, like the convex hull
+    ring.reverse();
+    Polygon::new(ring)
+}
+
+/// Edge of the boundary of the triangulation, as the index of an edge of a triangle
+struct BoundaryEdge {
+    length: f64,
+    triangle: usize,
+    index: usize,
+}
+
+impl PartialEq for BoundaryEdge {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BoundaryEdge {}
+
+impl PartialOrd for BoundaryEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BoundaryEdge {
+    /// The binary heap pops the longest edge first
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.length
+            .total_cmp(&other.length)
+            .then_with(|| (self.triangle, self.index).cmp(&(other.triangle, other.index)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::super::ops::convex_hull;
+    use super::super::polygons::{Orientation, shoelace};
+    use super::*;
+    use rand::{Rng, rng};
+
+    /// Points on a grid in a "C" shape, with some jitter
+    fn c_shape() -> Vec<Point> {
+        let mut random = rng();
+        let mut points = Vec::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                if i < 3 || !(3..=6).contains(&j) {
+                    points.push(Point::new(
+                        i as f64 + random.random_range(-0.1..0.1),
+                        j as f64 + random.random_range(-0.1..0.1),
+                    ));
+                }
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn test_concave_hull() {
+        let points = c_shape();
+        let hull = concave_hull(&points, 0.2).unwrap();
+        assert!(points.iter().all(|pt| hull.contains(pt)));
+        assert_eq!(hull.orientation(), Orientation::Clockwise);
+
+        // The notch of the "C" is not covered
+        assert!(!hull.contains(&Point::new(7.0, 5.0)));
+        assert!(hull.area() < 0.8 * convex_hull(&points).unwrap().area());
+
+        // Simple ring: no repeated vertices
+        let ring = &hull.outer[..hull.outer.len() - 1];
+        for (i, pt) in ring.iter().enumerate() {
+            assert!(ring[i + 1..].iter().all(|other| !other.is_close(pt)));
+        }
+    }
+
+    #[test]
+    fn test_concave_hull_ratio() {
+        let points = c_shape();
+        let convex = convex_hull(&points).unwrap();
+        let hull = concave_hull(&points, 1.0).unwrap();
+        assert!(core::approx(hull.area(), convex.area()));
+
+        // Smaller ratios give smaller hulls
+        let mut previous = hull.area();
+        for ratio in [0.5, 0.2, 0.1, 0.0] {
+            let hull = concave_hull(&points, ratio).unwrap();
+            assert!(hull.area() <= previous + 1e-9);
+            assert!(shoelace(&hull.outer) > 0.0);
+            assert!(points.iter().all(|pt| hull.contains(pt)));
+            previous = hull.area();
+        }
+    }
+
+    #[test]
+    fn test_concave_hull_invalid() {
+        let points = c_shape();
+        assert!(concave_hull(&points, -0.1).is_err());
+        assert!(concave_hull(&points, 1.5).is_err());
+
+        let line: Vec<Point> = (0..5).map(|i| Point::new(i as f64, 0.0)).collect();
+        assert!(concave_hull(&line, 0.5).is_err());
+    }
+}
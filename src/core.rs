@@ -9,7 +9,7 @@ pub trait GeometricObject {
     fn wkt(&self) -> String;
 
     fn area(&self) -> f64 {
-        return 0.0;
+        0.0
     }
 }
 
@@ -24,6 +24,7 @@ macro_rules! display_for_geom {
     };
 }
 
+use std::cell::Cell;
 use std::error::Error;
 
 pub(crate) use display_for_geom;
@@ -46,8 +47,9 @@ pub fn is_close(a: f64, b: f64, rtol: f64, atol: f64) -> bool {
 
 /// Determine if two values are approximately equal to one another.
 ///
-/// Determine if two floating point values are approximately equal. This is equivalent to calling
-/// `is_close` with relative tolerance of `1e-9` and absolute tolerance of `1e-12`.
+/// Determine if two floating point values are approximately equal with the current tolerance of
+/// the thread (see `Tolerance::current`). By default, this is equivalent to calling `is_close`
+/// with relative tolerance of `1e-9` and absolute tolerance of `1e-12`.
 ///
 /// Example:
 /// ```rust
@@ -58,10 +60,108 @@ pub fn is_close(a: f64, b: f64, rtol: f64, atol: f64) -> bool {
 /// assert!(geomlib::approx(x1, x2));
 /// ```
 pub fn approx(a: f64, b: f64) -> bool {
-    is_close(a, b, RTOL, ATOL)
+    Tolerance::current().is_close(a, b)
+}
+
+/// Tolerances used to compare coordinates and decide on degenerate cases, such as whether points
+/// coincide or are in line.
+///
+/// The absolute tolerance is in the units of the coordinates: the default one suits data with
+/// magnitudes around 1, and `Tolerance::for_extent` scales it for the extent of the data at hand.
+/// A tolerance can be passed to the functions that accept one (`Point::is_close_with`,
+/// `direction_with`, `Polygon::new_with` and `Polygon::with_holes_with`), or set for the current
+/// thread so that all the operations use it.
+///
+/// Example:
+/// ```rust
+/// use geomlib::{self, Point, Tolerance};
+/// // A micrometer-sized part, in meters
+/// let a = Point::new(0.0, 0.0);
+/// let b = Point::new(1e-13, 0.0);
+/// assert!(a.is_close(&b));
+///
+/// let tolerance = Tolerance::for_extent(1e-6).unwrap();
+/// assert!(!a.is_close_with(&b, &tolerance));
+/// assert!(!tolerance.scope(|| a.is_close(&b)));
+/// assert!(a.is_close(&b));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    pub rtol: f64,
+    pub atol: f64,
+}
+
+thread_local! {
+    static CURRENT_TOLERANCE: Cell<Tolerance> = const { Cell::new(Tolerance::DEFAULT) };
+}
+
+impl Tolerance {
+    /// Default tolerances, for data with magnitudes around 1
+    pub const DEFAULT: Tolerance = Tolerance {
+        rtol: RTOL,
+        atol: ATOL,
+    };
+
+    /// Instantiate tolerances, which must be finite and non-negative
+    pub fn new(rtol: f64, atol: f64) -> GeomResult<Self> {
+        if !(rtol.is_finite() && atol.is_finite() && rtol >= 0.0 && atol >= 0.0) {
+            return Err(GeometryError::ParameterError(format!(
+                "Tolerances must be finite and non-negative, got rtol={rtol} and atol={atol}"
+            )));
+        }
+        Ok(Self { rtol, atol })
+    }
+
+    /// Tolerances for data spanning the given extent, e.g. the size of its bounding box: the
+    /// default absolute tolerance is scaled by the extent.
+    pub fn for_extent(extent: f64) -> GeomResult<Self> {
+        if !(extent.is_finite() && extent > 0.0) {
+            return Err(GeometryError::ParameterError(format!(
+                "The extent must be finite and positive, got {extent}"
+            )));
+        }
+        Self::new(RTOL, ATOL * extent)
+    }
+
+    /// Determine if two numbers are close with these tolerances
+    pub fn is_close(&self, a: f64, b: f64) -> bool {
+        is_close(a, b, self.rtol, self.atol)
+    }
+
+    /// Tolerances used by the operations on the current thread
+    pub fn current() -> Self {
+        CURRENT_TOLERANCE.with(Cell::get)
+    }
+
+    /// Set the tolerances used by the operations on the current thread, returning the previous
+    /// ones
+    pub fn set_current(self) -> Self {
+        CURRENT_TOLERANCE.with(|current| current.replace(self))
+    }
+
+    /// Run a function with these tolerances set for the current thread, then restore the
+    /// previous ones, even if the function panics.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Tolerance);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                self.0.set_current();
+            }
+        }
+
+        let _restore = Restore(self.set_current());
+        f()
+    }
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 /// Errors raised by the functions in the library
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum GeometryError {
     ParsingError(String),
@@ -83,3 +183,46 @@ impl Error for GeometryError {}
 
 /// Result type for functions in the package
 pub type GeomResult<T> = Result<T, GeometryError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tolerance_scope() {
+        assert_eq!(Tolerance::current(), Tolerance::default());
+        assert!(!approx(1e-8, 0.0));
+
+        let coarse = Tolerance::new(0.0, 1e-6).unwrap();
+        let result = coarse.scope(|| {
+            assert_eq!(Tolerance::current(), coarse);
+            // Nested scopes restore the enclosing tolerance
+            Tolerance::DEFAULT.scope(|| assert!(!approx(1e-8, 0.0)));
+            approx(1e-8, 0.0)
+        });
+        assert!(result);
+        assert_eq!(Tolerance::current(), Tolerance::DEFAULT);
+
+        // Restored after a panic too
+        let outcome = std::panic::catch_unwind(|| coarse.scope(|| panic!("Failure")));
+        assert!(outcome.is_err());
+        assert_eq!(Tolerance::current(), Tolerance::DEFAULT);
+
+        let previous = coarse.set_current();
+        assert!(approx(1e-8, 0.0));
+        assert_eq!(previous.set_current(), coarse);
+    }
+
+    #[test]
+    fn test_tolerance_parameters() {
+        assert!(Tolerance::new(-1.0, 0.0).is_err());
+        assert!(Tolerance::new(0.0, f64::NAN).is_err());
+        assert!(Tolerance::for_extent(0.0).is_err());
+
+        let tolerance = Tolerance::for_extent(1e6).unwrap();
+        assert!(tolerance.is_close(1e-7, 0.0));
+        assert!(!tolerance.is_close(1e-5, 0.0));
+        let tolerance = Tolerance::for_extent(1e-3).unwrap();
+        assert!(!tolerance.is_close(1e-14, 0.0));
+    }
+}
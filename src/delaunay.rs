@@ -0,0 +1,1451 @@
+use super::core::{GeomResult, GeometricObject, GeometryError, display_for_geom};
+use super::points::*;
+use super::polygons::{MultiPolygon, Polygon};
+use std::collections::{HashSet, VecDeque};
+
+/// A triangle mesh built by Delaunay triangulation of a set of points.
+///
+/// Triangles are stored as counter-clockwise triples of indices into the vertex array. Each
+/// triangle knows its (up to) three neighbours: neighbour `i` is the triangle across the edge
+/// going from corner `i` to corner `i + 1`, or `None` on the boundary of the mesh (the convex
+/// hull of the points). Points can be added one at a time with `insert`; the mesh is kept
+/// Delaunay after each insertion.
+///
+/// Edges can also be constrained (see `insert_constraint` and `constrained_delaunay`): they are
+/// never flipped, and the mesh is then only Delaunay with respect to the points visible across
+/// them.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{GeometricObject, Point, TriangleMesh};
+/// let mut mesh = TriangleMesh::new();
+/// mesh.insert(Point::new(0.0, 0.0));
+/// mesh.insert(Point::new(1.0, 0.0));
+/// mesh.insert(Point::new(0.0, 1.0));
+/// let center = mesh.insert(Point::new(0.4, 0.4)).unwrap();
+///
+/// assert_eq!(mesh.triangles().len(), 3);
+/// assert_eq!(mesh.vertex_neighbors(center).len(), 3);
+/// assert!(geomlib::approx(mesh.area(), 0.5));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TriangleMesh {
+    vertices: Vec<Point>,
+    triangles: Vec<[usize; 3]>,
+    neighbors: Vec<[Option<usize>; 3]>,
+    /// A triangle incident to each vertex, if any
+    incident: Vec<Option<usize>>,
+    /// Vertices inserted while all the points were collinear
+    pending: Vec<usize>,
+    /// Triangle where the last point location ended, to start the next search
+    last: usize,
+    /// Constrained edges, as (smallest, largest) vertex indices
+    constraints: HashSet<(usize, usize)>,
+    /// Whether the boundary of the mesh is fixed, in which case it can't be extended
+    bounded: bool,
+}
+
+/// Location of a point with respect to the mesh
+enum Location {
+    Vertex(usize),
+    Inside(usize),
+    /// On the edge of a triangle (triangle, edge)
+    Edge(usize, usize),
+    /// Outside the mesh, beyond a boundary edge (triangle, edge)
+    Outside(usize, usize),
+}
+
+/// Options for the refinement of a triangle mesh
+#[derive(Clone, Debug)]
+pub struct RefineOptions {
+    /// Minimum angle of the triangles, in degrees. Ruppert's algorithm is only guaranteed to
+    /// terminate for values up to about 20.7 degrees.
+    pub min_angle: f64,
+    /// Maximum area of the triangles
+    pub max_area: Option<f64>,
+    /// Maximum number of vertices that can be added to the mesh
+    pub max_points: usize,
+}
+
+impl Default for RefineOptions {
+    fn default() -> Self {
+        Self {
+            min_angle: 20.0,
+            max_area: None,
+            max_points: 100_000,
+        }
+    }
+}
+
+/// Compute the Delaunay triangulation of a set of points
+///
+/// The vertices of the mesh are the distinct input points, in order of first appearance. The
+/// mesh has no triangles if there are fewer than 3 points or if they are all collinear.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, GeometricObject, Point};
+/// let pts = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(2.0, 0.0),
+///     Point::new(2.0, 2.0),
+///     Point::new(0.0, 2.0),
+///     Point::new(1.0, 1.0),
+/// ];
+///
+/// let mesh = geomlib::delaunay_triangulation(&pts);
+/// assert_eq!(mesh.triangles().len(), 4);
+/// assert_eq!(mesh.area(), 4.0);
+/// ```
+pub fn delaunay_triangulation(points: &[Point]) -> TriangleMesh {
+    let mut mesh = TriangleMesh::new();
+    for pt in points {
+        mesh.insert(pt.clone());
+    }
+    mesh
+}
+
+/// Compute the constrained Delaunay triangulation of a polygon
+///
+/// Every edge of the polygon's rings is an edge of the mesh (split at the vertices lying on it,
+/// if any), and the triangles cover the polygon exactly, without any in its holes. Within those
+/// constraints the triangulation is Delaunay: no vertex visible from the inside of a triangle
+/// lies in its circumcircle. Use `TriangleMesh::refine` to improve the shape of the triangles.
+/// Returns an error if the polygon's edges intersect each other.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, GeometricObject, Point, Polygon};
+/// let square = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(3.0, 0.0),
+///     Point::new(3.0, 3.0),
+///     Point::new(0.0, 3.0),
+///     Point::new(0.0, 0.0),
+/// ];
+/// let hole = vec![
+///     Point::new(1.0, 1.0),
+///     Point::new(1.0, 2.0),
+///     Point::new(2.0, 2.0),
+///     Point::new(2.0, 1.0),
+///     Point::new(1.0, 1.0),
+/// ];
+/// let poly = Polygon::with_holes(square, vec![hole]).unwrap();
+///
+/// let mesh = geomlib::constrained_delaunay(&poly).unwrap();
+/// assert_eq!(mesh.triangles().len(), 8);
+/// assert!(geomlib::approx(mesh.area(), poly.area()));
+/// ```
+pub fn constrained_delaunay(polygon: &Polygon) -> GeomResult<TriangleMesh> {
+    let mut mesh = TriangleMesh::new();
+    let rings: Vec<Vec<usize>> = polygon
+        .rings()
+        .map(|ring| {
+            ring.iter()
+                .filter_map(|pt| mesh.insert(pt.clone()))
+                .collect()
+        })
+        .collect();
+    if mesh.triangles.is_empty() {
+        return Err(GeometryError::OperationError(String::from(
+            "Cannot triangulate a polygon with collinear vertices",
+        )));
+    }
+
+    for ring in &rings {
+        for edge in ring.windows(2) {
+            mesh.insert_constraint(edge[0], edge[1])?;
+        }
+    }
+    mesh.remove_exterior();
+    Ok(mesh)
+}
+
+/// Key of an edge in the set of constrained edges
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Determine whether the segments (a, b) and (c, d) cross each other at a single point which
+/// is not an endpoint of either segment
+fn segments_cross(a: &Point, b: &Point, c: &Point, d: &Point) -> bool {
+    let opposite = |t1: Turn, t2: Turn| {
+        matches!(
+            (t1, t2),
+            (Turn::Left, Turn::Right) | (Turn::Right, Turn::Left)
+        )
+    };
+    opposite(direction(a, b, c), direction(a, b, d))
+        && opposite(direction(c, d, a), direction(c, d, b))
+}
+
+/// Determine whether a point lies strictly inside the circle having (a, b) as a diameter
+fn in_diametral_circle(a: &Point, b: &Point, pt: &Point) -> bool {
+    let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+    let mid = Point::new((ax + bx) / 2.0, (ay + by) / 2.0);
+    let (dist, radius) = (pt.l2_distance(&mid), a.l2_distance(b) / 2.0);
+    dist < radius && !super::core::approx(dist, radius)
+}
+
+/// Result of the search for the edges crossed by a new constrained edge
+enum Crossing {
+    /// Edges crossed, as pairs of vertices (right, left) of the constrained edge
+    Edges(Vec<(usize, usize)>),
+    /// A vertex lying on the constrained edge
+    Vertex(usize),
+}
+
+impl TriangleMesh {
+    /// Create an empty mesh
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Vertices of the mesh
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// Triangles of the mesh, as counter-clockwise indices into the vertex array
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Neighbours of a triangle: neighbour `i` is across the edge from corner `i` to `i + 1`
+    pub fn triangle_neighbors(&self, triangle: usize) -> [Option<usize>; 3] {
+        self.neighbors[triangle]
+    }
+
+    /// Get the corners of a triangle
+    pub fn corners(&self, triangle: usize) -> [&Point; 3] {
+        self.triangles[triangle].map(|i| &self.vertices[i])
+    }
+
+    /// Get the index of the triangle containing a point, if any
+    pub fn locate(&self, pt: &Point) -> Option<usize> {
+        match self.find(pt) {
+            Some(Location::Inside(t)) | Some(Location::Edge(t, _)) => Some(t),
+            Some(Location::Vertex(v)) => self.incident[v],
+            _ => None,
+        }
+    }
+
+    /// Get the triangles around a vertex, in counter-clockwise order
+    pub fn vertex_triangles(&self, vertex: usize) -> Vec<usize> {
+        let Some(start) = self.incident.get(vertex).copied().flatten() else {
+            return Vec::new();
+        };
+
+        // Turn clockwise until the boundary (if the vertex is on it), then counter-clockwise
+        let mut first = start;
+        while let Some(n) = self.neighbors[first][self.corner(first, vertex)] {
+            if n == start {
+                break;
+            }
+            first = n;
+        }
+        let mut around = vec![first];
+        let mut current = first;
+        while let Some(n) = self.neighbors[current][(self.corner(current, vertex) + 2) % 3] {
+            if n == first {
+                break;
+            }
+            around.push(n);
+            current = n;
+        }
+        around
+    }
+
+    /// Get the vertices connected to a vertex by an edge, in counter-clockwise order
+    pub fn vertex_neighbors(&self, vertex: usize) -> Vec<usize> {
+        let around = self.vertex_triangles(vertex);
+        let mut out: Vec<usize> = around
+            .iter()
+            .map(|&t| self.triangles[t][(self.corner(t, vertex) + 1) % 3])
+            .collect();
+        if let Some(&last) = around.last() {
+            let k = self.corner(last, vertex);
+            if self.neighbors[last][(k + 2) % 3].is_none() {
+                // Vertex on the boundary: the fan around it is open
+                out.push(self.triangles[last][(k + 2) % 3]);
+            }
+        }
+        out
+    }
+
+    /// Get the edges of the mesh as pairs of vertex indices, each edge given once
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut out = Vec::new();
+        for (t, tri) in self.triangles.iter().enumerate() {
+            for i in 0..3 {
+                // Interior edges are reported by the triangle with the lowest index
+                if self.neighbors[t][i].is_none_or(|n| n > t) {
+                    out.push((tri[i], tri[(i + 1) % 3]));
+                }
+            }
+        }
+        out
+    }
+
+    /// Convert the mesh to a collection of triangular polygons
+    pub fn to_multipolygon(&self) -> MultiPolygon {
+        let polygons = (0..self.triangles.len())
+            .map(|t| {
+                let [a, b, c] = self.corners(t);
+                Polygon {
+                    outer: vec![a.clone(), b.clone(), c.clone(), a.clone()],
+                    inner: Vec::new(),
+                }
+            })
+            .collect();
+        MultiPolygon::new(polygons)
+    }
+
+    /// Insert a point into the mesh and return its vertex index.
+    ///
+    /// If the point is (approximately) equal to an existing vertex, the mesh is unchanged and
+    /// the index of that vertex is returned. Points outside of the mesh extend it, unless its
+    /// boundary is fixed (after `constrained_delaunay` or `refine`): they are then ignored and
+    /// `None` is returned.
+    pub fn insert(&mut self, pt: Point) -> Option<usize> {
+        if self.triangles.is_empty() && !self.bounded {
+            return Some(self.insert_degenerate(pt));
+        }
+        match self.find(&pt)? {
+            Location::Vertex(v) => Some(v),
+            Location::Inside(t) => {
+                let v = self.push_vertex(pt);
+                self.split_triangle(t, v);
+                Some(v)
+            }
+            Location::Edge(t, i) => {
+                let v = self.push_vertex(pt);
+                self.split_edge(t, i, v);
+                Some(v)
+            }
+            Location::Outside(_, _) if self.bounded => None,
+            Location::Outside(t, i) => {
+                let v = self.push_vertex(pt);
+                self.extend_hull(t, i, v);
+                Some(v)
+            }
+        }
+    }
+
+    /// Determine whether the edge between two vertices is constrained
+    pub fn is_constrained(&self, a: usize, b: usize) -> bool {
+        self.constraints.contains(&edge_key(a, b))
+    }
+
+    /// Insert a constrained edge between two vertices of the mesh.
+    ///
+    /// The edge is forced into the mesh by flipping the edges crossing it, and won't be flipped
+    /// by later insertions. If vertices lie on it, the edge is split at them. Returns an error
+    /// if it crosses another constrained edge or leaves the mesh.
+    pub fn insert_constraint(&mut self, a: usize, b: usize) -> GeomResult<()> {
+        for v in [a, b] {
+            if self.incident.get(v).copied().flatten().is_none() {
+                return Err(GeometryError::ParameterError(format!(
+                    "Vertex {} is not part of the mesh",
+                    v
+                )));
+            }
+        }
+
+        let mut pending = vec![(a, b)];
+        while let Some((a, b)) = pending.pop() {
+            if a == b {
+                continue;
+            }
+            match self.crossed_edges(a, b)? {
+                Crossing::Vertex(v) => pending.extend([(a, v), (v, b)]),
+                Crossing::Edges(crossed) => {
+                    self.recover_edge(a, b, crossed)?;
+                    self.constraints.insert(edge_key(a, b));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Refine the mesh with Ruppert's algorithm.
+    ///
+    /// Vertices are added at the circumcenters of the triangles with an angle smaller than
+    /// `min_angle` or an area larger than `max_area`, or at the midpoints of the constrained
+    /// edges such a circumcenter (or any vertex) would encroach upon, that is, lie in their
+    /// diametral circle. The boundary of the mesh is constrained and remains fixed. Angles
+    /// between constrained edges smaller than `min_angle` may prevent the refinement from
+    /// terminating: an error is returned once `max_points` vertices have been added.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{self, GeometricObject, Point, Polygon, RefineOptions};
+    /// let poly = Polygon::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(10.0, 0.0),
+    ///     Point::new(10.0, 1.0),
+    ///     Point::new(0.0, 1.0),
+    ///     Point::new(0.0, 0.0),
+    /// ]).unwrap();
+    ///
+    /// let mut mesh = geomlib::constrained_delaunay(&poly).unwrap();
+    /// let options = RefineOptions { max_area: Some(0.5), ..Default::default() };
+    /// mesh.refine(&options).unwrap();
+    /// assert!(mesh.triangles().len() >= 20);
+    /// assert!(geomlib::approx(mesh.area(), 10.0));
+    /// ```
+    pub fn refine(&mut self, options: &RefineOptions) -> GeomResult<()> {
+        if !(0.0..60.0).contains(&options.min_angle) {
+            return Err(GeometryError::ParameterError(String::from(
+                "Minimum angle must be between 0 and 60 degrees",
+            )));
+        }
+        if options
+            .max_area
+            .is_some_and(|area| area.is_nan() || area <= 0.0)
+        {
+            return Err(GeometryError::ParameterError(String::from(
+                "Maximum area must be positive",
+            )));
+        }
+        self.fix_boundary();
+        let min_sin = options.min_angle.to_radians().sin();
+        let inputs = self.vertices.len();
+        let limit = inputs + options.max_points;
+
+        let mut segments: Vec<(usize, usize)> = self.constraints.iter().copied().collect();
+        let mut bad: Vec<[usize; 3]> = self.triangles.clone();
+        loop {
+            if self.vertices.len() > limit {
+                return Err(GeometryError::OperationError(format!(
+                    "Refinement needs more than {} additional vertices",
+                    options.max_points
+                )));
+            }
+
+            // Split the encroached segments first
+            if let Some((a, b)) = segments.pop() {
+                if self.is_constrained(a, b)
+                    && self.is_encroached(a, b)
+                    && let Some(v) = self.split_segment(a, b, inputs)
+                {
+                    self.queue_around(v, &mut segments, &mut bad);
+                }
+                continue;
+            }
+
+            let Some(corners) = bad.pop() else {
+                break;
+            };
+            let Some(t) = self.find_triangle(corners) else {
+                continue;
+            };
+            if !self.is_bad(t, min_sin, options.max_area) {
+                continue;
+            }
+            let [a, b, c] = self.corners(t);
+            let Some(center) = circumcenter(a, b, c) else {
+                continue;
+            };
+
+            // Split the segments encroached upon by the circumcenter instead of inserting it
+            let encroached: Vec<(usize, usize)> = self
+                .constraints
+                .iter()
+                .filter(|&&(a, b)| {
+                    in_diametral_circle(&self.vertices[a], &self.vertices[b], &center)
+                })
+                .copied()
+                .collect();
+            if !encroached.is_empty() {
+                let mut split = false;
+                for (a, b) in encroached {
+                    if let Some(v) = self.split_segment(a, b, inputs) {
+                        self.queue_around(v, &mut segments, &mut bad);
+                        split = true;
+                    }
+                }
+                // The triangle is given up on if none of the segments could be split
+                if split {
+                    bad.push(corners);
+                }
+                continue;
+            }
+
+            self.last = t;
+            let total = self.vertices.len();
+            if let Some(v) = self.insert(center).filter(|&v| v >= total) {
+                self.queue_around(v, &mut segments, &mut bad);
+            }
+        }
+        Ok(())
+    }
+
+    /// Position of a vertex among the corners of a triangle
+    fn corner(&self, triangle: usize, vertex: usize) -> usize {
+        self.triangles[triangle]
+            .iter()
+            .position(|&v| v == vertex)
+            .expect("Vertex is a corner of the triangle")
+    }
+
+    /// Find the triangle with the given corners, in counter-clockwise order
+    fn find_triangle(&self, corners: [usize; 3]) -> Option<usize> {
+        self.vertex_triangles(corners[0]).into_iter().find(|&t| {
+            let k = self.corner(t, corners[0]);
+            (0..3).all(|i| self.triangles[t][(k + i) % 3] == corners[i])
+        })
+    }
+
+    /// Find the triangle containing the edge going from `a` to `b`, and the edge's index
+    fn find_edge(&self, a: usize, b: usize) -> Option<(usize, usize)> {
+        self.vertex_triangles(a).into_iter().find_map(|t| {
+            let k = self.corner(t, a);
+            (self.triangles[t][(k + 1) % 3] == b).then_some((t, k))
+        })
+    }
+
+    fn push_vertex(&mut self, pt: Point) -> usize {
+        self.vertices.push(pt);
+        self.incident.push(None);
+        self.vertices.len() - 1
+    }
+
+    /// Write a triangle (new if `index` is the number of triangles) and update the references
+    /// to it from its corners.
+    fn set_triangle(&mut self, index: usize, corners: [usize; 3], adjacent: [Option<usize>; 3]) {
+        if index == self.triangles.len() {
+            self.triangles.push(corners);
+            self.neighbors.push(adjacent);
+        } else {
+            self.triangles[index] = corners;
+            self.neighbors[index] = adjacent;
+        }
+        for v in corners {
+            self.incident[v] = Some(index);
+        }
+    }
+
+    /// Make the triangle `neighbor`, if any, point to `new` instead of `old`
+    fn replace_neighbor(&mut self, neighbor: Option<usize>, old: usize, new: usize) {
+        if let Some(n) = neighbor {
+            for adj in self.neighbors[n].iter_mut() {
+                if *adj == Some(old) {
+                    *adj = Some(new);
+                }
+            }
+        }
+    }
+
+    /// Insert a point while the mesh has no triangles yet: the first triangle is created as
+    /// soon as a point is not collinear with the previous ones.
+    fn insert_degenerate(&mut self, pt: Point) -> usize {
+        if let Some(&v) = self
+            .pending
+            .iter()
+            .find(|&&v| self.vertices[v].is_close(&pt))
+        {
+            return v;
+        }
+        let v = self.push_vertex(pt);
+        self.pending.push(v);
+        if self.pending.len() < 3 {
+            return v;
+        }
+        let (a, b) = (self.pending[0], self.pending[1]);
+        let corners = match direction(&self.vertices[a], &self.vertices[b], &self.vertices[v]) {
+            Turn::InLine => return v,
+            Turn::Left => [a, b, v],
+            Turn::Right => [b, a, v],
+        };
+        self.set_triangle(0, corners, [None; 3]);
+        let pending = std::mem::take(&mut self.pending);
+        for &other in &pending[2..pending.len() - 1] {
+            // Collinear with the first two points: on an edge or outside of the triangle
+            match self.find(&self.vertices[other].clone()) {
+                Some(Location::Edge(t, i)) => self.split_edge(t, i, other),
+                Some(Location::Outside(t, i)) => self.extend_hull(t, i, other),
+                _ => unreachable!("Collinear points are on the boundary or outside"),
+            }
+        }
+        v
+    }
+
+    /// Locate a point by walking through the mesh towards it
+    fn find(&self, pt: &Point) -> Option<Location> {
+        if self.triangles.is_empty() {
+            return None;
+        }
+        let mut current = self.last.min(self.triangles.len() - 1);
+        let mut steps = 0;
+        'walk: while steps <= self.triangles.len() {
+            steps += 1;
+            // Vary the first edge examined so that the walk cannot cycle
+            let offset = steps % 3;
+            for k in 0..3 {
+                let i = (k + offset) % 3;
+                let [a, b] = [i, (i + 1) % 3].map(|j| &self.vertices[self.triangles[current][j]]);
+                if direction(a, b, pt) == Turn::Right {
+                    match self.neighbors[current][i] {
+                        Some(n) => {
+                            current = n;
+                            continue 'walk;
+                        }
+                        // The boundary of a bounded mesh may be non-convex
+                        None if self.bounded => break 'walk,
+                        None => return Some(Location::Outside(current, i)),
+                    }
+                }
+            }
+            return Some(self.classify(current, pt));
+        }
+
+        // The walk did not converge or left the mesh: examine every triangle
+        for t in 0..self.triangles.len() {
+            let [a, b, c] = self.corners(t);
+            if direction(a, b, pt) != Turn::Right
+                && direction(b, c, pt) != Turn::Right
+                && direction(c, a, pt) != Turn::Right
+            {
+                return Some(self.classify(t, pt));
+            }
+        }
+        (0..self.triangles.len())
+            .flat_map(|t| (0..3).map(move |i| (t, i)))
+            .find(|&(t, i)| {
+                let [a, b] = [i, (i + 1) % 3].map(|j| &self.vertices[self.triangles[t][j]]);
+                self.neighbors[t][i].is_none() && direction(a, b, pt) == Turn::Right
+            })
+            .map(|(t, i)| Location::Outside(t, i))
+    }
+
+    /// Classify a point inside (or on the boundary of) a triangle
+    fn classify(&self, triangle: usize, pt: &Point) -> Location {
+        let tri = self.triangles[triangle];
+        if let Some(&v) = tri.iter().find(|&&v| self.vertices[v].is_close(pt)) {
+            return Location::Vertex(v);
+        }
+        for i in 0..3 {
+            let [a, b] = [i, (i + 1) % 3].map(|j| &self.vertices[tri[j]]);
+            if direction(a, b, pt) == Turn::InLine {
+                return Location::Edge(triangle, i);
+            }
+        }
+        Location::Inside(triangle)
+    }
+
+    /// Split a triangle in three around a new vertex inside it
+    fn split_triangle(&mut self, t: usize, v: usize) {
+        let [a, b, c] = self.triangles[t];
+        let [n_ab, n_bc, n_ca] = self.neighbors[t];
+        let (t1, t2) = (self.triangles.len(), self.triangles.len() + 1);
+
+        self.set_triangle(t, [a, b, v], [n_ab, Some(t1), Some(t2)]);
+        self.set_triangle(t1, [b, c, v], [n_bc, Some(t2), Some(t)]);
+        self.set_triangle(t2, [c, a, v], [n_ca, Some(t), Some(t1)]);
+        self.replace_neighbor(n_bc, t, t1);
+        self.replace_neighbor(n_ca, t, t2);
+
+        self.legalize(vec![(t, 0), (t1, 0), (t2, 0)]);
+    }
+
+    /// Split the edge `i` of a triangle (and the triangle across it) at a new vertex
+    fn split_edge(&mut self, t: usize, i: usize, v: usize) {
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        if self.constraints.remove(&edge_key(a, b)) {
+            self.constraints.insert(edge_key(a, v));
+            self.constraints.insert(edge_key(v, b));
+        }
+        let adj = self.neighbors[t];
+        let (other, n_bc, n_ca) = (adj[i], adj[(i + 1) % 3], adj[(i + 2) % 3]);
+
+        let t1 = self.triangles.len();
+        self.set_triangle(t, [a, v, c], [None, Some(t1), n_ca]);
+        self.set_triangle(t1, [v, b, c], [None, n_bc, Some(t)]);
+        self.replace_neighbor(n_bc, t, t1);
+        let mut to_check = vec![(t, 2), (t1, 1)];
+
+        if let Some(u) = other {
+            // Split the triangle on the other side, (b, a, d), the same way
+            let j = self.corner(u, b);
+            let d = self.triangles[u][(j + 2) % 3];
+            let (m_ad, m_db) = (
+                self.neighbors[u][(j + 1) % 3],
+                self.neighbors[u][(j + 2) % 3],
+            );
+            let u1 = self.triangles.len();
+            self.set_triangle(u, [v, a, d], [Some(t), m_ad, Some(u1)]);
+            self.set_triangle(u1, [b, v, d], [Some(t1), Some(u), m_db]);
+            self.replace_neighbor(m_db, u, u1);
+            self.neighbors[t][0] = Some(u);
+            self.neighbors[t1][0] = Some(u1);
+            to_check.extend([(u, 1), (u1, 2)]);
+        }
+        self.legalize(to_check);
+    }
+
+    /// Connect a new vertex outside of the mesh to all the boundary edges visible from it,
+    /// starting from the boundary edge `i` of triangle `t`.
+    fn extend_hull(&mut self, t: usize, i: usize, v: usize) {
+        let pt = self.vertices[v].clone();
+        let visible = |mesh: &Self, (t, i): (usize, usize)| {
+            let [a, b] = [i, (i + 1) % 3].map(|j| &mesh.vertices[mesh.triangles[t][j]]);
+            direction(a, b, &pt) == Turn::Right
+        };
+
+        // Visible boundary edges, in counter-clockwise order along the boundary
+        let mut chain = vec![(t, i)];
+        let mut edge = self.prev_boundary_edge(t, i);
+        while edge != (t, i) && visible(self, edge) {
+            chain.insert(0, edge);
+            edge = self.prev_boundary_edge(edge.0, edge.1);
+        }
+        let mut edge = self.next_boundary_edge(t, i);
+        while edge != chain[0] && visible(self, edge) {
+            chain.push(edge);
+            edge = self.next_boundary_edge(edge.0, edge.1);
+        }
+
+        // One new triangle (b, a, v) for each visible edge (a, b), linked to the next one
+        let first = self.triangles.len();
+        let mut to_check = Vec::with_capacity(chain.len());
+        for (k, &(t, i)) in chain.iter().enumerate() {
+            let new = first + k;
+            let (a, b) = (self.triangles[t][i], self.triangles[t][(i + 1) % 3]);
+            let prev = (k > 0).then(|| new - 1);
+            let next = (k + 1 < chain.len()).then_some(new + 1);
+            self.set_triangle(new, [b, a, v], [Some(t), prev, next]);
+            self.neighbors[t][i] = Some(new);
+            to_check.push((new, 0));
+        }
+        self.legalize(to_check);
+    }
+
+    /// Next boundary edge (counter-clockwise) after the boundary edge `i` of triangle `t`
+    fn next_boundary_edge(&self, t: usize, i: usize) -> (usize, usize) {
+        let pivot = self.triangles[t][(i + 1) % 3];
+        let (mut t, mut i) = (t, (i + 1) % 3);
+        while let Some(n) = self.neighbors[t][i] {
+            t = n;
+            i = self.corner(n, pivot);
+        }
+        (t, i)
+    }
+
+    /// Previous boundary edge (clockwise) before the boundary edge `i` of triangle `t`
+    fn prev_boundary_edge(&self, t: usize, i: usize) -> (usize, usize) {
+        let pivot = self.triangles[t][i];
+        let (mut t, mut i) = (t, (i + 2) % 3);
+        while let Some(n) = self.neighbors[t][i] {
+            t = n;
+            i = (self.corner(n, pivot) + 2) % 3;
+        }
+        (t, i)
+    }
+
+    /// Restore the Delaunay property by flipping edges, starting from the given edges which
+    /// are opposite to a newly inserted vertex.
+    fn legalize(&mut self, mut stack: Vec<(usize, usize)>) {
+        while let Some((t, i)) = stack.pop() {
+            self.last = t;
+            if !self.is_illegal(t, i) {
+                continue;
+            }
+            let (t, u) = self.flip(t, i);
+            stack.push((t, 1));
+            stack.push((u, 0));
+        }
+    }
+
+    /// Determine whether the edge `i` of a triangle must be flipped: it is not constrained and
+    /// the vertex across it lies in the triangle's circumcircle.
+    fn is_illegal(&self, t: usize, i: usize) -> bool {
+        let Some(u) = self.neighbors[t][i] else {
+            return false;
+        };
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        if self.is_constrained(a, b) {
+            return false;
+        }
+        let d = self.triangles[u][(self.corner(u, b) + 2) % 3];
+        let [pa, pb, pc, pd] = [a, b, c, d].map(|v| &self.vertices[v]);
+        in_circle(pa, pb, pc, pd)
+    }
+
+    /// Flip the edge `i` of triangle `t`, (a, b) with opposite vertex c, to the other diagonal
+    /// (c, d) of the quadrilateral formed with the triangle across it. The triangles become
+    /// (c, a, d) and (d, b, c), and keep their indices.
+    fn flip(&mut self, t: usize, i: usize) -> (usize, usize) {
+        let u = self.neighbors[t][i].expect("Flipped edges are interior");
+        let tri = self.triangles[t];
+        let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        let j = self.corner(u, b);
+        let d = self.triangles[u][(j + 2) % 3];
+
+        let (x_bc, x_ca) = (
+            self.neighbors[t][(i + 1) % 3],
+            self.neighbors[t][(i + 2) % 3],
+        );
+        let (y_ad, y_db) = (
+            self.neighbors[u][(j + 1) % 3],
+            self.neighbors[u][(j + 2) % 3],
+        );
+        self.set_triangle(t, [c, a, d], [x_ca, y_ad, Some(u)]);
+        self.set_triangle(u, [d, b, c], [y_db, x_bc, Some(t)]);
+        self.replace_neighbor(y_ad, u, t);
+        self.replace_neighbor(x_bc, t, u);
+        (t, u)
+    }
+
+    /// Find the edges crossed by the segment between two vertices, walking from `a` to `b`
+    fn crossed_edges(&self, a: usize, b: usize) -> GeomResult<Crossing> {
+        let (pa, pb) = (&self.vertices[a], &self.vertices[b]);
+        let ahead = |v: usize| {
+            let ((ax, ay), (bx, by)) = (pa.coords(), pb.coords());
+            let (vx, vy) = self.vertices[v].coords();
+            (vx - ax) * (bx - ax) + (vy - ay) * (by - ay) > 0.0
+        };
+
+        // Triangle around `a` through which the segment leaves it
+        let mut start = None;
+        for t in self.vertex_triangles(a) {
+            let k = self.corner(t, a);
+            let (p, q) = (
+                self.triangles[t][(k + 1) % 3],
+                self.triangles[t][(k + 2) % 3],
+            );
+            if p == b || q == b {
+                return Ok(Crossing::Edges(Vec::new()));
+            }
+            let (turn_p, turn_q) = (
+                direction(pa, pb, &self.vertices[p]),
+                direction(pa, pb, &self.vertices[q]),
+            );
+            if turn_p == Turn::InLine && ahead(p) {
+                return Ok(Crossing::Vertex(p));
+            }
+            if turn_q == Turn::InLine && ahead(q) {
+                return Ok(Crossing::Vertex(q));
+            }
+            if turn_p == Turn::Right && turn_q == Turn::Left {
+                start = Some((t, (k + 1) % 3));
+                break;
+            }
+        }
+        let outside =
+            || GeometryError::OperationError(String::from("Constrained edge leaves the mesh"));
+        let (mut t, mut i) = start.ok_or_else(outside)?;
+
+        let mut crossed = Vec::new();
+        loop {
+            let (p, q) = (self.triangles[t][i], self.triangles[t][(i + 1) % 3]);
+            if self.is_constrained(p, q) {
+                return Err(GeometryError::OperationError(String::from(
+                    "Constrained edges intersect",
+                )));
+            }
+            crossed.push((p, q));
+
+            let u = self.neighbors[t][i].ok_or_else(outside)?;
+            let j = self.corner(u, q);
+            let r = self.triangles[u][(j + 2) % 3];
+            if r == b {
+                return Ok(Crossing::Edges(crossed));
+            }
+            (t, i) = match direction(pa, pb, &self.vertices[r]) {
+                Turn::InLine => return Ok(Crossing::Vertex(r)),
+                Turn::Right => (u, (j + 2) % 3),
+                Turn::Left => (u, (j + 1) % 3),
+            };
+        }
+    }
+
+    /// Make the segment (a, b) an edge of the mesh by flipping the edges crossing it, then
+    /// restore the Delaunay property around it (Sloan's algorithm).
+    fn recover_edge(&mut self, a: usize, b: usize, crossed: Vec<(usize, usize)>) -> GeomResult<()> {
+        let (pa, pb) = (self.vertices[a].clone(), self.vertices[b].clone());
+        let mut queue = VecDeque::from(crossed);
+        let mut created = Vec::new();
+        let mut stalled = 0;
+        while let Some((p, q)) = queue.pop_front() {
+            let (t, i) = self.find_edge(p, q).expect("Crossed edges are in the mesh");
+            let u = self.neighbors[t][i].expect("Crossed edges are interior");
+            let c = self.triangles[t][(i + 2) % 3];
+            let d = self.triangles[u][(self.corner(u, q) + 2) % 3];
+            let [pp, pq, pc, pd] = [p, q, c, d].map(|v| &self.vertices[v]);
+
+            // Only flip if the quadrilateral is strictly convex
+            if !segments_cross(pp, pq, pc, pd) {
+                queue.push_back((p, q));
+                stalled += 1;
+                if stalled > queue.len() {
+                    return Err(GeometryError::OperationError(String::from(
+                        "Unable to insert constrained edge",
+                    )));
+                }
+                continue;
+            }
+            stalled = 0;
+            self.flip(t, i);
+            let [pc, pd] = [c, d].map(|v| &self.vertices[v]);
+            if ![a, b].contains(&c) && ![a, b].contains(&d) && segments_cross(&pa, &pb, pc, pd) {
+                queue.push_back((c, d));
+            } else if edge_key(c, d) != edge_key(a, b) {
+                created.push((c, d));
+            }
+        }
+
+        // Flip the new edges until they are all Delaunay
+        let mut flipped = true;
+        while flipped {
+            flipped = false;
+            for edge in created.iter_mut() {
+                let Some((t, i)) = self.find_edge(edge.0, edge.1) else {
+                    continue;
+                };
+                if self.is_illegal(t, i) {
+                    let c = self.triangles[t][(i + 2) % 3];
+                    let (_, u) = self.flip(t, i);
+                    let d = self.triangles[u][0];
+                    *edge = (c, d);
+                    flipped = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the triangles outside of the region enclosed by the constrained edges: those
+    /// reached from the outside by crossing an even number of constrained edges.
+    fn remove_exterior(&mut self) {
+        let total = self.triangles.len();
+        let mut crossings: Vec<Option<usize>> = vec![None; total];
+        let mut queue = VecDeque::new();
+        for t in 0..total {
+            for i in 0..3 {
+                if self.neighbors[t][i].is_none() {
+                    let (a, b) = (self.triangles[t][i], self.triangles[t][(i + 1) % 3]);
+                    queue.push_back((t, self.is_constrained(a, b) as usize));
+                }
+            }
+        }
+
+        // Breadth-first search, with constrained edges counting as one step (0-1 BFS)
+        while let Some((t, depth)) = queue.pop_front() {
+            if crossings[t].is_some_and(|d| d <= depth) {
+                continue;
+            }
+            crossings[t] = Some(depth);
+            for i in 0..3 {
+                let Some(n) = self.neighbors[t][i] else {
+                    continue;
+                };
+                let (a, b) = (self.triangles[t][i], self.triangles[t][(i + 1) % 3]);
+                if self.is_constrained(a, b) {
+                    queue.push_back((n, depth + 1));
+                } else {
+                    queue.push_front((n, depth));
+                }
+            }
+        }
+
+        let keep: Vec<bool> = crossings
+            .iter()
+            .map(|d| d.is_some_and(|d| d % 2 == 1))
+            .collect();
+        self.retain_triangles(&keep);
+    }
+
+    /// Keep only the given triangles. The boundary of the mesh becomes fixed.
+    fn retain_triangles(&mut self, keep: &[bool]) {
+        let mut new_index = vec![None; self.triangles.len()];
+        let mut total = 0;
+        for (t, &kept) in keep.iter().enumerate() {
+            if kept {
+                new_index[t] = Some(total);
+                total += 1;
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(total);
+        let mut neighbors = Vec::with_capacity(total);
+        for t in (0..keep.len()).filter(|&t| keep[t]) {
+            triangles.push(self.triangles[t]);
+            neighbors.push(self.neighbors[t].map(|n| n.and_then(|n| new_index[n])));
+        }
+        self.triangles = triangles;
+        self.neighbors = neighbors;
+
+        self.incident = vec![None; self.vertices.len()];
+        for (t, tri) in self.triangles.iter().enumerate() {
+            for &v in tri {
+                self.incident[v] = Some(t);
+            }
+        }
+        self.last = 0;
+        self.bounded = true;
+    }
+
+    /// Constrain the edges on the boundary of the mesh, which then can't be extended
+    fn fix_boundary(&mut self) {
+        for t in 0..self.triangles.len() {
+            for i in 0..3 {
+                if self.neighbors[t][i].is_none() {
+                    let (a, b) = (self.triangles[t][i], self.triangles[t][(i + 1) % 3]);
+                    self.constraints.insert(edge_key(a, b));
+                }
+            }
+        }
+        self.bounded = true;
+    }
+
+    /// Determine whether a constrained edge is encroached upon by the vertex across it
+    fn is_encroached(&self, a: usize, b: usize) -> bool {
+        [(a, b), (b, a)].into_iter().any(|(p, q)| {
+            self.find_edge(p, q).is_some_and(|(t, i)| {
+                let apex = &self.vertices[self.triangles[t][(i + 2) % 3]];
+                in_diametral_circle(&self.vertices[p], &self.vertices[q], apex)
+            })
+        })
+    }
+
+    /// Split a constrained edge, returning the new vertex. Vertices with an index below
+    /// `inputs` were in the mesh before refinement.
+    ///
+    /// Edges are split at their midpoint, except those with a single input vertex: they are
+    /// split at the power of two distance from it closest to the midpoint ("concentric
+    /// shells"), so that the splits of edges meeting at a small angle don't encroach upon
+    /// each other indefinitely.
+    fn split_segment(&mut self, a: usize, b: usize, inputs: usize) -> Option<usize> {
+        let (t, i) = self.find_edge(a, b).or_else(|| self.find_edge(b, a))?;
+        let (from, to) = if b < inputs { (b, a) } else { (a, b) };
+        let length = self.vertices[from].l2_distance(&self.vertices[to]);
+        let fraction = if (a < inputs) != (b < inputs) {
+            2.0_f64.powf((length / 2.0).log2().round()) / length
+        } else {
+            0.5
+        };
+        let ((fx, fy), (tx, ty)) = (self.vertices[from].coords(), self.vertices[to].coords());
+        let mid = Point::new(fx + fraction * (tx - fx), fy + fraction * (ty - fy));
+        if mid.is_close(&self.vertices[a]) || mid.is_close(&self.vertices[b]) {
+            return None;
+        }
+        let v = self.push_vertex(mid);
+        self.split_edge(t, i, v);
+        Some(v)
+    }
+
+    /// Determine whether a triangle's smallest angle has a sine below `min_sin` or its area
+    /// is above `max_area`
+    fn is_bad(&self, t: usize, min_sin: f64, max_area: Option<f64>) -> bool {
+        let [a, b, c] = self.corners(t);
+        let (ab, bc, ca) = (a.l2_distance(b), b.l2_distance(c), c.l2_distance(a));
+        let ((ax, ay), (bx, by), (cx, cy)) = (a.coords(), b.coords(), c.coords());
+        let area = ((bx - ax) * (cy - ay) - (by - ay) * (cx - ax)).abs() / 2.0;
+        if area == 0.0 {
+            return false;
+        }
+        // The smallest angle is opposite the shortest edge: sin = shortest / (2 * circumradius)
+        let circumradius = ab * bc * ca / (4.0 * area);
+        let shortest = ab.min(bc).min(ca);
+        shortest / (2.0 * circumradius) < min_sin || max_area.is_some_and(|max| area > max)
+    }
+
+    /// Queue the triangles around a new vertex and their constrained edges for refinement
+    fn queue_around(
+        &self,
+        v: usize,
+        segments: &mut Vec<(usize, usize)>,
+        bad: &mut Vec<[usize; 3]>,
+    ) {
+        for t in self.vertex_triangles(v) {
+            let tri = self.triangles[t];
+            bad.push(tri);
+            for i in 0..3 {
+                if self.is_constrained(tri[i], tri[(i + 1) % 3]) {
+                    segments.push((tri[i], tri[(i + 1) % 3]));
+                }
+            }
+        }
+    }
+}
+
+impl GeometricObject for TriangleMesh {
+    /// WKT representation of the mesh, as a multipolygon of triangles
+    fn wkt(&self) -> String {
+        self.to_multipolygon().wkt()
+    }
+
+    /// Total area of the triangles
+    fn area(&self) -> f64 {
+        self.to_multipolygon().area()
+    }
+}
+
+display_for_geom!(TriangleMesh);
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::{random_points, square};
+    use super::super::{convex_hull, core};
+    use super::*;
+
+    /// Verify the structure of the mesh and the Delaunay property
+    fn check_mesh(mesh: &TriangleMesh) {
+        for (t, tri) in mesh.triangles().iter().enumerate() {
+            let [a, b, c] = mesh.corners(t);
+            assert_eq!(direction(a, b, c), Turn::Left);
+
+            for (i, adj) in mesh.triangle_neighbors(t).iter().enumerate() {
+                let Some(n) = *adj else {
+                    continue;
+                };
+                // Neighbours share the edge, in the opposite direction
+                let other = mesh.triangles()[n];
+                let j = other.iter().position(|&v| v == tri[(i + 1) % 3]).unwrap();
+                assert_eq!(other[(j + 1) % 3], tri[i]);
+                assert_eq!(mesh.triangle_neighbors(n)[j], Some(t));
+            }
+
+            for pt in mesh.vertices() {
+                assert!(!in_circle(a, b, c, pt));
+            }
+        }
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        assert!(delaunay_triangulation(&[]).triangles().is_empty());
+
+        let line: Vec<Point> = (0..5)
+            .map(|i| Point::new(i as f64, 2.0 * i as f64))
+            .collect();
+        let mesh = delaunay_triangulation(&line);
+        assert!(mesh.triangles().is_empty());
+        assert_eq!(mesh.wkt(), "MULTIPOLYGON EMPTY");
+
+        // Duplicates are only inserted once
+        let mut pts = vec![Point::new(0.0, 0.0); 3];
+        pts.push(Point::new(1.0, 0.0));
+        pts.push(Point::new(0.0, 0.0));
+        pts.push(Point::new(0.0, 1.0));
+        let mesh = delaunay_triangulation(&pts);
+        assert_eq!(mesh.vertices().len(), 3);
+        assert_eq!(mesh.triangles().len(), 1);
+    }
+
+    #[test]
+    fn test_collinear_start() {
+        // Collinear points are all used once a triangle can be formed
+        let mut pts: Vec<Point> = (0..5).map(|i| Point::new(i as f64, 0.0)).collect();
+        pts.push(Point::new(2.0, 1.0));
+        let mesh = delaunay_triangulation(&pts);
+        assert_eq!(mesh.triangles().len(), 4);
+        assert!(core::approx(mesh.area(), 2.0));
+        check_mesh(&mesh);
+    }
+
+    #[test]
+    fn test_grid() {
+        // Cocircular points: any valid triangulation of each square
+        let mut pts = Vec::new();
+        for i in 0..10 {
+            for j in 0..10 {
+                pts.push(Point::new(i as f64, j as f64));
+            }
+        }
+        let mesh = delaunay_triangulation(&pts);
+        assert_eq!(mesh.triangles().len(), 2 * 81);
+        assert!(core::approx(mesh.area(), 81.0));
+        check_mesh(&mesh);
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        // Cocircular points far from the origin, where the plain determinants are unreliable
+        let mut pts = Vec::new();
+        for i in 0..8 {
+            for j in 0..8 {
+                pts.push(Point::new(
+                    500000.0 + 0.125 * i as f64,
+                    5000000.0 + 0.125 * j as f64,
+                ));
+            }
+        }
+        let mesh = delaunay_triangulation(&pts);
+        assert_eq!(mesh.triangles().len(), 2 * 49);
+        assert!(core::approx(mesh.area(), 49.0 / 64.0));
+        check_mesh(&mesh);
+    }
+
+    #[test]
+    fn test_random_points() {
+        let pts = random_points(500, 0.0..1.0, 0.0..1.0);
+        let mesh = delaunay_triangulation(&pts);
+        check_mesh(&mesh);
+
+        // The mesh covers the convex hull: 2n - 2 - h triangles
+        let hull = convex_hull(&pts).unwrap();
+        let hull_size = hull.outer.len() - 1;
+        assert_eq!(mesh.triangles().len(), 2 * pts.len() - 2 - hull_size);
+        assert!(core::approx(mesh.area(), hull.area()));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 2.0),
+            Point::new(1.0, 1.1),
+        ];
+        let mesh = delaunay_triangulation(&pts);
+        // Counter-clockwise around the center, from any starting vertex
+        let around = mesh.vertex_neighbors(4);
+        let start = around.iter().position(|&v| v == 0).unwrap();
+        assert_eq!(around[start..], [0, 1, 2, 3][..4 - start]);
+        assert_eq!(mesh.vertex_triangles(4).len(), 4);
+
+        // Corner on the boundary
+        let mut corner = mesh.vertex_neighbors(0);
+        corner.sort();
+        assert_eq!(corner, vec![1, 3, 4]);
+        assert_eq!(mesh.edges().len(), 8);
+
+        let t = mesh.locate(&Point::new(1.0, 0.5)).unwrap();
+        assert!(mesh.triangles()[t].contains(&4));
+        assert!(mesh.locate(&Point::new(3.0, 0.5)).is_none());
+    }
+
+    #[test]
+    fn test_incremental_insertion() {
+        let pts = random_points(200, 0.0..1.0, 0.0..1.0);
+        let mut mesh = delaunay_triangulation(&pts[..100]);
+        for pt in &pts[100..] {
+            mesh.insert(pt.clone());
+        }
+        check_mesh(&mesh);
+
+        // Point far outside of the current hull
+        let far = mesh.insert(Point::new(5.0, 5.0)).unwrap();
+        check_mesh(&mesh);
+        assert!(mesh.vertex_neighbors(far).len() >= 2);
+    }
+
+    #[test]
+    fn test_wkt() {
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 1.0),
+        ];
+        let mesh = delaunay_triangulation(&pts);
+        assert_eq!(mesh.wkt(), "MULTIPOLYGON(((0 0, 1 0, 0 1, 0 0)))");
+    }
+
+    /// Comb-shaped polygon, whose plain Delaunay triangulation crosses its edges
+    fn comb() -> Polygon {
+        let mut ring = vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)];
+        for i in (0..5).rev() {
+            let x = i as f64 * 2.0;
+            ring.push(Point::new(x + 2.0, 3.0));
+            ring.push(Point::new(x + 1.5, 3.0));
+            ring.push(Point::new(x + 1.5, 0.5));
+            ring.push(Point::new(x + 1.0, 0.5));
+        }
+        ring.push(Point::new(0.0, 3.0));
+        ring.push(Point::new(0.0, 0.0));
+        Polygon::new(ring).unwrap()
+    }
+
+    /// Verify the structure of a constrained mesh, and that it covers the polygon
+    fn check_constrained(mesh: &TriangleMesh, poly: &Polygon) {
+        for t in 0..mesh.triangles().len() {
+            let [a, b, c] = mesh.corners(t);
+            assert_eq!(direction(a, b, c), Turn::Left);
+            let (ax, ay) = a.coords();
+            let (bx, by) = b.coords();
+            let (cx, cy) = c.coords();
+            let centroid = Point::new((ax + bx + cx) / 3.0, (ay + by + cy) / 3.0);
+            assert!(poly.contains(&centroid));
+        }
+        assert!(core::is_close(mesh.area(), poly.area(), 1e-9, 1e-12));
+
+        // The constrained edges cover the polygon's rings
+        let on_rings = |pt: &Point| {
+            poly.rings().any(|ring| {
+                ring.windows(2)
+                    .any(|pair| core::approx(pt.distance_to_segment(&pair[0], &pair[1]), 0.0))
+            })
+        };
+        let mut constrained_length = 0.0;
+        for (a, b) in mesh.edges() {
+            if mesh.is_constrained(a, b) {
+                let [pa, pb] = [a, b].map(|v| &mesh.vertices()[v]);
+                assert!(on_rings(pa) && on_rings(pb));
+                constrained_length += pa.l2_distance(pb);
+            }
+        }
+        let perimeter: f64 = poly
+            .rings()
+            .flat_map(|ring| ring.windows(2))
+            .map(|pair| pair[0].l2_distance(&pair[1]))
+            .sum();
+        assert!(core::approx(constrained_length, perimeter));
+    }
+
+    #[test]
+    fn test_constrained_polygon() {
+        let poly = comb();
+        let mesh = constrained_delaunay(&poly).unwrap();
+        assert_eq!(mesh.triangles().len(), poly.outer.len() - 3);
+        check_constrained(&mesh, &poly);
+
+        // The boundary of the mesh is fixed
+        let mut mesh = mesh;
+        assert!(mesh.insert(Point::new(1.25, 2.0)).is_none());
+        assert!(mesh.insert(Point::new(20.0, 2.0)).is_none());
+        assert!(mesh.insert(Point::new(0.25, 2.0)).is_some());
+    }
+
+    #[test]
+    fn test_constrained_holes() {
+        let holes = vec![square(1.0, 1.0, 2.0), square(5.0, 5.0, 3.0)];
+        let poly = Polygon::with_holes(square(0.0, 0.0, 10.0), holes).unwrap();
+        let mesh = constrained_delaunay(&poly).unwrap();
+        check_constrained(&mesh, &poly);
+        assert!(mesh.locate(&Point::new(2.0, 2.0)).is_none());
+        assert!(mesh.locate(&Point::new(4.0, 4.0)).is_some());
+    }
+
+    #[test]
+    fn test_constraint_edges() {
+        let mut pts = random_points(300, 0.0..1.0, 0.0..1.0);
+        pts.extend([Point::new(-0.5, 0.5), Point::new(1.5, 0.5)]);
+        let mut mesh = delaunay_triangulation(&pts);
+
+        // Horizontal edge through the whole mesh
+        mesh.insert_constraint(300, 301).unwrap();
+        assert!(
+            mesh.edges()
+                .iter()
+                .any(|&e| e == (300, 301) || e == (301, 300))
+        );
+        assert!(core::approx(mesh.area(), convex_hull(&pts).unwrap().area()));
+        for (t, tri) in mesh.triangles().iter().enumerate() {
+            let [a, b, c] = mesh.corners(t);
+            assert_eq!(direction(a, b, c), Turn::Left);
+            // Only the triangles along the constraint may be non-Delaunay
+            for (i, adj) in mesh.triangle_neighbors(t).iter().enumerate() {
+                if adj.is_some() && !mesh.is_constrained(tri[i], tri[(i + 1) % 3]) {
+                    assert!(!mesh.is_illegal(t, i));
+                }
+            }
+        }
+
+        // Crossing constraint
+        pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 0.0),
+        ];
+        let mut mesh = delaunay_triangulation(&pts);
+        mesh.insert_constraint(0, 1).unwrap();
+        mesh.insert_constraint(2, 3).unwrap_err();
+        mesh.insert_constraint(0, 7).unwrap_err();
+    }
+
+    #[test]
+    fn test_constraint_through_vertices() {
+        let mut pts: Vec<Point> = (0..5).map(|i| Point::new(i as f64, 0.0)).collect();
+        pts.extend([Point::new(2.0, 1.0), Point::new(2.0, -1.0)]);
+        let mut mesh = delaunay_triangulation(&pts);
+        mesh.insert_constraint(0, 4).unwrap();
+        for i in 0..4 {
+            assert!(mesh.is_constrained(i, i + 1));
+        }
+        assert!(!mesh.is_constrained(0, 4));
+    }
+
+    #[test]
+    fn test_refine() {
+        let poly =
+            Polygon::with_holes(square(0.0, 0.0, 10.0), vec![square(3.0, 3.0, 2.0)]).unwrap();
+        let mut mesh = constrained_delaunay(&poly).unwrap();
+        let options = RefineOptions {
+            min_angle: 25.0,
+            max_area: Some(2.0),
+            ..Default::default()
+        };
+        mesh.refine(&options).unwrap();
+        check_constrained(&mesh, &poly);
+
+        let min_sin = 25.0_f64.to_radians().sin();
+        for t in 0..mesh.triangles().len() {
+            assert!(!mesh.is_bad(t, min_sin - 1e-9, Some(2.0 + 1e-9)));
+        }
+
+        // Comb with refinement of angles only
+        let poly = comb();
+        let mut mesh = constrained_delaunay(&poly).unwrap();
+        mesh.refine(&RefineOptions::default()).unwrap();
+        check_constrained(&mesh, &poly);
+        let min_sin = 20.0_f64.to_radians().sin();
+        for t in 0..mesh.triangles().len() {
+            assert!(!mesh.is_bad(t, min_sin - 1e-9, None));
+        }
+    }
+
+    #[test]
+    fn test_refine_limits() {
+        let poly = Polygon::new(square(0.0, 0.0, 10.0)).unwrap();
+        let mut mesh = constrained_delaunay(&poly).unwrap();
+        let options = RefineOptions {
+            max_area: Some(0.01),
+            max_points: 50,
+            ..Default::default()
+        };
+        assert!(mesh.refine(&options).is_err());
+
+        let options = RefineOptions {
+            min_angle: 70.0,
+            ..Default::default()
+        };
+        assert!(mesh.refine(&options).is_err());
+
+        // Refining a plain Delaunay triangulation keeps its boundary
+        let pts = random_points(50, 0.0..1.0, 0.0..1.0);
+        let mut mesh = delaunay_triangulation(&pts);
+        let area = mesh.area();
+        mesh.refine(&RefineOptions::default()).unwrap();
+        assert!(core::is_close(mesh.area(), area, 1e-9, 1e-12));
+    }
+
+    #[test]
+    fn test_refine_star() {
+        // Corners sharper than the minimum angle, whose segments end up too short to split
+        let mut outer: Vec<Point> = (0..10)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::PI / 5.0;
+                let radius = if i % 2 == 0 { 10.0 } else { 2.0 };
+                Point::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect();
+        outer.push(outer[0].clone());
+        let poly = Polygon::with_holes(outer, vec![square(-1.0, -1.0, 2.0)]).unwrap();
+        let mut mesh = constrained_delaunay(&poly).unwrap();
+        let options = RefineOptions {
+            min_angle: 20.0,
+            max_area: Some(1.0),
+            max_points: 500,
+        };
+        assert!(matches!(
+            mesh.refine(&options),
+            Err(GeometryError::OperationError(_))
+        ));
+    }
+}
@@ -0,0 +1,380 @@
+use super::core::{GeometricObject, display_for_geom};
+use super::linestring::LineString;
+use super::points::{MultiPoint, Point};
+use super::polygons::{MultiPolygon, Polygon};
+use super::segment::Segment;
+use super::serialization::GeomWrapper;
+
+/// Axis-aligned bounding box of a geometry
+///
+/// The empty envelope, of empty geometries, has infinite inverted bounds: it contains and
+/// intersects nothing, and is the neutral element of `union`.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Envelope, Point};
+/// let a = Envelope::new(0.0, 0.0, 2.0, 1.0);
+/// let b = Envelope::from_point(&Point::new(3.0, -1.0));
+/// let both = a.union(&b);
+/// assert_eq!(both, Envelope::new(0.0, -1.0, 3.0, 1.0));
+/// assert!(both.contains_point(&Point::new(2.5, 0.0)));
+/// assert!(!a.intersects(&b));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Envelope {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Envelope {
+    /// The envelope of empty geometries
+    pub const EMPTY: Envelope = Envelope {
+        min_x: f64::INFINITY,
+        min_y: f64::INFINITY,
+        max_x: f64::NEG_INFINITY,
+        max_y: f64::NEG_INFINITY,
+    };
+
+    /// Instantiate an envelope from its bounds
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    /// Envelope of a single point
+    pub fn from_point(pt: &Point) -> Self {
+        let (x, y) = pt.coords();
+        Self::new(x, y, x, y)
+    }
+
+    /// Envelope of a sequence of points, empty if there are none
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point>) -> Self {
+        points
+            .into_iter()
+            .fold(Self::EMPTY, |env, pt| env.union(&Self::from_point(pt)))
+    }
+
+    /// Return true if the envelope contains no point
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    pub fn width(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.max_x - self.min_x
+        }
+    }
+
+    pub fn height(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.max_y - self.min_y
+        }
+    }
+
+    /// Center of the envelope, None if it is empty
+    pub fn center(&self) -> Option<Point> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(Point::new(
+            (self.min_x + self.max_x) / 2.0,
+            (self.min_y + self.max_y) / 2.0,
+        ))
+    }
+
+    /// Smallest envelope containing both envelopes
+    pub fn union(&self, other: &Envelope) -> Envelope {
+        Envelope::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+
+    /// Return true if the envelopes share at least a point, including on their boundaries
+    pub fn intersects(&self, other: &Envelope) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    /// Return true if the other envelope lies within this one. An empty envelope lies within
+    /// none.
+    pub fn contains(&self, other: &Envelope) -> bool {
+        !other.is_empty()
+            && self.min_x <= other.min_x
+            && other.max_x <= self.max_x
+            && self.min_y <= other.min_y
+            && other.max_y <= self.max_y
+    }
+
+    /// Return true if the point lies in the envelope, including on its boundary
+    pub fn contains_point(&self, pt: &Point) -> bool {
+        self.contains(&Envelope::from_point(pt))
+    }
+
+    /// Distance from a point to the closest point of the envelope, 0 if it lies inside and
+    /// infinite if the envelope is empty
+    pub fn distance_to(&self, pt: &Point) -> f64 {
+        if self.is_empty() {
+            return f64::INFINITY;
+        }
+        let (x, y) = pt.coords();
+        let dx = (self.min_x - x).max(x - self.max_x).max(0.0);
+        let dy = (self.min_y - y).max(y - self.max_y).max(0.0);
+        dx.hypot(dy)
+    }
+
+    /// Envelope grown by the given distance on all sides
+    pub fn expand(&self, distance: f64) -> Envelope {
+        if self.is_empty() {
+            return *self;
+        }
+        Envelope::new(
+            self.min_x - distance,
+            self.min_y - distance,
+            self.max_x + distance,
+            self.max_y + distance,
+        )
+    }
+
+    /// Envelope as a clockwise polygon, None if it is empty or flat
+    pub fn to_polygon(&self) -> Option<Polygon> {
+        if self.width() == 0.0 || self.height() == 0.0 {
+            return None;
+        }
+        let corners = [
+            (self.min_x, self.min_y),
+            (self.min_x, self.max_y),
+            (self.max_x, self.max_y),
+            (self.max_x, self.min_y),
+            (self.min_x, self.min_y),
+        ];
+        Polygon::new(corners.iter().map(|&(x, y)| Point::new(x, y)).collect()).ok()
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl GeometricObject for Envelope {
+    /// WKT representation of the envelope, as a polygon
+    fn wkt(&self) -> String {
+        let (x0, y0, x1, y1) = (self.min_x, self.min_y, self.max_x, self.max_y);
+        if self.is_empty() {
+            return String::from("POLYGON EMPTY");
+        }
+        format!("POLYGON (({x0} {y0}, {x0} {y1}, {x1} {y1}, {x1} {y0}, {x0} {y0}))")
+    }
+
+    fn area(&self) -> f64 {
+        self.width() * self.height()
+    }
+}
+
+display_for_geom!(Envelope);
+
+/// Objects with a bounding box, which can be stored in spatial indexes
+pub trait Bounded {
+    /// Bounding box of the object
+    fn envelope(&self) -> Envelope;
+
+    /// Distance from a point to the object. Defaults to the distance to the envelope, which is a
+    /// lower bound of the actual distance.
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.envelope().distance_to(pt)
+    }
+}
+
+impl Bounded for Envelope {
+    fn envelope(&self) -> Envelope {
+        *self
+    }
+}
+
+impl Bounded for Point {
+    fn envelope(&self) -> Envelope {
+        Envelope::from_point(self)
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.l2_distance(pt)
+    }
+}
+
+impl Bounded for MultiPoint {
+    fn envelope(&self) -> Envelope {
+        Envelope::from_points(&self.points)
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.points
+            .iter()
+            .map(|other| other.l2_distance(pt))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Bounded for LineString {
+    fn envelope(&self) -> Envelope {
+        Envelope::from_points(&self.points)
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.edges()
+            .map(|(start, end)| pt.distance_to_segment(start, end))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Bounded for Segment {
+    fn envelope(&self) -> Envelope {
+        Envelope::from_points([&self.start, &self.end])
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.distance_to_point(pt)
+    }
+}
+
+impl Bounded for Polygon {
+    fn envelope(&self) -> Envelope {
+        Envelope::from_points(&self.outer)
+    }
+
+    /// Distance to the polygon's area: 0 inside, else the distance to its boundary
+    fn distance_to(&self, pt: &Point) -> f64 {
+        if self.contains(pt) {
+            return 0.0;
+        }
+        self.rings()
+            .flat_map(|ring| ring.iter().zip(ring.iter().skip(1)))
+            .map(|(start, end)| pt.distance_to_segment(start, end))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Bounded for MultiPolygon {
+    fn envelope(&self) -> Envelope {
+        self.polygons
+            .iter()
+            .fold(Envelope::EMPTY, |env, poly| env.union(&poly.envelope()))
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        self.polygons
+            .iter()
+            .map(|poly| poly.distance_to(pt))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+impl Bounded for GeomWrapper {
+    fn envelope(&self) -> Envelope {
+        match self {
+            GeomWrapper::Polygon(poly) => poly.envelope(),
+            GeomWrapper::Point(pt) => pt.envelope(),
+            GeomWrapper::MultiPoint(pts) => pts.envelope(),
+            GeomWrapper::LineString(line) => line.envelope(),
+            GeomWrapper::MultiPolygon(polys) => polys.envelope(),
+        }
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        match self {
+            GeomWrapper::Polygon(poly) => poly.distance_to(pt),
+            GeomWrapper::Point(other) => other.distance_to(pt),
+            GeomWrapper::MultiPoint(pts) => pts.distance_to(pt),
+            GeomWrapper::LineString(line) => line.distance_to(pt),
+            GeomWrapper::MultiPolygon(polys) => polys.distance_to(pt),
+        }
+    }
+}
+
+impl<T: Bounded> Bounded for &T {
+    fn envelope(&self) -> Envelope {
+        (*self).envelope()
+    }
+
+    fn distance_to(&self, pt: &Point) -> f64 {
+        (*self).distance_to(pt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        let square = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let env = square.envelope();
+        assert_eq!(env, Envelope::new(0.0, 0.0, 2.0, 2.0));
+        assert_eq!(env.area(), 4.0);
+        assert!(env.center().unwrap().is_close(&Point::new(1.0, 1.0)));
+        assert_eq!(env.to_polygon().unwrap().area(), 4.0);
+
+        let pt = Point::new(5.0, 6.0);
+        assert_eq!(env.distance_to(&pt), 5.0);
+        assert_eq!(square.distance_to(&pt), 5.0);
+        assert_eq!(square.distance_to(&Point::new(1.0, 1.5)), 0.0);
+        assert!(env.expand(5.0).contains_point(&pt));
+
+        // Touching envelopes intersect
+        assert!(env.intersects(&Envelope::new(2.0, 2.0, 3.0, 3.0)));
+        assert!(!env.intersects(&Envelope::new(2.1, 0.0, 3.0, 3.0)));
+        assert!(env.contains(&Envelope::new(0.5, 0.5, 2.0, 1.0)));
+    }
+
+    #[test]
+    fn test_empty_envelope() {
+        let empty = MultiPoint::new(Vec::new()).envelope();
+        assert!(empty.is_empty());
+        assert_eq!(empty, Envelope::EMPTY);
+        assert_eq!(empty.area(), 0.0);
+        assert!(empty.center().is_none());
+        assert!(empty.to_polygon().is_none());
+        assert_eq!(empty.wkt(), "POLYGON EMPTY");
+
+        let env = Envelope::new(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(empty.union(&env), env);
+        assert!(!empty.intersects(&env));
+        assert!(!env.contains(&empty));
+        assert!(!empty.contains_point(&Point::new(0.0, 0.0)));
+        assert_eq!(empty.distance_to(&Point::new(0.0, 0.0)), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_distances() {
+        let line = LineString::new(vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)]).unwrap();
+        assert_eq!(line.distance_to(&Point::new(2.0, 3.0)), 3.0);
+        let pts = MultiPoint::new(vec![Point::new(0.0, 0.0), Point::new(4.0, 0.0)]);
+        assert_eq!(pts.distance_to(&Point::new(4.0, 3.0)), 3.0);
+        assert_eq!(
+            GeomWrapper::LineString(line).envelope(),
+            Envelope::new(0.0, 0.0, 4.0, 0.0)
+        );
+    }
+}
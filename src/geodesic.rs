@@ -0,0 +1,517 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::LineString;
+use super::points::Point;
+use super::polygons::{MultiPolygon, Polygon};
+use super::projection::{WGS84_A, WGS84_F, check_geographic};
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// Mean radius of the earth, in meters
+const MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// Maximum number of iterations of Vincenty's formulae
+const MAX_ITERATIONS: usize = 200;
+
+/// Number of terms of the series of Karney's area integral `I4`
+const AREA_ORDER: usize = 6;
+
+/// Number of coefficients of the polynomials giving the terms of the series of `I4`
+const AREA_COEFFS: usize = AREA_ORDER * (AREA_ORDER + 1) / 2;
+
+/// Coefficients of the series of `I4` as polynomials in the third flattening: for each
+/// coefficient, the numerator from the highest degree, then the denominator (from
+/// GeographicLib, to the sixth order)
+const C4_COEFFS: [f64; 77] = [
+    97.0, 15015.0, 1088.0, 156.0, 45045.0, -224.0, -4784.0, 1573.0, 45045.0, -10656.0, 14144.0,
+    -4576.0, -858.0, 45045.0, 64.0, 624.0, -4576.0, 6864.0, -3003.0, 15015.0, 100.0, 208.0, 572.0,
+    3432.0, -12012.0, 30030.0, 45045.0, 1.0, 9009.0, -2944.0, 468.0, 135135.0, 5792.0, 1040.0,
+    -1287.0, 135135.0, 5952.0, -11648.0, 9152.0, -2574.0, 135135.0, -64.0, -624.0, 4576.0, -6864.0,
+    3003.0, 135135.0, 8.0, 10725.0, 1856.0, -936.0, 225225.0, -8448.0, 4992.0, -1144.0, 225225.0,
+    -1440.0, 4160.0, -4576.0, 1716.0, 225225.0, -136.0, 63063.0, 1024.0, -208.0, 105105.0, 3584.0,
+    -3328.0, 1144.0, 315315.0, -128.0, 135135.0, -2560.0, 832.0, 405405.0, 128.0, 99099.0,
+];
+
+impl Point {
+    /// Return the great-circle distance in meters to another point, both given as longitude
+    /// and latitude in degrees, with the haversine formula on a sphere of the earth's mean
+    /// radius. It is within about 0.5% of the distance on the ellipsoid.
+    ///
+    /// Example
+    /// ```rust
+    /// use geomlib::Point;
+    /// let paris = Point::new(2.3522, 48.8566);
+    /// let london = Point::new(-0.1276, 51.5072);
+    /// assert!((paris.haversine_distance(&london) - 343_500.0).abs() < 1000.0);
+    /// ```
+    pub fn haversine_distance(&self, other: &Point) -> f64 {
+        let ((lon1, lat1), (lon2, lat2)) = (self.coords(), other.coords());
+        let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+        let half_dphi = (phi2 - phi1) / 2.0;
+        let half_dlambda = (lon2 - lon1).to_radians() / 2.0;
+        let h = half_dphi.sin().powi(2) + phi1.cos() * phi2.cos() * half_dlambda.sin().powi(2);
+        2.0 * MEAN_RADIUS * h.sqrt().min(1.0).asin()
+    }
+
+    /// Return the geodesic distance in meters to another point on the WGS84 ellipsoid, both
+    /// given as longitude and latitude in degrees, with Vincenty's inverse formulae.
+    ///
+    /// Returns an error if the coordinates are invalid or if the formulae don't converge,
+    /// which may happen for nearly antipodal points.
+    ///
+    /// Example
+    /// ```rust
+    /// use geomlib::Point;
+    /// let start = Point::new(0.0, 0.0);
+    /// let end = Point::new(1.0, 0.0);
+    /// let distance = start.geodesic_distance(&end).unwrap();
+    /// assert!((distance - 111_319.491).abs() < 1e-3);
+    /// ```
+    pub fn geodesic_distance(&self, other: &Point) -> GeomResult<f64> {
+        check_geographic(self)?;
+        check_geographic(other)?;
+        Ok(vincenty_inverse(self, other)?.distance)
+    }
+}
+
+impl LineString {
+    /// Return the length in meters of the linestring on the WGS84 ellipsoid, with its
+    /// coordinates given as longitude and latitude in degrees. Each segment is taken to be a
+    /// geodesic.
+    pub fn geodesic_length(&self) -> GeomResult<f64> {
+        self.edges()
+            .map(|(start, end)| start.geodesic_distance(end))
+            .sum()
+    }
+}
+
+impl Polygon {
+    /// Return the area in square meters of the polygon on the WGS84 ellipsoid, with its
+    /// coordinates given as longitude and latitude in degrees. The area of the holes is
+    /// subtracted from that of the outer ring.
+    ///
+    /// The edges are geodesics, and the area between each of them and the equator is computed
+    /// with Karney's series (Algorithms for geodesics, 2013) from the azimuths given by
+    /// Vincenty's formulae. The relative error is around 1e-11. Each ring bounds the smaller of
+    /// the two regions it divides the ellipsoid into, which may contain a pole.
+    ///
+    /// Returns an error if any of the coordinates is invalid, or if the geodesic between the
+    /// ends of an edge cannot be found, as for nearly antipodal points.
+    ///
+    /// Example
+    /// ```rust
+    /// use geomlib::{Point, Polygon};
+    /// // An eighth of the earth
+    /// let octant = Polygon::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(90.0, 0.0),
+    ///     Point::new(0.0, 90.0),
+    ///     Point::new(0.0, 0.0),
+    /// ]).unwrap();
+    /// let area = octant.geodesic_area().unwrap();
+    /// assert!((area / 63_758_202_715e3 - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn geodesic_area(&self) -> GeomResult<f64> {
+        for pt in self.rings().flatten() {
+            check_geographic(pt)?;
+        }
+        let coeffs = area_series_coefficients();
+        let mut area = ring_area(&self.outer, &coeffs)?.abs();
+        for hole in &self.inner {
+            area -= ring_area(hole, &coeffs)?.abs();
+        }
+        Ok(area)
+    }
+}
+
+impl MultiPolygon {
+    /// Return the total area in square meters of the polygons on the WGS84 ellipsoid, with
+    /// their coordinates given as longitude and latitude in degrees, see
+    /// `Polygon::geodesic_area`.
+    pub fn geodesic_area(&self) -> GeomResult<f64> {
+        self.polygons.iter().map(|poly| poly.geodesic_area()).sum()
+    }
+}
+
+/// Solution of the inverse geodesic problem between two points: the length of the geodesic in
+/// meters, the sines and cosines of the reduced latitudes of the points and their difference
+/// of longitude on the auxiliary sphere
+struct Inverse {
+    distance: f64,
+    sin_u1: f64,
+    cos_u1: f64,
+    sin_u2: f64,
+    cos_u2: f64,
+    lambda: f64,
+}
+
+/// Solve the inverse geodesic problem between two valid points on the WGS84 ellipsoid with
+/// Vincenty's formulae
+fn vincenty_inverse(start: &Point, end: &Point) -> GeomResult<Inverse> {
+    let ((lon1, lat1), (lon2, lat2)) = (start.coords(), end.coords());
+    let b = WGS84_A * (1.0 - WGS84_F);
+
+    // Reduced latitudes
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+    let l = (lon2 - lon1).to_radians();
+    let solution = |distance: f64, lambda: f64| Inverse {
+        distance,
+        sin_u1,
+        cos_u1,
+        sin_u2,
+        cos_u2,
+        lambda,
+    };
+
+    let mut lambda = l;
+    for _ in 0..MAX_ITERATIONS {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        if sin_sigma == 0.0 {
+            // Coincident points
+            return Ok(solution(0.0, lambda));
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        // On the equator, the midpoint term vanishes
+        let cos_2sm = if cos2_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        };
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma * (cos_2sm + c * cos_sigma * (-1.0 + 2.0 * cos_2sm * cos_2sm)));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (WGS84_A * WGS84_A - b * b) / (b * b);
+            let big_a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sm
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sm * cos_2sm)
+                            - big_b / 6.0
+                                * cos_2sm
+                                * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                * (-3.0 + 4.0 * cos_2sm * cos_2sm)));
+            return Ok(solution(b * big_a * (sigma - delta_sigma), lambda));
+        }
+    }
+    Err(GeometryError::OperationError(format!(
+        "Geodesic distance between {start} and {end} did not converge"
+    )))
+}
+
+/// Square of the radius of the sphere with the same surface area as the WGS84 ellipsoid
+fn authalic_radius_sq() -> f64 {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let b = WGS84_A * (1.0 - WGS84_F);
+    (WGS84_A * WGS84_A + b * b * e2.sqrt().atanh() / e2.sqrt()) / 2.0
+}
+
+/// Evaluate a polynomial, given by its coefficients from the highest degree, with Horner's method
+fn polyval(coeffs: &[f64], x: f64) -> f64 {
+    coeffs.iter().fold(0.0, |acc, coeff| acc * x + coeff)
+}
+
+/// Coefficients of the series of the area integral `I4` for WGS84, as polynomials in `eps`
+/// (from the highest degree) for each of the `AREA_ORDER` terms of the series
+fn area_series_coefficients() -> [f64; AREA_COEFFS] {
+    // Third flattening
+    let n = WGS84_F / (2.0 - WGS84_F);
+    let mut coeffs = [0.0; AREA_COEFFS];
+    let (mut k, mut o) = (0, 0);
+    for l in 0..AREA_ORDER {
+        for j in (l..AREA_ORDER).rev() {
+            let m = AREA_ORDER - j - 1;
+            coeffs[k] = polyval(&C4_COEFFS[o..=o + m], n) / C4_COEFFS[o + m + 1];
+            k += 1;
+            o += m + 2;
+        }
+    }
+    coeffs
+}
+
+/// Signed area in square meters between the geodesic from `start` to `end` and the equator,
+/// following Karney (Algorithms for geodesics, 2013, section 6)
+fn edge_area(start: &Point, end: &Point, coeffs: &[f64; AREA_COEFFS]) -> GeomResult<f64> {
+    let Inverse {
+        sin_u1,
+        cos_u1,
+        sin_u2,
+        cos_u2,
+        lambda,
+        ..
+    } = vincenty_inverse(start, end)?;
+    let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+    // Azimuths at both ends
+    let (mut sin_alpha1, mut cos_alpha1) = (
+        cos_u2 * sin_lambda,
+        cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda,
+    );
+    let (mut sin_alpha2, mut cos_alpha2) = (
+        cos_u1 * sin_lambda,
+        cos_u1 * sin_u2 * cos_lambda - sin_u1 * cos_u2,
+    );
+    let norm = sin_alpha1.hypot(cos_alpha1);
+    if norm == 0.0 {
+        return Ok(0.0);
+    }
+    (sin_alpha1, cos_alpha1) = (sin_alpha1 / norm, cos_alpha1 / norm);
+    let norm = sin_alpha2.hypot(cos_alpha2);
+    (sin_alpha2, cos_alpha2) = (sin_alpha2 / norm, cos_alpha2 / norm);
+
+    // Ellipsoidal correction, from the azimuth at the equator
+    let sin_alpha0 = sin_alpha1 * cos_u1;
+    let cos_alpha0 = cos_alpha1.hypot(sin_alpha1 * sin_u1);
+    let mut area = 0.0;
+    if sin_alpha0 != 0.0 && cos_alpha0 != 0.0 {
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let k2 = cos_alpha0 * cos_alpha0 * e2 / (1.0 - e2);
+        let eps = k2 / (2.0 * (1.0 + (1.0 + k2).sqrt()) + k2);
+        let mut series = [0.0; AREA_ORDER];
+        let (mut o, mut mult) = (0, 1.0);
+        for (l, term) in series.iter_mut().enumerate() {
+            let m = AREA_ORDER - l - 1;
+            *term = mult * polyval(&coeffs[o..=o + m], eps);
+            o += m + 1;
+            mult *= eps;
+        }
+        // Arc lengths from the equator on the auxiliary sphere
+        let sigma1 = sin_u1.atan2(cos_alpha1 * cos_u1);
+        let sigma2 = sin_u2.atan2(cos_alpha2 * cos_u2);
+        let i4 = |sigma: f64| -> f64 {
+            series
+                .iter()
+                .enumerate()
+                .map(|(l, term)| term * ((2 * l + 1) as f64 * sigma).cos())
+                .sum()
+        };
+        area = WGS84_A * WGS84_A * e2 * cos_alpha0 * sin_alpha0 * (i4(sigma2) - i4(sigma1));
+    }
+
+    // Spherical part, from the difference of the azimuths, accurately for short lines
+    let cos_omega = cos_lambda;
+    let alpha12 = if cos_omega > -FRAC_1_SQRT_2 && (sin_u2 - sin_u1).abs() < 1.75 {
+        let (d_omega, d_u1, d_u2) = (1.0 + cos_omega, 1.0 + cos_u1, 1.0 + cos_u2);
+        2.0 * (sin_lambda * (sin_u1 * d_u2 + sin_u2 * d_u1))
+            .atan2(d_omega * (sin_u1 * sin_u2 + d_u1 * d_u2))
+    } else {
+        (sin_alpha2 * cos_alpha1 - cos_alpha2 * sin_alpha1)
+            .atan2(cos_alpha2 * cos_alpha1 + sin_alpha2 * sin_alpha1)
+    };
+    Ok(area + authalic_radius_sq() * alpha12)
+}
+
+/// Direction in which an edge crosses the prime meridian: 1 eastwards, -1 westwards, else 0
+fn transit(lon1: f64, lon2: f64) -> i32 {
+    let normalize = |lon: f64| if lon >= 180.0 { lon - 360.0 } else { lon };
+    let dlon = (lon2 - lon1 + 180.0).rem_euclid(360.0) - 180.0;
+    let (lon1, lon2) = (normalize(lon1), normalize(lon2));
+    if dlon > 0.0 && ((lon1 < 0.0 && lon2 >= 0.0) || (lon1 > 0.0 && lon2 == 0.0)) {
+        1
+    } else if dlon < 0.0 && lon1 >= 0.0 && lon2 < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+/// Signed area in square meters of a closed ring of longitudes and latitudes on the WGS84
+/// ellipsoid, positive if it is counter-clockwise, of the smaller of the two regions the ring
+/// delimits.
+fn ring_area(ring: &[Point], coeffs: &[f64; AREA_COEFFS]) -> GeomResult<f64> {
+    let mut area = 0.0;
+    let mut crossings = 0;
+    for (start, end) in ring.iter().zip(&ring[1..]) {
+        area += edge_area(start, end, coeffs)?;
+        crossings += transit(start.coords().0, end.coords().0);
+    }
+
+    // The areas to the equator are only defined up to the area of the whole ellipsoid, and
+    // half of it for rings around a pole
+    let total = 4.0 * PI * authalic_radius_sq();
+    area %= total;
+    if crossings % 2 != 0 {
+        area += if area < 0.0 {
+            total / 2.0
+        } else {
+            -total / 2.0
+        };
+    }
+    if area > total / 2.0 {
+        area -= total;
+    } else if area <= -total / 2.0 {
+        area += total;
+    }
+    Ok(area)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::super::projection::{CoordinateSystem, Project};
+    use super::super::test_utils::square;
+    use super::*;
+
+    /// Degrees, minutes and seconds to decimal degrees
+    fn dms(degrees: f64, minutes: f64, seconds: f64) -> f64 {
+        degrees.signum() * (degrees.abs() + minutes / 60.0 + seconds / 3600.0)
+    }
+
+    #[test]
+    fn test_haversine() {
+        let start = Point::new(0.0, 0.0);
+        let degree = 2.0 * PI * MEAN_RADIUS / 360.0;
+        assert!(core::approx(
+            start.haversine_distance(&Point::new(1.0, 0.0)),
+            degree
+        ));
+        assert!(core::approx(
+            start.haversine_distance(&Point::new(0.0, -1.0)),
+            degree
+        ));
+        assert!(core::approx(
+            start.haversine_distance(&Point::new(180.0, 0.0)),
+            180.0 * degree
+        ));
+        assert_eq!(start.haversine_distance(&start), 0.0);
+    }
+
+    #[test]
+    fn test_vincenty() {
+        // Vincenty's reference example: Flinders Peak to Buninyong
+        let flinders = Point::new(dms(144.0, 25.0, 29.5244), dms(-37.0, 57.0, 3.7203));
+        let buninyong = Point::new(dms(143.0, 55.0, 35.3839), dms(-37.0, 39.0, 10.1561));
+        let distance = flinders.geodesic_distance(&buninyong).unwrap();
+        assert!((distance - 54_972.271).abs() < 1e-3);
+
+        // Quarter meridian
+        let distance = Point::new(0.0, 0.0)
+            .geodesic_distance(&Point::new(0.0, 90.0))
+            .unwrap();
+        assert!((distance - 10_001_965.729).abs() < 1e-3);
+
+        // Symmetric, and close to the haversine distance
+        let (a, b) = (Point::new(-74.0, 40.7), Point::new(2.35, 48.85));
+        let distance = a.geodesic_distance(&b).unwrap();
+        assert!(core::approx(distance, b.geodesic_distance(&a).unwrap()));
+        assert!((distance / a.haversine_distance(&b) - 1.0).abs() < 5e-3);
+
+        assert_eq!(a.geodesic_distance(&a).unwrap(), 0.0);
+        assert!(a.geodesic_distance(&Point::new(0.0, 95.0)).is_err());
+        // Nearly antipodal points
+        assert!(
+            Point::new(0.0, 0.0)
+                .geodesic_distance(&Point::new(179.7, 0.5))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_geodesic_length() {
+        let line = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 45.0),
+            Point::new(0.0, 90.0),
+        ])
+        .unwrap();
+        assert!((line.geodesic_length().unwrap() - 10_001_965.729).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_geodesic_area() {
+        // An eighth of the earth, whatever the orientation
+        let mut octant = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(90.0, 0.0),
+            Point::new(0.0, 90.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let expected = 510_065_621.718e6 / 8.0;
+        assert!(core::is_close(
+            octant.geodesic_area().unwrap(),
+            expected,
+            1e-9,
+            0.0
+        ));
+        octant.reverse_orientation();
+        assert!(core::is_close(
+            octant.geodesic_area().unwrap(),
+            expected,
+            1e-9,
+            0.0
+        ));
+
+        // Reference values from GeographicLib
+        let australia = Polygon::new(
+            [
+                (125.0, -15.0),
+                (113.0, -22.0),
+                (117.0, -37.0),
+                (130.0, -33.0),
+                (148.0, -39.0),
+                (154.0, -27.0),
+                (144.0, -15.0),
+                (125.0, -15.0),
+            ]
+            .iter()
+            .map(|&(lon, lat)| Point::new(lon, lat))
+            .collect(),
+        )
+        .unwrap();
+        let area = australia.geodesic_area().unwrap();
+        assert!((area / 7_786_102_826_806.07 - 1.0).abs() < 1e-10);
+        let arctic = Polygon::new(vec![
+            Point::new(0.0, 80.0),
+            Point::new(120.0, 80.0),
+            Point::new(-120.0, 80.0),
+            Point::new(0.0, 80.0),
+        ])
+        .unwrap();
+        let area = arctic.geodesic_area().unwrap();
+        assert!((area / 1_634_782_820_860.72 - 1.0).abs() < 1e-10);
+
+        // Small square: close to its area projected in UTM, corrected for the scale factor
+        let poly =
+            Polygon::with_holes(square(2.9, 45.0, 0.2), vec![square(3.0, 45.1, 0.05)]).unwrap();
+        let (projected, _) = poly.project_to_utm(&CoordinateSystem::Wgs84).unwrap();
+        let planar = projected.area() / 0.9996_f64.powi(2);
+        assert!((poly.geodesic_area().unwrap() / planar - 1.0).abs() < 1e-4);
+
+        // Across the antimeridian
+        let poly = Polygon::new(vec![
+            Point::new(179.9, 10.0),
+            Point::new(-179.9, 10.0),
+            Point::new(-179.9, 10.2),
+            Point::new(179.9, 10.2),
+            Point::new(179.9, 10.0),
+        ])
+        .unwrap();
+        let shifted = Polygon::new(square(-0.1, 10.0, 0.2)).unwrap();
+        let area = poly.geodesic_area().unwrap();
+        assert!((area / 484_932_684.776 - 1.0).abs() < 1e-10);
+        assert!(core::approx(area, shifted.geodesic_area().unwrap()));
+
+        let multi = MultiPolygon::new(vec![poly, shifted]);
+        assert!(core::approx(multi.geodesic_area().unwrap(), 2.0 * area));
+
+        // Invalid coordinates
+        let invalid = Polygon::new(square(179.9, 10.0, 0.2)).unwrap();
+        assert!(invalid.geodesic_area().is_err());
+        assert!(MultiPolygon::new(vec![invalid]).geodesic_area().is_err());
+    }
+}
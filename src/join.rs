@@ -0,0 +1,438 @@
+use super::core::{GeomResult, GeometryError};
+use super::envelope::{Bounded, Envelope};
+use super::linestring::LineString;
+use super::ops::segments_intersect;
+use super::points::{MultiPoint, Point, Turn, direction};
+use super::polygons::{MultiPolygon, Polygon};
+use super::rtree::RTree;
+use super::segment::{Segment, SegmentIntersection};
+use super::serialization::GeomWrapper;
+
+/// Spatial predicates to join geometries on
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JoinPredicate {
+    /// The geometries share at least a point
+    Intersects,
+    /// Every point of the right geometry lies in the left one, boundary included
+    Contains,
+    /// Every point of the left geometry lies in the right one, boundary included
+    Within,
+    /// The geometries are at most at this distance from each other
+    WithinDistance(f64),
+}
+
+/// Decomposition of a geometry in isolated points, segments and areas, on which the spatial
+/// predicates are evaluated
+pub struct Parts<'a> {
+    points: Vec<&'a Point>,
+    segments: Vec<Segment>,
+    areas: Vec<&'a Polygon>,
+}
+
+/// Geometries which can be related to each other by spatial predicates
+///
+/// Examples
+/// ```rust
+/// use geomlib::{LineString, Point, Polygon, Relate};
+/// let square = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(0.0, 2.0),
+///     Point::new(2.0, 2.0),
+///     Point::new(2.0, 0.0),
+///     Point::new(0.0, 0.0),
+/// ])
+/// .unwrap();
+/// let line = LineString::new(vec![Point::new(1.0, 1.0), Point::new(3.0, 1.0)]).unwrap();
+/// assert!(square.intersects(&line));
+/// assert!(!square.covers(&line));
+/// assert!(Point::new(0.5, 0.5).covered_by(&square));
+/// assert_eq!(Point::new(1.0, 4.0).distance(&square), 2.0);
+/// ```
+pub trait Relate: Bounded {
+    /// Parts of the geometry
+    fn parts(&self) -> Parts<'_>;
+
+    /// Determine whether the geometries share at least a point
+    fn intersects(&self, other: &impl Relate) -> bool {
+        intersects(&self.parts(), &other.parts())
+    }
+
+    /// Determine whether every point of the other geometry lies in this one, boundary included
+    fn covers(&self, other: &impl Relate) -> bool {
+        contains(&self.parts(), &other.parts())
+    }
+
+    /// Determine whether every point of this geometry lies in the other one, boundary included
+    fn covered_by(&self, other: &impl Relate) -> bool {
+        contains(&other.parts(), &self.parts())
+    }
+
+    /// Smallest distance between the points of the geometries, 0 if they intersect
+    fn distance(&self, other: &impl Relate) -> f64 {
+        distance(&self.parts(), &other.parts())
+    }
+}
+
+/// Join two collections of geometries on a spatial predicate, and return the pairs of indices
+/// of the geometries for which it holds, sorted by left then right index.
+///
+/// The right geometries are indexed in an R-tree, so that only the pairs with close enough
+/// envelopes are tested exactly.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, JoinPredicate, Point, Polygon};
+/// let square = |x: f64| {
+///     let corners = [(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)];
+///     Polygon::new(corners.iter().map(|(dx, dy)| Point::new(x + dx, *dy)).collect()).unwrap()
+/// };
+/// let squares: Vec<Polygon> = (0..10).map(|i| square(2.0 * i as f64)).collect();
+/// let points = vec![Point::new(4.5, 0.5), Point::new(5.5, 0.5), Point::new(18.2, 0.9)];
+///
+/// let pairs = geomlib::spatial_join(&points, &squares, JoinPredicate::Within).unwrap();
+/// assert_eq!(pairs, vec![(0, 2), (2, 9)]);
+/// ```
+pub fn spatial_join<A: Relate, B: Relate>(
+    left: &[A],
+    right: &[B],
+    predicate: JoinPredicate,
+) -> GeomResult<Vec<(usize, usize)>> {
+    let reach = match predicate {
+        JoinPredicate::WithinDistance(d) if d.is_nan() || d < 0.0 => {
+            return Err(GeometryError::ParameterError(format!(
+                "The join distance must be non-negative, got {d}"
+            )));
+        }
+        JoinPredicate::WithinDistance(d) => d,
+        _ => 0.0,
+    };
+
+    let index: RTree<Indexed> = right
+        .iter()
+        .enumerate()
+        .map(|(index, geom)| Indexed {
+            envelope: geom.envelope(),
+            index,
+        })
+        .collect();
+    let right_parts: Vec<Parts> = right.iter().map(|geom| geom.parts()).collect();
+
+    let mut pairs = Vec::new();
+    for (i, geom) in left.iter().enumerate() {
+        let parts = geom.parts();
+        let mut candidates: Vec<usize> = index
+            .query_envelope(&geom.envelope().expand(reach))
+            .iter()
+            .map(|candidate| candidate.index)
+            .collect();
+        candidates.sort_unstable();
+        for j in candidates {
+            let other = &right_parts[j];
+            let holds = match predicate {
+                JoinPredicate::Intersects => intersects(&parts, other),
+                JoinPredicate::Contains => contains(&parts, other),
+                JoinPredicate::Within => contains(other, &parts),
+                JoinPredicate::WithinDistance(d) => distance(&parts, other) <= d,
+            };
+            if holds {
+                pairs.push((i, j));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Envelope of a geometry of a collection, with its index
+struct Indexed {
+    envelope: Envelope,
+    index: usize,
+}
+
+impl Bounded for Indexed {
+    fn envelope(&self) -> Envelope {
+        self.envelope
+    }
+}
+
+impl Relate for Point {
+    fn parts(&self) -> Parts<'_> {
+        Parts {
+            points: vec![self],
+            segments: Vec::new(),
+            areas: Vec::new(),
+        }
+    }
+}
+
+impl Relate for MultiPoint {
+    fn parts(&self) -> Parts<'_> {
+        Parts {
+            points: self.points.iter().collect(),
+            segments: Vec::new(),
+            areas: Vec::new(),
+        }
+    }
+}
+
+impl Relate for LineString {
+    fn parts(&self) -> Parts<'_> {
+        Parts {
+            points: Vec::new(),
+            segments: self.segments().collect(),
+            areas: Vec::new(),
+        }
+    }
+}
+
+impl Relate for Polygon {
+    fn parts(&self) -> Parts<'_> {
+        Parts {
+            points: Vec::new(),
+            segments: ring_segments(self),
+            areas: vec![self],
+        }
+    }
+}
+
+impl Relate for MultiPolygon {
+    fn parts(&self) -> Parts<'_> {
+        Parts {
+            points: Vec::new(),
+            segments: self.polygons.iter().flat_map(ring_segments).collect(),
+            areas: self.polygons.iter().collect(),
+        }
+    }
+}
+
+impl Relate for GeomWrapper {
+    fn parts(&self) -> Parts<'_> {
+        match self {
+            GeomWrapper::Polygon(poly) => poly.parts(),
+            GeomWrapper::Point(pt) => pt.parts(),
+            GeomWrapper::MultiPoint(pts) => pts.parts(),
+            GeomWrapper::LineString(line) => line.parts(),
+            GeomWrapper::MultiPolygon(polys) => polys.parts(),
+        }
+    }
+}
+
+/// Edges of all the rings of a polygon
+fn ring_segments(poly: &Polygon) -> Vec<Segment> {
+    poly.rings()
+        .flat_map(|ring| ring.iter().zip(ring.iter().skip(1)))
+        .map(|(a, b)| Segment::new(a.clone(), b.clone()))
+        .collect()
+}
+
+impl Parts<'_> {
+    fn is_empty(&self) -> bool {
+        self.points.is_empty() && self.segments.is_empty()
+    }
+
+    /// Isolated points and segment endpoints
+    fn vertices(&self) -> impl Iterator<Item = &Point> {
+        self.points
+            .iter()
+            .copied()
+            .chain(self.segments.iter().flat_map(|s| [&s.start, &s.end]))
+    }
+
+    /// Isolated points as degenerate segments, and segments
+    fn linework(&self) -> impl Iterator<Item = Segment> {
+        self.points
+            .iter()
+            .map(|&pt| Segment::new(pt.clone(), pt.clone()))
+            .chain(self.segments.iter().cloned())
+    }
+
+    /// Determine whether a point lies on the linework of the geometry
+    fn touches(&self, pt: &Point) -> bool {
+        self.linework().any(|s| on_segment(pt, &s.start, &s.end))
+    }
+
+    /// Determine whether a point lies in the geometry, boundary included
+    fn covers(&self, pt: &Point) -> bool {
+        self.touches(pt) || self.areas.iter().any(|area| area.contains(pt))
+    }
+
+    /// Determine whether a point lies in the interior of the geometry's areas
+    fn strictly_covers(&self, pt: &Point) -> bool {
+        !self.touches(pt) && self.areas.iter().any(|area| area.contains(pt))
+    }
+}
+
+/// Determine whether a point lies on a segment
+fn on_segment(pt: &Point, a: &Point, b: &Point) -> bool {
+    if pt.is_close(a) || pt.is_close(b) {
+        return true;
+    }
+    let ((x, y), (x1, y1), (x2, y2)) = (pt.coords(), a.coords(), b.coords());
+    let in_box = x1.min(x2) <= x && x <= x1.max(x2) && y1.min(y2) <= y && y <= y1.max(y2);
+    in_box && direction(a, b, pt) == Turn::InLine
+}
+
+fn intersects(a: &Parts, b: &Parts) -> bool {
+    a.linework()
+        .any(|s1| b.linework().any(|s2| segments_intersect(&s1, &s2)))
+        || a.vertices()
+            .any(|pt| b.areas.iter().any(|area| area.contains(pt)))
+        || b.vertices()
+            .any(|pt| a.areas.iter().any(|area| area.contains(pt)))
+}
+
+/// Determine whether `a` contains `b`: the points and vertices of `b` are in `a`, and its
+/// segments don't leave it. Each segment is split wherever it meets the boundary of `a`, and the
+/// parts in between must be in `a`, which catches segments leaving `a` through one of its
+/// vertices. If `b` has areas, they must not contain any vertex of `a`'s boundary either, e.g.
+/// of one of its holes.
+fn contains(a: &Parts, b: &Parts) -> bool {
+    if b.is_empty() || (!b.areas.is_empty() && a.areas.is_empty()) {
+        return false;
+    }
+    if !b.vertices().all(|pt| a.covers(pt)) {
+        return false;
+    }
+    let segments_inside = b.segments.iter().all(|seg| {
+        let mut cuts = vec![0.0, 1.0];
+        for boundary in &a.segments {
+            match seg.intersection(boundary) {
+                SegmentIntersection::None => {}
+                SegmentIntersection::Point(pt) => cuts.push(seg.project(&pt)),
+                SegmentIntersection::Overlap(shared) => {
+                    cuts.extend([seg.project(&shared.start), seg.project(&shared.end)])
+                }
+            }
+        }
+        cuts.sort_by(f64::total_cmp);
+        cuts.windows(2)
+            .all(|pair| a.covers(&seg.point_at((pair[0] + pair[1]) / 2.0)))
+    });
+    segments_inside && !a.vertices().any(|pt| b.strictly_covers(pt))
+}
+
+fn distance(a: &Parts, b: &Parts) -> f64 {
+    if intersects(a, b) {
+        return 0.0;
+    }
+    let one_way = |from: &Parts, to: &Parts| {
+        from.vertices()
+            .flat_map(|pt| to.linework().map(move |s| s.distance_to_point(pt)))
+            .fold(f64::INFINITY, f64::min)
+    };
+    one_way(a, b).min(one_way(b, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::serialization::parse_wkt;
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn wkt(raw: &str) -> GeomWrapper {
+        parse_wkt(String::from(raw)).unwrap()
+    }
+
+    #[test]
+    fn test_predicates() {
+        let square = wkt("POLYGON ((0 0, 0 4, 4 4, 4 0, 0 0), (1 1, 2 1, 2 2, 1 2, 1 1))");
+        let inner = wkt("POLYGON ((2.5 2.5, 2.5 3, 3 3, 3 2.5, 2.5 2.5))");
+        let around_hole = wkt("POLYGON ((0.5 0.5, 0.5 3, 3 3, 3 0.5, 0.5 0.5))");
+        let in_hole = wkt("POINT (1.5 1.5)");
+        let crossing = wkt("LINESTRING (-1 3, 5 3)");
+        let along_edge = wkt("LINESTRING (0 1, 0 3)");
+        let across_hole = wkt("LINESTRING (0.5 1.5, 3 1.5)");
+        let far = wkt("MULTIPOINT (10 10, 10 12)");
+
+        assert!(square.covers(&inner));
+        assert!(inner.covered_by(&square));
+        assert!(!square.covers(&around_hole));
+        assert!(square.intersects(&around_hole));
+        assert!(!square.covers(&in_hole));
+        assert!(!square.intersects(&in_hole));
+        assert_eq!(square.distance(&in_hole), 0.5);
+        assert!(square.intersects(&crossing));
+        assert!(!square.covers(&crossing));
+        assert!(square.covers(&along_edge));
+        assert!(!square.covers(&across_hole));
+        assert!(!square.intersects(&far));
+        assert!(square.distance(&far) == 6.0_f64.hypot(6.0));
+
+        // Lines and points
+        assert!(crossing.covers(&wkt("POINT (2 3)")));
+        assert!(crossing.covers(&wkt("LINESTRING (0 3, 1 3)")));
+        assert!(!crossing.covers(&inner));
+        assert!(crossing.intersects(&wkt("LINESTRING (2 0, 2 5)")));
+        assert_eq!(crossing.distance(&wkt("POINT (2 5)")), 2.0);
+        assert!(far.covers(&wkt("POINT (10 12)")));
+        assert!(!far.covers(&wkt("POINT (10 11)")));
+    }
+
+    #[test]
+    fn test_contains_through_vertices() {
+        // Notch dipping down to (2 1): the line leaves the polygon through the vertices (1 2)
+        // and (3 2) only, without crossing any edge
+        let notched = wkt("POLYGON ((0 0, 0 4, 1.8 4, 1 2, 2 1, 3 2, 2.2 4, 8 4, 8 0, 0 0))");
+        let line = wkt("LINESTRING (0.5 2, 7 2)");
+        assert!(!notched.covers(&wkt("POINT (2 2)")));
+        assert!(line.covers(&wkt("POINT (2 2)")));
+        assert!(!notched.covers(&line));
+        assert!(!line.covered_by(&notched));
+
+        // Lines touching the notch from inside, or running along its edges, are still covered
+        assert!(notched.covers(&wkt("LINESTRING (0.5 1, 2 1, 7 1)")));
+        assert!(notched.covers(&wkt("LINESTRING (0.5 2, 1 2)")));
+        assert!(notched.covers(&wkt("LINESTRING (1 2, 2 1, 3 2, 7 2)")));
+    }
+
+    #[test]
+    fn test_join_points_in_polygons() {
+        // Grid of unit squares, and random points
+        let squares: Vec<Polygon> = (0..100)
+            .map(|i| {
+                let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+                let corners = [(0.1, 0.1), (0.1, 0.9), (0.9, 0.9), (0.9, 0.1), (0.1, 0.1)];
+                let ring = corners
+                    .iter()
+                    .map(|(dx, dy)| Point::new(x + dx, y + dy))
+                    .collect();
+                Polygon::new(ring).unwrap()
+            })
+            .collect();
+        let mut random = rng();
+        let points: Vec<Point> = (0..500)
+            .map(|_| Point::new(random.random_range(-1.0..11.0), random.random()))
+            .map(|pt| Point::new(pt.coords().0, pt.coords().1 * 10.0))
+            .collect();
+
+        let brute_force = |holds: &dyn Fn(&Point, &Polygon) -> bool| {
+            let mut pairs = Vec::new();
+            for (i, pt) in points.iter().enumerate() {
+                for (j, square) in squares.iter().enumerate() {
+                    if holds(pt, square) {
+                        pairs.push((i, j));
+                    }
+                }
+            }
+            pairs
+        };
+
+        let within = spatial_join(&points, &squares, JoinPredicate::Within).unwrap();
+        assert_eq!(within, brute_force(&|pt, sq| sq.contains(pt)));
+        let intersects = spatial_join(&points, &squares, JoinPredicate::Intersects).unwrap();
+        assert_eq!(intersects, within);
+
+        let contains = spatial_join(&squares, &points, JoinPredicate::Contains).unwrap();
+        let mut flipped: Vec<(usize, usize)> = within.iter().map(|&(i, j)| (j, i)).collect();
+        flipped.sort();
+        assert_eq!(contains, flipped);
+
+        let near = spatial_join(&points, &squares, JoinPredicate::WithinDistance(0.15)).unwrap();
+        assert_eq!(
+            near,
+            brute_force(&|pt, sq| Relate::distance(pt, sq) <= 0.15)
+        );
+        assert!(near.len() > within.len());
+
+        assert!(spatial_join(&points, &squares, JoinPredicate::WithinDistance(-1.0)).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use super::points::Point;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Static KD-tree over a set of points, for nearest neighbour and radius queries.
+///
+/// The tree is built once from the points, by recursively splitting them at the median along x
+/// and y in turn. It is stored implicitly as a permutation of the points, so that it takes no
+/// more memory than an index per point.
+///
+/// Queries return the indices of the points in the input vector together with their distances
+/// to the query point, sorted by increasing distance.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{KdTree, Point};
+/// let points: Vec<Point> = (0..100)
+///     .map(|i| Point::new((i % 10) as f64, (i / 10) as f64))
+///     .collect();
+/// let tree = KdTree::new(points);
+///
+/// let (index, distance) = tree.nearest(&Point::new(2.2, 6.9)).unwrap();
+/// assert_eq!(tree.points()[index].coords(), (2.0, 7.0));
+/// assert!(geomlib::approx(distance, 0.05_f64.sqrt()));
+///
+/// assert_eq!(tree.k_nearest(&Point::new(2.2, 6.9), 4).len(), 4);
+/// assert_eq!(tree.within_radius(&Point::new(5.0, 5.0), 1.0).len(), 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct KdTree {
+    points: Vec<Point>,
+    /// Indices of the points, ordered so that each subtree is a contiguous range with its root
+    /// in the middle
+    order: Vec<usize>,
+}
+
+/// Point found by a query, ordered by distance
+#[derive(Clone, Copy, Debug)]
+struct Neighbor {
+    distance: f64,
+    index: usize,
+}
+
+impl KdTree {
+    /// Build the tree from a set of points
+    pub fn new(points: Vec<Point>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        build(&points, &mut order, 0);
+        Self { points, order }
+    }
+
+    /// Points of the tree, in the order they were given
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Index of the point closest to `pt` and its distance, None if the tree is empty
+    pub fn nearest(&self, pt: &Point) -> Option<(usize, f64)> {
+        self.k_nearest(pt, 1).pop()
+    }
+
+    /// Indices of the `k` points closest to `pt` and their distances, by increasing distance.
+    /// Returns all the points if there are less than `k`.
+    pub fn k_nearest(&self, pt: &Point, k: usize) -> Vec<(usize, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search_nearest(pt, k, 0, self.order.len(), 0, &mut heap);
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|n| (n.index, n.distance))
+            .collect()
+    }
+
+    /// Indices of the points within `radius` of `pt` (inclusive) and their distances, by
+    /// increasing distance
+    pub fn within_radius(&self, pt: &Point, radius: f64) -> Vec<(usize, f64)> {
+        let mut found = Vec::new();
+        self.search_radius(pt, radius, 0, self.order.len(), 0, &mut found);
+        found.sort();
+        found.into_iter().map(|n| (n.index, n.distance)).collect()
+    }
+
+    /// Signed offset of `pt` from the splitting line of a node along its axis
+    fn offset(&self, pt: &Point, node: usize, depth: usize) -> f64 {
+        let (x, y) = pt.coords();
+        let (nx, ny) = self.points[self.order[node]].coords();
+        if depth.is_multiple_of(2) {
+            x - nx
+        } else {
+            y - ny
+        }
+    }
+
+    /// Depth-first search of the nearest points in the subtree spanning `lo..hi`, visiting the
+    /// side of the query point first. `heap` keeps the `k` closest points found so far, the
+    /// furthest on top.
+    fn search_nearest(
+        &self,
+        pt: &Point,
+        k: usize,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        heap: &mut BinaryHeap<Neighbor>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let index = self.order[mid];
+        heap.push(Neighbor {
+            distance: self.points[index].l2_distance(pt),
+            index,
+        });
+        if heap.len() > k {
+            heap.pop();
+        }
+
+        let offset = self.offset(pt, mid, depth);
+        let (near, far) = if offset < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search_nearest(pt, k, near.0, near.1, depth + 1, heap);
+        // The other side can only hold closer points if the splitting line is closer
+        let worst = heap.peek().map_or(f64::INFINITY, |n| n.distance);
+        if heap.len() < k || offset.abs() <= worst {
+            self.search_nearest(pt, k, far.0, far.1, depth + 1, heap);
+        }
+    }
+
+    fn search_radius(
+        &self,
+        pt: &Point,
+        radius: f64,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        found: &mut Vec<Neighbor>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let index = self.order[mid];
+        let distance = self.points[index].l2_distance(pt);
+        if distance <= radius {
+            found.push(Neighbor { distance, index });
+        }
+
+        // Points before the middle are on the lower side of the splitting line, points after
+        // it on the upper side
+        let offset = self.offset(pt, mid, depth);
+        if offset <= radius {
+            self.search_radius(pt, radius, lo, mid, depth + 1, found);
+        }
+        if offset >= -radius {
+            self.search_radius(pt, radius, mid + 1, hi, depth + 1, found);
+        }
+    }
+}
+
+impl FromIterator<Point> for KdTree {
+    fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Arrange the indices so that the median point along the axis of this depth is in the middle,
+/// with the smaller ones before it and the larger ones after, then recurse on both halves
+fn build(points: &[Point], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    let coord = |i: &usize| {
+        let (x, y) = points[*i].coords();
+        if depth.is_multiple_of(2) { x } else { y }
+    };
+    order.select_nth_unstable_by(mid, |a, b| coord(a).total_cmp(&coord(b)));
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+impl PartialEq for Neighbor {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Neighbor {}
+
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbor {
+    /// By distance, then index, so that the binary heap pops the furthest point first
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.index.cmp(&other.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::random_points;
+    use super::*;
+
+    /// All the points sorted by distance, then index
+    fn brute_force(points: &[Point], pt: &Point) -> Vec<(usize, f64)> {
+        let mut all: Vec<(usize, f64)> = points
+            .iter()
+            .enumerate()
+            .map(|(i, other)| (i, other.l2_distance(pt)))
+            .collect();
+        all.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        all
+    }
+
+    #[test]
+    fn test_random_queries() {
+        let points = random_points(2000, -10.0..10.0, 0.0..5.0);
+        let tree = KdTree::new(points.clone());
+        assert_eq!(tree.len(), 2000);
+        for query in random_points(50, -10.0..10.0, 0.0..5.0) {
+            let expected = brute_force(&points, &query);
+            assert_eq!(tree.nearest(&query), Some(expected[0]));
+            assert_eq!(tree.k_nearest(&query, 10), expected[..10].to_vec());
+
+            let within: Vec<(usize, f64)> = expected
+                .iter()
+                .copied()
+                .take_while(|&(_, d)| d <= 1.5)
+                .collect();
+            assert_eq!(tree.within_radius(&query, 1.5), within);
+        }
+    }
+
+    #[test]
+    fn test_duplicates() {
+        // Many points on the same lines and locations
+        let points: Vec<Point> = (0..300)
+            .map(|i| Point::new((i % 3) as f64, (i % 7) as f64))
+            .collect();
+        let tree: KdTree = points.iter().cloned().collect();
+        let query = Point::new(1.0, 3.0);
+        let expected = brute_force(&points, &query);
+        assert_eq!(tree.k_nearest(&query, 25), expected[..25].to_vec());
+        let within = tree.within_radius(&query, 1.0);
+        assert_eq!(within.len(), expected.iter().filter(|n| n.1 <= 1.0).count());
+        assert!(
+            within
+                .iter()
+                .all(|n| points[n.0].l2_distance(&query) <= 1.0)
+        );
+    }
+
+    #[test]
+    fn test_small_trees() {
+        let tree = KdTree::new(Vec::new());
+        assert!(tree.is_empty());
+        assert!(tree.nearest(&Point::new(0.0, 0.0)).is_none());
+        assert!(tree.within_radius(&Point::new(0.0, 0.0), 1.0).is_empty());
+
+        let tree = KdTree::new(vec![Point::new(1.0, 1.0), Point::new(3.0, 1.0)]);
+        assert_eq!(
+            tree.k_nearest(&Point::new(2.5, 1.0), 5),
+            vec![(1, 0.5), (0, 1.5)]
+        );
+        assert!(tree.k_nearest(&Point::new(2.5, 1.0), 0).is_empty());
+    }
+}
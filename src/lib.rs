@@ -1,12 +1,58 @@
+mod buffer;
+mod calipers;
+mod circles;
+mod closest;
+mod concave;
 pub mod core;
+mod delaunay;
+mod envelope;
+mod geodesic;
+mod join;
+mod kdtree;
 mod linestring;
 mod ops;
+mod overlay;
 mod points;
 mod polygons;
+mod predicates;
+mod projection;
+mod quadtree;
+mod referencing;
+mod rtree;
+mod segment;
 pub mod serialization;
+mod similarity;
+mod simplify;
+mod split;
+mod sweep;
+#[cfg(test)]
+mod test_utils;
+mod transform;
+mod triangulation;
+mod voronoi;
 
+pub use self::buffer::*;
+pub use self::calipers::*;
+pub use self::circles::*;
+pub use self::closest::*;
+pub use self::concave::*;
+pub use self::delaunay::*;
+pub use self::envelope::*;
+pub use self::join::*;
+pub use self::kdtree::*;
 pub use self::linestring::*;
 pub use self::ops::*;
 pub use self::points::*;
 pub use self::polygons::*;
+pub use self::predicates::*;
+pub use self::projection::*;
+pub use self::quadtree::*;
+pub use self::rtree::*;
+pub use self::segment::*;
+pub use self::similarity::*;
+pub use self::simplify::*;
+pub use self::sweep::*;
+pub use self::transform::*;
+pub use self::triangulation::*;
+pub use self::voronoi::*;
 pub use core::*;
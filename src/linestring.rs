@@ -1,10 +1,14 @@
 use super::Point;
+use super::Segment;
 use super::core::{GeomResult, GeometricObject, GeometryError, display_for_geom};
 use std::iter::Zip;
 use std::slice::Iter;
 
+/// Maximum number of points a sequence can be densified to
+const MAX_DENSE_POINTS: usize = 100_000_000;
+
 /// Represents a sequence of line segments in 2D
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct LineString {
     pub points: Vec<Point>,
 }
@@ -17,7 +21,7 @@ impl GeometricObject for LineString {
             txt.push_str(&format!("{x} {y},"));
         }
         txt = txt.strip_suffix(",").unwrap().to_string();
-        txt.push_str(")");
+        txt.push(')');
         txt
     }
 }
@@ -38,7 +42,48 @@ impl LineString {
 
     /// Returns an iterator over the segments of the linestring
     pub fn edges<'a>(&'a self) -> Zip<Iter<'a, Point>, Iter<'a, Point>> {
-        return self.points.iter().zip(&self.points[1..]);
+        self.points.iter().zip(&self.points[1..])
+    }
+
+    /// Returns an iterator over the segments of the linestring, as owned `Segment`s
+    pub fn segments(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.edges()
+            .map(|(a, b)| Segment::new(a.clone(), b.clone()))
+    }
+
+    /// Insert vertices so that no segment is longer than `max_segment_length`.
+    ///
+    /// Each segment is split into the smallest number of equal parts that satisfies the
+    /// maximum length, and the original vertices are preserved. Returns an error if this would
+    /// need more than 100 million vertices.
+    pub fn densify(&self, max_segment_length: f64) -> GeomResult<LineString> {
+        LineString::new(densify_points(&self.points, max_segment_length)?)
+    }
+
+    /// Replace the vertices by `n_points` vertices evenly spaced by arc length along the
+    /// linestring, from its first point to its last.
+    ///
+    /// The other vertices are not kept, so corners may be cut.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{LineString, Point};
+    /// let line = LineString::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(1.0, 0.0),
+    ///     Point::new(4.0, 0.0),
+    ///     Point::new(4.0, 2.0),
+    /// ])
+    /// .unwrap();
+    /// let even = line.resample(4).unwrap();
+    /// let coords: Vec<(f64, f64)> = even.points.iter().map(|p| p.coords()).collect();
+    /// assert_eq!(coords, vec![(0.0, 0.0), (2.0, 0.0), (4.0, 0.0), (4.0, 2.0)]);
+    ///
+    /// let dense = line.densify(1.5).unwrap();
+    /// assert_eq!(dense.total_vertices(), 6);
+    /// ```
+    pub fn resample(&self, n_points: usize) -> GeomResult<LineString> {
+        LineString::new(resample_points(&self.points, n_points)?)
     }
 
     /// Get the total number of vertices in the linestring.
@@ -47,6 +92,96 @@ impl LineString {
     }
 }
 
+/// Insert vertices along a sequence of points so that no segment is longer than `max_len`.
+///
+/// Each segment is split into the smallest number of equal parts that satisfies the
+/// maximum length. The original vertices are always preserved.
+pub(crate) fn densify_points(points: &[Point], max_len: f64) -> GeomResult<Vec<Point>> {
+    if max_len.is_nan() || max_len <= 0.0 {
+        return Err(GeometryError::ParameterError(format!(
+            "Maximum segment length must be positive, got {max_len}"
+        )));
+    }
+    let parts: Vec<f64> = points
+        .iter()
+        .zip(points.iter().skip(1))
+        .map(|(start, end)| (start.l2_distance(end) / max_len).ceil().max(1.0))
+        .collect();
+    let total: f64 = parts.iter().sum();
+    if total.is_nan() || total > MAX_DENSE_POINTS as f64 {
+        return Err(GeometryError::ParameterError(format!(
+            "Densifying with a maximum segment length of {max_len} needs too many points"
+        )));
+    }
+
+    let mut dense = Vec::with_capacity(total as usize + 1);
+    for ((start, end), parts) in points.iter().zip(points.iter().skip(1)).zip(parts) {
+        let (x1, y1) = start.coords();
+        let (x2, y2) = end.coords();
+        let parts = parts as usize;
+
+        dense.push(start.clone());
+        for i in 1..parts {
+            let t = i as f64 / parts as f64;
+            dense.push(Point::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1)));
+        }
+    }
+    if let Some(last) = points.last() {
+        dense.push(last.clone());
+    }
+    Ok(dense)
+}
+
+/// Compute `n_points` points evenly spaced by arc length along a sequence of points, from its
+/// first point to its last.
+///
+/// The vertices of the sequence are not kept, except for its endpoints, so corners may be cut.
+/// A closed sequence gives a closed sequence.
+pub(crate) fn resample_points(points: &[Point], n_points: usize) -> GeomResult<Vec<Point>> {
+    if n_points < 2 {
+        return Err(GeometryError::ParameterError(format!(
+            "At least 2 points are needed to resample, got {n_points}"
+        )));
+    }
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return Err(GeometryError::ParameterError(String::from(
+            "Cannot resample an empty sequence of points",
+        )));
+    };
+    let total: f64 = points
+        .iter()
+        .zip(points.iter().skip(1))
+        .map(|(start, end)| start.l2_distance(end))
+        .sum();
+    let step = total / (n_points - 1) as f64;
+
+    let mut resampled = Vec::with_capacity(n_points);
+    resampled.push(first.clone());
+    let mut travelled = 0.0;
+    for (start, end) in points.iter().zip(points.iter().skip(1)) {
+        let (x1, y1) = start.coords();
+        let (x2, y2) = end.coords();
+        let length = start.l2_distance(end);
+        while resampled.len() < n_points - 1 {
+            let target = step * resampled.len() as f64;
+            if target > travelled + length {
+                break;
+            }
+            let t = if length > 0.0 {
+                (target - travelled) / length
+            } else {
+                0.0
+            };
+            resampled.push(Point::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1)));
+        }
+        travelled += length;
+    }
+    // Targets beyond the computed total length, from rounding
+    resampled.resize(n_points, last.clone());
+    resampled[n_points - 1] = last.clone();
+    Ok(resampled)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Point;
@@ -80,4 +215,67 @@ mod tests {
         let edges: Vec<(&Point, &Point)> = ls.edges().collect();
         assert_eq!(edges.len(), 2);
     }
+
+    #[test]
+    fn test_densify_points() {
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 0.25),
+        ];
+        let dense = densify_points(&pts, 0.3).unwrap();
+        assert_eq!(dense.len(), 6);
+        assert!(dense[0].is_close(&pts[0]));
+        assert!(dense[1].is_close(&Point::new(0.25, 0.0)));
+        assert!(dense[4].is_close(&pts[1]));
+        assert!(dense[5].is_close(&pts[2]));
+
+        assert!(densify_points(&pts, 0.0).is_err());
+
+        // Too many parts, or infinitely many
+        assert!(densify_points(&pts, 1e-300).is_err());
+        let far = [Point::new(0.0, 0.0), Point::new(f64::INFINITY, 0.0)];
+        assert!(densify_points(&far, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_resample_points() {
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 3.0),
+            Point::new(0.0, 3.0),
+            Point::new(0.0, 0.0),
+        ];
+        let even = resample_points(&pts, 7).unwrap();
+        let coords: Vec<(f64, f64)> = even.iter().map(|p| p.coords()).collect();
+        assert_eq!(
+            coords,
+            vec![
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 1.0),
+                (3.0, 3.0),
+                (1.0, 3.0),
+                (0.0, 2.0),
+                (0.0, 0.0)
+            ]
+        );
+        for (a, b) in even.iter().zip(&even[1..]) {
+            assert!(a.l2_distance(b) <= 2.0 + 1e-12);
+        }
+
+        // Many points, some at the vertices
+        let ls = LineString::new(pts).unwrap();
+        let fine = ls.resample(121).unwrap();
+        assert_eq!(fine.total_vertices(), 121);
+        assert!(fine.points[30].is_close(&Point::new(3.0, 0.0)));
+        assert_eq!(fine.points[120].coords(), (0.0, 0.0));
+
+        let degenerate = resample_points(&[Point::new(1.0, 1.0)], 3).unwrap();
+        assert!(degenerate.iter().all(|p| p.coords() == (1.0, 1.0)));
+        assert!(resample_points(&[], 3).is_err());
+        assert!(ls.resample(1).is_err());
+    }
 }
@@ -1,20 +1,65 @@
+mod buffer;
+mod calipers;
+mod circles;
 mod cli_commands;
+mod closest;
+mod concave;
 mod core;
+mod delaunay;
+mod envelope;
+mod geodesic;
+mod join;
+mod kdtree;
 mod linestring;
 mod ops;
+mod overlay;
 mod points;
 mod polygons;
+mod predicates;
+mod projection;
+mod quadtree;
+mod referencing;
+mod rtree;
+mod segment;
 pub mod serialization;
+mod similarity;
+mod simplify;
+mod split;
+mod sweep;
+#[cfg(test)]
+mod test_utils;
+mod transform;
+mod triangulation;
+mod voronoi;
 
 use crate::core::GeometryError;
 
+pub use self::buffer::*;
+pub use self::calipers::*;
+pub use self::circles::*;
+pub use self::closest::*;
+pub use self::concave::*;
+pub use self::delaunay::*;
+pub use self::envelope::*;
+pub use self::join::*;
+pub use self::kdtree::*;
 pub use self::linestring::*;
 pub use self::ops::*;
 pub use self::points::*;
 pub use self::polygons::*;
-use clap::{Parser, Subcommand};
+pub use self::predicates::*;
+pub use self::projection::*;
+pub use self::quadtree::*;
+pub use self::rtree::*;
+pub use self::segment::*;
+pub use self::similarity::*;
+pub use self::simplify::*;
+pub use self::sweep::*;
+pub use self::transform::*;
+pub use self::triangulation::*;
+pub use self::voronoi::*;
+use clap::{Parser, Subcommand, ValueEnum};
 pub use core::GeometricObject;
-use log;
 use std::fs::File;
 use std::io;
 use std::io::Read;
@@ -40,8 +85,8 @@ enum AppCommands {
 
     /// Convex Hull computation.
     ///
-    /// Computes the convex hull of a geometry given as WKT. You must provide either a wkt string
-    /// directly or a path to a file containing the wkt.
+    /// Computes the convex hull of a geometry given as WKT, or its concave hull with `--concave`.
+    /// You must provide either a wkt string directly or a path to a file containing the wkt.
     ConvexHull {
         /// File to read the geometry (WKT) from to compute convex hull
         #[arg(short, long, default_value = "")]
@@ -54,6 +99,11 @@ enum AppCommands {
         /// If given, save the output as wkt to this filepath
         #[arg(short, long, default_value = "")]
         output_file: String,
+
+        /// If given, compute a concave hull instead, with this ratio between 0 (most concave)
+        /// and 1 (convex hull)
+        #[arg(short, long)]
+        concave: Option<f64>,
     },
 
     /// Compute Polygon Clip (intersection)
@@ -81,6 +131,185 @@ enum AppCommands {
         #[arg(short, long, default_value = "")]
         output_file: String,
     },
+
+    /// Simplify a LineString or Polygon.
+    ///
+    /// Removes vertices from the geometry given as WKT. You must provide either a wkt string
+    /// directly or a path to a file containing the wkt.
+    Simplify {
+        /// File to read the geometry (WKT) from to simplify
+        #[arg(short, long, default_value = "")]
+        file: String,
+
+        /// WKT string of the geometry to simplify
+        #[arg(short, long, default_value = "")]
+        wkt: String,
+
+        /// Simplification tolerance: a distance for Douglas-Peucker, an area for
+        /// Visvalingam-Whyatt
+        #[arg(short, long)]
+        tolerance: f64,
+
+        /// Simplification algorithm
+        #[arg(short, long, value_enum, default_value_t = SimplifyAlgorithm::DouglasPeucker)]
+        method: SimplifyAlgorithm,
+
+        /// Avoid introducing new self-intersections in the geometry
+        #[arg(short, long)]
+        preserve_topology: bool,
+
+        /// If given, save the output as wkt to this filepath
+        #[arg(short, long, default_value = "")]
+        output_file: String,
+    },
+
+    /// Apply an affine transformation to a geometry.
+    ///
+    /// The geometry given as WKT is scaled, skewed, rotated and then translated by the given
+    /// amounts. You must provide either a wkt string directly or a path to a file containing
+    /// the wkt.
+    Transform {
+        /// File to read the geometry (WKT) from to transform
+        #[arg(short, long, default_value = "")]
+        file: String,
+
+        /// WKT string of the geometry to transform
+        #[arg(short, long, default_value = "")]
+        wkt: String,
+
+        /// Scaling factors along x and y
+        #[arg(short, long, num_args = 2, value_names = ["SX", "SY"], allow_negative_numbers = true)]
+        scale: Option<Vec<f64>>,
+
+        /// Skew angles in degrees, of the vertical axis towards x and of the horizontal axis
+        /// towards y
+        #[arg(long, num_args = 2, value_names = ["AX", "AY"], allow_negative_numbers = true)]
+        skew: Option<Vec<f64>>,
+
+        /// Counter-clockwise rotation angle in degrees
+        #[arg(short, long, allow_negative_numbers = true)]
+        rotate: Option<f64>,
+
+        /// Translation offsets along x and y
+        #[arg(short, long, num_args = 2, value_names = ["DX", "DY"], allow_negative_numbers = true)]
+        translate: Option<Vec<f64>>,
+
+        /// Scale, skew and rotate about the centroid of the geometry instead of the origin
+        #[arg(short, long)]
+        centroid: bool,
+
+        /// If given, save the output as wkt to this filepath
+        #[arg(short, long, default_value = "")]
+        output_file: String,
+    },
+
+    /// Convert a geometry between coordinate systems.
+    ///
+    /// Coordinate systems are given by their EPSG identifiers: 4326 for longitude and latitude
+    /// (WGS84), 3857 for Web Mercator, and 326xx or 327xx for the UTM zones. The target can
+    /// also be "utm" to pick the UTM zone containing the geometry's centroid. You must provide
+    /// either a wkt string directly or a path to a file containing the wkt.
+    Project {
+        /// File to read the geometry (WKT) from to project
+        #[arg(short, long, default_value = "")]
+        file: String,
+
+        /// WKT string of the geometry to project
+        #[arg(short, long, default_value = "")]
+        wkt: String,
+
+        /// Coordinate system of the input geometry
+        #[arg(long, default_value = "4326")]
+        from_srid: String,
+
+        /// Coordinate system to project the geometry to
+        #[arg(long)]
+        to_srid: String,
+
+        /// If given, save the output as wkt to this filepath
+        #[arg(short, long, default_value = "")]
+        output_file: String,
+    },
+
+    /// Find the points closest to a query point.
+    ///
+    /// The points are given as a WKT MULTIPOINT and indexed in a KD-tree. Returns the `k`
+    /// nearest points, or all the points within the given radius. You must provide either a wkt
+    /// string directly or a path to a file containing the wkt.
+    Neighbors {
+        /// File to read the points (WKT) from
+        #[arg(short, long, default_value = "")]
+        file: String,
+
+        /// WKT string of the points
+        #[arg(short, long, default_value = "")]
+        wkt: String,
+
+        /// Coordinates of the query point
+        #[arg(
+            short,
+            long,
+            required = true,
+            num_args = 2,
+            value_names = ["X", "Y"],
+            allow_negative_numbers = true
+        )]
+        point: Vec<f64>,
+
+        /// Number of nearest points to find
+        #[arg(short, long, default_value_t = 1)]
+        k: usize,
+
+        /// If given, find all the points within this distance instead of the k nearest
+        #[arg(short, long)]
+        radius: Option<f64>,
+
+        /// If given, save the points found as wkt to this filepath
+        #[arg(short, long, default_value = "")]
+        output_file: String,
+    },
+
+    /// Join two sets of geometries on a spatial predicate.
+    ///
+    /// Each file holds one WKT geometry per line. Prints the pairs of line numbers (starting at
+    /// 1) of the geometries for which the predicate holds.
+    Join {
+        /// File with the left geometries, one WKT per line
+        #[arg(short, long)]
+        left_file: String,
+
+        /// File with the right geometries, one WKT per line
+        #[arg(short, long)]
+        right_file: String,
+
+        /// Predicate between the left and right geometries
+        #[arg(short, long, value_enum, default_value_t = JoinKind::Intersects)]
+        predicate: JoinKind,
+
+        /// Maximum distance between the geometries, for the within-distance predicate
+        #[arg(short, long)]
+        distance: Option<f64>,
+
+        /// If given, save the pairs to this filepath
+        #[arg(short, long, default_value = "")]
+        output_file: String,
+    },
+}
+
+/// Spatial predicates available from the CLI
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum JoinKind {
+    Intersects,
+    Contains,
+    Within,
+    WithinDistance,
+}
+
+/// Simplification algorithms available from the CLI
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SimplifyAlgorithm {
+    DouglasPeucker,
+    VisvalingamWhyatt,
 }
 
 fn main() {
@@ -103,12 +332,13 @@ fn run(cli: Cli) -> core::GeomResult<()> {
                     )));
                 }
             };
-            return cli_commands::parse_show_detail(source);
+            cli_commands::parse_show_detail(source)
         }
         AppCommands::ConvexHull {
             file,
             wkt,
             output_file,
+            concave,
         } => {
             let ofp = if output_file.trim() == "" {
                 None
@@ -119,7 +349,7 @@ fn run(cli: Cli) -> core::GeomResult<()> {
                 Err(err) => Err(core::GeometryError::OperationError(format!(
                     "Error reading WKT from file: {err}"
                 ))),
-                Ok(source) => cli_commands::compute_convex_hull(source, ofp),
+                Ok(source) => cli_commands::compute_convex_hull(source, concave, ofp),
             }
         }
         AppCommands::ClipPolygon {
@@ -141,13 +371,130 @@ fn run(cli: Cli) -> core::GeomResult<()> {
 
             cli_commands::compute_clip_polygon(wkt_s, wkt_c, out_file)
         }
+        AppCommands::Simplify {
+            file,
+            wkt,
+            tolerance,
+            method,
+            preserve_topology,
+            output_file,
+        } => {
+            let source = get_string(wkt, file).map_err(cli_commands::wrap_io_error)?;
+            let method = match method {
+                SimplifyAlgorithm::DouglasPeucker => SimplifyMethod::DouglasPeucker,
+                SimplifyAlgorithm::VisvalingamWhyatt => SimplifyMethod::VisvalingamWhyatt,
+            };
+            let ofp = if output_file.trim() == "" {
+                None
+            } else {
+                Some(output_file.trim())
+            };
+            cli_commands::compute_simplify(source, method, tolerance, preserve_topology, ofp)
+        }
+        AppCommands::Transform {
+            file,
+            wkt,
+            scale,
+            skew,
+            rotate,
+            translate,
+            centroid,
+            output_file,
+        } => {
+            let source = get_string(wkt, file).map_err(cli_commands::wrap_io_error)?;
+            // Pairs of values are guaranteed by the number of arguments
+            let pair = |values: Option<Vec<f64>>| values.map(|v| (v[0], v[1]));
+            let ofp = if output_file.trim() == "" {
+                None
+            } else {
+                Some(output_file.trim())
+            };
+            let mut transform = AffineTransform::identity();
+            if let Some((sx, sy)) = pair(scale) {
+                transform = transform.scale(sx, sy);
+            }
+            if let Some((ax, ay)) = pair(skew) {
+                transform = transform.skew(ax.to_radians(), ay.to_radians());
+            }
+            if let Some(angle) = rotate {
+                transform = transform.rotate(angle.to_radians());
+            }
+            cli_commands::compute_transform(source, transform, centroid, pair(translate), ofp)
+        }
+        AppCommands::Project {
+            file,
+            wkt,
+            from_srid,
+            to_srid,
+            output_file,
+        } => {
+            let source = get_string(wkt, file).map_err(cli_commands::wrap_io_error)?;
+            let from = from_srid.parse()?;
+            let to = if to_srid.trim().eq_ignore_ascii_case("utm") {
+                None
+            } else {
+                Some(to_srid.parse()?)
+            };
+            let ofp = if output_file.trim() == "" {
+                None
+            } else {
+                Some(output_file.trim())
+            };
+            cli_commands::compute_projection(source, from, to, ofp)
+        }
+        AppCommands::Neighbors {
+            file,
+            wkt,
+            point,
+            k,
+            radius,
+            output_file,
+        } => {
+            let source = get_string(wkt, file).map_err(cli_commands::wrap_io_error)?;
+            // Two coordinates are guaranteed: the argument is required and takes two values
+            let query = Point::new(point[0], point[1]);
+            let ofp = if output_file.trim() == "" {
+                None
+            } else {
+                Some(output_file.trim())
+            };
+            cli_commands::compute_neighbors(source, query, k, radius, ofp)
+        }
+        AppCommands::Join {
+            left_file,
+            right_file,
+            predicate,
+            distance,
+            output_file,
+        } => {
+            let left = get_string(String::new(), left_file).map_err(cli_commands::wrap_io_error)?;
+            let right =
+                get_string(String::new(), right_file).map_err(cli_commands::wrap_io_error)?;
+            let predicate = match (predicate, distance) {
+                (JoinKind::Intersects, _) => JoinPredicate::Intersects,
+                (JoinKind::Contains, _) => JoinPredicate::Contains,
+                (JoinKind::Within, _) => JoinPredicate::Within,
+                (JoinKind::WithinDistance, Some(d)) => JoinPredicate::WithinDistance(d),
+                (JoinKind::WithinDistance, None) => {
+                    return Err(GeometryError::ParameterError(String::from(
+                        "The within-distance predicate requires a distance",
+                    )));
+                }
+            };
+            let ofp = if output_file.trim() == "" {
+                None
+            } else {
+                Some(output_file.trim())
+            };
+            cli_commands::compute_join(left, right, predicate, ofp)
+        }
     }
 }
 
 /// Get string value from either the given value or the filepath.
 /// The input value takes precedence over the filepath.
 fn get_string(input: String, fp: String) -> Result<String, io::Error> {
-    if input.len() > 0 {
+    if !input.is_empty() {
         return Ok(input);
     }
     log::debug!("Reading string from file: {}", fp);
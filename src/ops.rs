@@ -1,12 +1,10 @@
-use log;
 use std::slice::Iter;
 
 use super::core::{self, GeomResult, GeometryError};
 use super::points::*;
 use super::polygons::*;
-
-/// Line segment between two points
-pub type Segment<'a> = (&'a Point, &'a Point);
+use super::predicates::orient2d;
+use super::segment::Segment;
 
 /// Compute the convex hull of a set of points.
 ///
@@ -28,12 +26,12 @@ pub type Segment<'a> = (&'a Point, &'a Point);
 /// ];
 /// let square: Polygon = geomlib::convex_hull(&points).unwrap();
 /// ```
-pub fn convex_hull(points: &Vec<Point>) -> Option<Polygon> {
+pub fn convex_hull(points: &[Point]) -> Option<Polygon> {
     if points.len() < 3 {
         return None;
     }
 
-    let mut source_points = sort_lex(points.clone());
+    let mut source_points = sort_lex(points.to_vec());
     let mut hull = half_hull(source_points.iter());
     hull.pop(); // Pop element - it will be the first in the lower hull
 
@@ -60,9 +58,8 @@ fn half_hull(points: Iter<Point>) -> Vec<Point> {
             continue;
         }
 
-        while hull.len() > 1
-            && direction(&hull[hull.len() - 2], &hull[hull.len() - 1], &pt) != Turn::Right
-        {
+        // Exact predicate: only strict right turns are kept, however close to collinear
+        while hull.len() > 1 && orient2d(&hull[hull.len() - 2], &hull[hull.len() - 1], pt) >= 0.0 {
             hull.pop();
         }
         hull.push(pt.clone());
@@ -77,29 +74,25 @@ fn half_hull(points: Iter<Point>) -> Vec<Point> {
 /// uses a "parameteric" approach to finding the intersection of the
 /// segments. These are represented in the form `start_pt + t * (end_pt - start_pt)`,
 /// then we solve for the parameters `t` and use them to get the intersection
-/// point.
+/// point. See `Segment::intersection` for the part shared by collinear segments.
 ///
 /// Examples
 /// ```rust
-/// use geomlib::{self, Point};
-/// let (start1, end1) = (Point::new(0.0, 0.0), Point::new(1.0, 1.0));
-/// let seg1 = (&start1, &end1);
-///
-/// let (start2, end2) = (Point::new(1.0, 0.0), Point::new(0.0, 1.0));
-/// let seg2 = (&start2, &end2);
+/// use geomlib::{self, Point, Segment};
+/// let seg1 = Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+/// let seg2 = Segment::new(Point::new(1.0, 0.0), Point::new(0.0, 1.0));
 /// let pt = Point::new(0.5, 0.5);
 ///
-/// let inter = geomlib::intersection_point(seg1, seg2).unwrap();
+/// let inter = geomlib::intersection_point(&seg1, &seg2).unwrap();
 /// assert!(inter.is_close(&pt));
 ///
-/// let (start3, end3) = (Point::new(2.0, 0.0), Point::new(2.0, 3.0));
-/// let seg3 = (&start3, &end3);
-/// match geomlib::intersection_point(seg1, seg3) {
+/// let seg3 = Segment::new(Point::new(2.0, 0.0), Point::new(2.0, 3.0));
+/// match geomlib::intersection_point(&seg1, &seg3) {
 ///     None => println!("Segments do not intersect"),
 ///     Some(_) => panic!("This is bad!"),
 /// };
 /// ```
-pub fn intersection_point(s1: Segment, s2: Segment) -> Option<Point> {
+pub fn intersection_point(s1: &Segment, s2: &Segment) -> Option<Point> {
     intersection_with_line(s1, s2, true)
 }
 
@@ -108,14 +101,11 @@ pub fn intersection_point(s1: Segment, s2: Segment) -> Option<Point> {
 /// Computes the intersection point of `seg` with the line defined by `line`. Returns None
 /// if the segment does not intersect with the line. If `in_bounds` is true, this will also
 /// return None if the intersection not between the points in `line`.
-pub fn intersection_with_line(line: Segment, seg: Segment, in_bounds: bool) -> Option<Point> {
-    let (a, b) = line;
-    let (c, d) = seg;
-
-    let (a1, a2) = a.coords();
-    let (b1, b2) = b.coords();
-    let (c1, c2) = c.coords();
-    let (d1, d2) = d.coords();
+pub fn intersection_with_line(line: &Segment, seg: &Segment, in_bounds: bool) -> Option<Point> {
+    let (a1, a2) = line.start.coords();
+    let (b1, b2) = line.end.coords();
+    let (c1, c2) = seg.start.coords();
+    let (d1, d2) = seg.end.coords();
 
     let det = (b1 - a1) * (c2 - d2) - (b2 - a2) * (c1 - d1);
     if core::approx(det, 0.0) {
@@ -126,12 +116,12 @@ pub fn intersection_with_line(line: Segment, seg: Segment, in_bounds: bool) -> O
     let t1 = ((c2 - d2) * (c1 - a1) + (d1 - c1) * (c2 - a2)) / det;
     let t2 = ((a2 - b2) * (c1 - a1) + (b1 - a1) * (c2 - a2)) / det;
 
-    if !(0.0 <= t2 && t2 <= 1.0) {
+    if !(0.0..=1.0).contains(&t2) {
         // Does not intersect seg
         return None;
     }
 
-    if (!in_bounds) || (0.0 <= t1 && t1 <= 1.0) {
+    if (!in_bounds) || (0.0..=1.0).contains(&t1) {
         Some(Point::new(
             t1 * b1 + (1.0 - t1) * a1,
             t1 * b2 + (1.0 - t1) * a2,
@@ -141,11 +131,52 @@ pub fn intersection_with_line(line: Segment, seg: Segment, in_bounds: bool) -> O
     }
 }
 
+/// Determine whether two (closed) line segments intersect.
+///
+/// Unlike `intersection_point`, this also returns true for collinear segments that
+/// overlap and for segments that only touch at an endpoint.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point, Segment};
+/// let s1 = Segment::new(Point::new(0.0, 0.0), Point::new(2.0, 0.0));
+/// let s2 = Segment::new(Point::new(1.0, 0.0), Point::new(3.0, 0.0));
+/// assert!(geomlib::segments_intersect(&s1, &s2));
+/// ```
+pub fn segments_intersect(s1: &Segment, s2: &Segment) -> bool {
+    let (a, b) = (&s1.start, &s1.end);
+    let (c, d) = (&s2.start, &s2.end);
+    let d1 = direction(a, b, c);
+    let d2 = direction(a, b, d);
+    let d3 = direction(c, d, a);
+    let d4 = direction(c, d, b);
+
+    if d1 != d2 && d3 != d4 && d1 != Turn::InLine && d2 != Turn::InLine {
+        // Proper crossing
+        return true;
+    }
+
+    (d1 == Turn::InLine && in_segment_box(c, s1))
+        || (d2 == Turn::InLine && in_segment_box(d, s1))
+        || (d3 == Turn::InLine && in_segment_box(a, s2))
+        || (d4 == Turn::InLine && in_segment_box(b, s2))
+}
+
+/// Determine if the point lies in the bounding box of the segment
+fn in_segment_box(pt: &Point, seg: &Segment) -> bool {
+    let (x, y) = pt.coords();
+    let (x1, y1) = seg.start.coords();
+    let (x2, y2) = seg.end.coords();
+    x1.min(x2) <= x && x <= x1.max(x2) && y1.min(y2) <= y && y <= y1.max(y2)
+}
+
 /// Compute the clipped polygon (intersection) of a subject polygon with a
 /// clipping polygon. The clipping polygon must be convex.
 ///
 /// Compute the intersection of a subject polygon with a convex clipping polygon
-/// using the Sutherland-Hodgman algorithm.
+/// using the Sutherland-Hodgman algorithm. Each of the subject's rings is clipped
+/// separately, so holes in the subject are preserved (or dropped, if they lie outside
+/// the clipping polygon). Holes in the clipping polygon are ignored.
 pub fn clip_polygon(subject: &Polygon, clip: &Polygon) -> GeomResult<Option<Polygon>> {
     if !clip.is_convex() {
         return Err(GeometryError::ParameterError(String::from(
@@ -158,23 +189,55 @@ pub fn clip_polygon(subject: &Polygon, clip: &Polygon) -> GeomResult<Option<Poly
         Orientation::CounterClockwise => Turn::Left,
     };
 
-    let mut vertices = subject.outer.clone();
+    let outer = match clip_ring(&subject.outer, clip, &turn_dir)? {
+        None => return Ok(None),
+        Some(ring) => ring,
+    };
+    let mut holes = Vec::with_capacity(subject.inner.len());
+    for ring in &subject.inner {
+        if let Some(hole) = clip_ring(ring, clip, &turn_dir)?
+            && hole.len() > 3
+        {
+            holes.push(hole);
+        }
+    }
+    Ok(Some(Polygon::with_holes(outer, holes)?))
+}
+
+/// Exact side of the line through `a` and `b` on which `pt` lies, as a turn direction
+fn side(a: &Point, b: &Point, pt: &Point) -> Turn {
+    let det = orient2d(a, b, pt);
+    if det > 0.0 {
+        Turn::Left
+    } else if det < 0.0 {
+        Turn::Right
+    } else {
+        Turn::InLine
+    }
+}
+
+/// Clip a single closed ring to the convex clipping polygon. `turn_dir` is the direction
+/// of the turn towards the inside of the clipping polygon from its edges.
+fn clip_ring(ring: &[Point], clip: &Polygon, turn_dir: &Turn) -> GeomResult<Option<Vec<Point>>> {
+    let mut vertices = ring.to_vec();
     let mut clipped = Vec::with_capacity(vertices.len());
     vertices.pop();
     for (ce1, ce2) in clip.edges() {
+        let clip_edge = Segment::new(ce1.clone(), ce2.clone());
         for i in 0..vertices.len() {
             let s1 = &vertices[i];
             let s2 = &vertices[(i + 1) % vertices.len()];
 
-            let s1_in = direction(ce1, ce2, s1) == turn_dir;
-            let s2_in = direction(ce1, ce2, s2) == turn_dir;
+            let s1_in = side(ce1, ce2, s1) == *turn_dir;
+            let s2_in = side(ce1, ce2, s2) == *turn_dir;
+            let edge = Segment::new(s1.clone(), s2.clone());
 
             if s1_in {
                 clipped.push(s1.clone());
                 if !s2_in {
                     // Next vertex not in the half-plane defined by the clipping line -
                     // Add the intersection to the list.
-                    match intersection_with_line((ce1, ce2), (s1, s2), false) {
+                    match intersection_with_line(&clip_edge, &edge, false) {
                         Some(pt) => clipped.push(pt),
                         _ => {
                             return Err(GeometryError::OperationError(String::from(
@@ -185,7 +248,7 @@ pub fn clip_polygon(subject: &Polygon, clip: &Polygon) -> GeomResult<Option<Poly
                 }
             } else if s2_in {
                 // First point not in half-plane, second point is - add intersection
-                match intersection_with_line((ce1, ce2), (s1, s2), false) {
+                match intersection_with_line(&clip_edge, &edge, false) {
                     Some(pt) => clipped.push(pt),
                     _ => {
                         return Err(GeometryError::OperationError(String::from(
@@ -207,12 +270,18 @@ pub fn clip_polygon(subject: &Polygon, clip: &Polygon) -> GeomResult<Option<Poly
         clipped.clear();
     }
 
+    if vertices.len() < 3 {
+        // Only touching the clipping polygon
+        return Ok(None);
+    }
     vertices.push(vertices[0].clone());
-    return Ok(Some(Polygon::new(vertices)?));
+    Ok(Some(vertices))
 }
 
 #[cfg(test)]
+#[allow(clippy::redundant_pattern_matching)]
 mod tests {
+    use super::core::GeometricObject;
     use super::*;
     use rand::{Rng, rng};
 
@@ -260,66 +329,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_convex_hull_large_coordinates() {
+        // A grid at UTM-like coordinates: only the corners are on the hull
+        let mut points = Vec::new();
+        for i in 0..20 {
+            for j in 0..20 {
+                points.push(Point::new(
+                    500000.0 + 0.5 * i as f64,
+                    5000000.0 + 0.25 * j as f64,
+                ));
+            }
+        }
+        let hull = convex_hull(&points).unwrap();
+        assert_eq!(hull.outer.len(), 5);
+        assert!(hull.is_convex());
+
+        // Random points in a small area far from the origin
+        let mut random = rng();
+        let points: Vec<Point> = (0..300)
+            .map(|_| {
+                Point::new(
+                    650000.0 + random.random::<f64>(),
+                    4500000.0 + random.random::<f64>(),
+                )
+            })
+            .collect();
+        let hull = convex_hull(&points).unwrap();
+        assert!(hull.is_convex());
+        for pt in &points {
+            assert!(hull.contains(pt) || hull.outer.iter().any(|v| v.is_close(pt)));
+        }
+    }
+
     #[test]
     fn test_intersect_true() {
         // Diagonals in unit square
-        let s1 = (&Point::new(0.0, 0.0), &Point::new(1.0, 1.0));
-        let s2 = (&Point::new(0.0, 1.0), &Point::new(1.0, 0.0));
+        let s1 = Segment::new(Point::new(0.0, 0.0), Point::new(1.0, 1.0));
+        let s2 = Segment::new(Point::new(0.0, 1.0), Point::new(1.0, 0.0));
 
-        let inter = intersection_point(s1, s2).unwrap();
+        let inter = intersection_point(&s1, &s2).unwrap();
         assert!(inter.is_close(&Point::new(0.5, 0.5)));
 
         // Example 2
-        let s1 = (&Point::new(0.0, 0.0), &Point::new(4.0, 4.0));
-        let s2 = (&Point::new(1.0, 3.0), &Point::new(3.0, 1.0));
+        let s1 = Segment::new(Point::new(0.0, 0.0), Point::new(4.0, 4.0));
+        let s2 = Segment::new(Point::new(1.0, 3.0), Point::new(3.0, 1.0));
 
-        let inter = intersection_point(s1, s2).unwrap();
+        let inter = intersection_point(&s1, &s2).unwrap();
         assert!(inter.is_close(&Point::new(2.0, 2.0)));
 
         // Example 3
-        let s1 = (&Point::new(2.0, 1.0), &Point::new(6.0, 3.0));
-        let s2 = (&Point::new(4.0, 0.0), &Point::new(4.0, 3.0));
+        let s1 = Segment::new(Point::new(2.0, 1.0), Point::new(6.0, 3.0));
+        let s2 = Segment::new(Point::new(4.0, 0.0), Point::new(4.0, 3.0));
 
-        let inter = intersection_point(s1, s2).unwrap();
-        let inter2 = intersection_point(s2, s1).unwrap();
+        let inter = intersection_point(&s1, &s2).unwrap();
+        let inter2 = intersection_point(&s2, &s1).unwrap();
         assert!(inter.is_close(&Point::new(4.0, 2.0)));
         assert!(inter.is_close(&inter2));
 
         // Consecutive segments
-        let s1 = (&Point::new(2.0, 1.0), &Point::new(6.0, 3.0));
-        let s2 = (&Point::new(6.0, 3.0), &Point::new(9.0, 0.0));
+        let s1 = Segment::new(Point::new(2.0, 1.0), Point::new(6.0, 3.0));
+        let s2 = Segment::new(Point::new(6.0, 3.0), Point::new(9.0, 0.0));
 
-        let inter = intersection_point(s1, s2).unwrap();
+        let inter = intersection_point(&s1, &s2).unwrap();
         assert!(inter.is_close(&Point::new(6.0, 3.0)));
     }
 
     #[test]
     fn test_intersect_false() {
         // Parallel
-        let s1 = (&Point::new(0.0, 0.0), &Point::new(4.0, 4.0));
-        let s2 = (&Point::new(1.0, 0.0), &Point::new(5.0, 4.0));
-        if let Some(_) = intersection_point(s1, s2) {
+        let s1 = Segment::new(Point::new(0.0, 0.0), Point::new(4.0, 4.0));
+        let s2 = Segment::new(Point::new(1.0, 0.0), Point::new(5.0, 4.0));
+        if let Some(_) = intersection_point(&s1, &s2) {
             panic!("Parallel segments intersected!")
         }
 
         // Non intersecting
-        let s1 = (&Point::new(5.0, 1.0), &Point::new(7.0, 3.0));
-        let s2 = (&Point::new(2.0, 0.0), &Point::new(3.0, 2.0));
-        if let Some(_) = intersection_point(s1, s2) {
+        let s1 = Segment::new(Point::new(5.0, 1.0), Point::new(7.0, 3.0));
+        let s2 = Segment::new(Point::new(2.0, 0.0), Point::new(3.0, 2.0));
+        if let Some(_) = intersection_point(&s1, &s2) {
             panic!("Unexpected segment intersection!")
         }
     }
 
+    #[test]
+    fn test_segments_intersect() {
+        let seg = |a: &Point, b: &Point| Segment::new(a.clone(), b.clone());
+        let (a, b) = (Point::new(0.0, 0.0), Point::new(2.0, 2.0));
+        let (c, d) = (Point::new(0.0, 2.0), Point::new(2.0, 0.0));
+        assert!(segments_intersect(&seg(&a, &b), &seg(&c, &d)));
+
+        // Touching at an endpoint
+        let e = Point::new(3.0, 0.0);
+        assert!(segments_intersect(&seg(&c, &d), &seg(&d, &e)));
+
+        // Collinear, overlapping and disjoint
+        let (f, g) = (Point::new(1.0, 1.0), Point::new(3.0, 3.0));
+        let (h, i) = (Point::new(2.5, 2.5), Point::new(4.0, 4.0));
+        assert!(segments_intersect(&seg(&a, &b), &seg(&f, &g)));
+        assert!(!segments_intersect(&seg(&a, &b), &seg(&h, &i)));
+
+        // Parallel
+        assert!(!segments_intersect(
+            &seg(&a, &b),
+            &seg(&d, &Point::new(4.0, 2.0))
+        ));
+    }
+
     #[test]
     fn test_intersect_line() {
-        let line = (&Point::new(1.0, 3.0), &Point::new(3.0, 1.0));
-        let seg = (&Point::new(3.0, 0.0), &Point::new(4.0, 1.0));
+        let line = Segment::new(Point::new(1.0, 3.0), Point::new(3.0, 1.0));
+        let seg = Segment::new(Point::new(3.0, 0.0), Point::new(4.0, 1.0));
 
         let pt = Point::new(3.5, 0.5);
-        let inter = intersection_with_line(line, seg, false).unwrap();
+        let inter = intersection_with_line(&line, &seg, false).unwrap();
         assert!(inter.is_close(&pt));
 
-        if let Some(_) = intersection_with_line(line, seg, true) {
+        if let Some(_) = intersection_with_line(&line, &seg, true) {
             panic!("Intersected out of segment bounds!");
         }
     }
@@ -367,6 +493,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_clip_with_holes() {
+        let subject = Polygon::with_holes(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 4.0),
+                Point::new(4.0, 4.0),
+                Point::new(4.0, 0.0),
+                Point::new(0.0, 0.0),
+            ],
+            vec![
+                vec![
+                    Point::new(1.0, 1.0),
+                    Point::new(2.0, 1.0),
+                    Point::new(2.0, 2.0),
+                    Point::new(1.0, 2.0),
+                    Point::new(1.0, 1.0),
+                ],
+                vec![
+                    Point::new(3.0, 3.0),
+                    Point::new(3.5, 3.0),
+                    Point::new(3.5, 3.5),
+                    Point::new(3.0, 3.0),
+                ],
+            ],
+        )
+        .unwrap();
+        let clip = Polygon::new(vec![
+            Point::new(0.5, 0.5),
+            Point::new(0.5, 2.5),
+            Point::new(2.5, 2.5),
+            Point::new(2.5, 0.5),
+            Point::new(0.5, 0.5),
+        ])
+        .unwrap();
+
+        let clipped = clip_polygon(&subject, &clip).unwrap().unwrap();
+        assert_eq!(clipped.inner.len(), 1);
+        assert!(core::approx(clipped.area(), 3.0));
+    }
+
     #[test]
     fn test_clip_no_intersect() {
         // Unit Square
@@ -391,5 +558,16 @@ mod tests {
             None => (),
             _ => panic!("Computed intersection of non intersecting polygons"),
         };
+
+        // Polygons touching at a vertex
+        let poly3 = Polygon::new(vec![
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+        ])
+        .unwrap();
+        assert!(clip_polygon(&poly1, &poly3).unwrap().is_none());
     }
 }
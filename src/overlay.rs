@@ -0,0 +1,688 @@
+use super::points::*;
+use super::polygons::{Polygon, shoelace, winding_number};
+use std::collections::HashMap;
+
+/// Relative tolerance (with respect to the extent of the input) used to merge nodes of the
+/// planar graph built from the input rings.
+const NODE_RTOL: f64 = 1e-10;
+
+/// Segment of one of the input rings, or of a blade line if it has no ring
+struct RingSegment {
+    start: Point,
+    end: Point,
+    ring: Option<usize>,
+}
+
+/// Planar graph (arrangement) of the segments of a set of rings. All intersections between
+/// the segments are nodes of the graph, so edges only meet at their endpoints.
+///
+/// Each undirected edge `e` is stored as two half-edges: `2 * e` goes from the lower to the
+/// higher node index, `2 * e + 1` in the opposite direction.
+struct Arrangement {
+    nodes: Vec<Point>,
+    /// Node indices of each edge, lower index first
+    edges: Vec<(usize, usize)>,
+    /// Net number of ring segments running along each edge from its first to its second node
+    edge_delta: Vec<i32>,
+    /// Whether ring segments run along each edge, as opposed to blade segments only
+    edge_ring: Vec<bool>,
+    /// Outgoing half-edges of each node, sorted counter-clockwise by angle
+    outgoing: Vec<Vec<usize>>,
+    /// Position of each half-edge in its origin's `outgoing` list
+    position: Vec<usize>,
+    /// Node of the start of each ring
+    ring_nodes: Vec<Option<usize>>,
+}
+
+/// Compute the region of the plane where the winding number of the given rings is positive.
+///
+/// The rings must be closed (first and last points equal) but can have any orientation and
+/// intersect each other or themselves freely. Counter-clockwise rings add one to the winding
+/// number of the points they enclose, clockwise rings subtract one. The result is given as a
+/// set of non-overlapping polygons with holes.
+///
+/// This can be used to compute unions (all rings counter-clockwise), differences (rings to
+/// subtract clockwise) and to resolve self-intersecting rings.
+pub(crate) fn positive_region(rings: &[Vec<Point>]) -> Vec<Polygon> {
+    let arrangement = match Arrangement::build(rings, &[]) {
+        None => return Vec::new(),
+        Some(arr) => arr,
+    };
+    let (face_of, face_areas) = arrangement.faces();
+    let windings = arrangement.face_windings(rings, &face_of, &face_areas);
+
+    let inside = |he: usize| windings[face_of[he]] > 0;
+    let is_boundary = |he: usize| inside(he) && !inside(twin(he));
+
+    // Trace the boundary of the region: the half-edges with the region on the left only
+    let mut visited = vec![false; face_of.len()];
+    let mut shells = Vec::new();
+    let mut holes = Vec::new();
+    for start in 0..face_of.len() {
+        if visited[start] || !is_boundary(start) {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = start;
+        loop {
+            visited[current] = true;
+            ring.push(arrangement.nodes[arrangement.origin(current)].clone());
+
+            // Rotate around the next node until we find a boundary half-edge
+            let mut nxt = arrangement.next(current);
+            while !is_boundary(nxt) {
+                nxt = arrangement.next(twin(nxt));
+            }
+            current = nxt;
+            if current == start || visited[current] {
+                break;
+            }
+        }
+
+        let mut ring = remove_collinear(ring);
+        if ring.len() < 3 {
+            continue;
+        }
+        ring.push(ring[0].clone());
+        if shoelace(&ring) < 0.0 {
+            shells.push(ring);
+        } else {
+            holes.push(ring);
+        }
+    }
+
+    assemble_polygons(shells, holes)
+}
+
+/// Split the region where the winding number of the given rings is positive (see
+/// `positive_region`) along blade lines, into the faces they delimit.
+///
+/// Parts of the blades outside of the region, or which do not separate two faces (such as
+/// lines ending inside the region or joining its outer boundary to a hole), are ignored. The
+/// faces are given as non-overlapping polygons with holes, which cover the region.
+pub(crate) fn split_region(rings: &[Vec<Point>], blades: &[Vec<Point>]) -> Vec<Polygon> {
+    let mut arrangement = match Arrangement::build(rings, blades) {
+        None => return Vec::new(),
+        Some(arr) => arr,
+    };
+
+    // Remove the blade edges with the same face on both sides until there are none left:
+    // removing a bridge splits a boundary cycle in two, which may expose others
+    let (face_of, face_areas) = loop {
+        let (face_of, face_areas) = arrangement.faces();
+        let loose: Vec<bool> = (0..arrangement.edges.len())
+            .map(|e| !arrangement.edge_ring[e] && face_of[2 * e] == face_of[2 * e + 1])
+            .collect();
+        if !loose.contains(&true) {
+            break (face_of, face_areas);
+        }
+        arrangement.remove_edges(&loose);
+    };
+    let windings = arrangement.face_windings(rings, &face_of, &face_areas);
+
+    // Each boundary cycle of a face in the region is a shell if counter-clockwise, else a hole
+    let mut traced = vec![false; face_areas.len()];
+    let mut shells = Vec::new();
+    let mut holes = Vec::new();
+    for (start, &face) in face_of.iter().enumerate() {
+        if traced[face] || windings[face] <= 0 {
+            continue;
+        }
+        traced[face] = true;
+        let mut ring = Vec::new();
+        let mut current = start;
+        loop {
+            ring.push(arrangement.nodes[arrangement.origin(current)].clone());
+            current = arrangement.next(current);
+            if current == start {
+                break;
+            }
+        }
+
+        let mut ring = remove_collinear(ring);
+        if ring.len() < 3 {
+            continue;
+        }
+        ring.push(ring[0].clone());
+        if face_areas[face] > 0.0 {
+            shells.push(ring);
+        } else {
+            holes.push(ring);
+        }
+    }
+
+    assemble_polygons(shells, holes)
+}
+
+/// Assign each hole to the smallest shell that contains it and build the polygons.
+pub(crate) fn assemble_polygons(shells: Vec<Vec<Point>>, holes: Vec<Vec<Point>>) -> Vec<Polygon> {
+    let shell_areas: Vec<f64> = shells.iter().map(|s| shoelace(s).abs()).collect();
+    let mut shell_holes: Vec<Vec<Vec<Point>>> = shells.iter().map(|_| Vec::new()).collect();
+    for hole in holes {
+        // The midpoint of an edge of the hole can only lie on the boundary of a shell with the
+        // same outline, such as the face inside a closed blade, which is no larger than it
+        let (x1, y1) = hole[0].coords();
+        let (x2, y2) = hole[1].coords();
+        let sample = Point::new((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+        let hole_area = shoelace(&hole).abs();
+
+        let container = (0..shells.len())
+            .filter(|&i| shell_areas[i] - hole_area > NODE_RTOL * shell_areas[i])
+            .filter(|&i| winding_number(&shells[i], &sample) != 0)
+            .min_by(|&i, &j| shell_areas[i].total_cmp(&shell_areas[j]));
+        if let Some(idx) = container {
+            shell_holes[idx].push(hole);
+        }
+    }
+
+    shells
+        .into_iter()
+        .zip(shell_holes)
+        .filter_map(|(shell, holes)| Polygon::with_holes(shell, holes).ok())
+        .collect()
+}
+
+/// Remove vertices in the middle of straight runs of a ring (given without closing vertex)
+fn remove_collinear(ring: Vec<Point>) -> Vec<Point> {
+    let total = ring.len();
+    if total < 3 {
+        return ring;
+    }
+    let mut kept = Vec::with_capacity(total);
+    for i in 0..total {
+        let prev = &ring[(i + total - 1) % total];
+        let next = &ring[(i + 1) % total];
+        let pt = &ring[i];
+        let (px, py) = pt.coords();
+        let (ax, ay) = prev.coords();
+        let (bx, by) = next.coords();
+        let straight = direction(prev, pt, next) == Turn::InLine
+            && (ax - px) * (bx - px) + (ay - py) * (by - py) < 0.0;
+        if !straight {
+            kept.push(pt.clone());
+        }
+    }
+    kept
+}
+
+/// Opposite half-edge
+fn twin(he: usize) -> usize {
+    he ^ 1
+}
+
+impl Arrangement {
+    /// Build the arrangement of the segments of the given rings and blade lines. Returns None
+    /// if there are no (non-degenerate) segments.
+    fn build(rings: &[Vec<Point>], blades: &[Vec<Point>]) -> Option<Self> {
+        let mut segments = Vec::new();
+        let lines = rings
+            .iter()
+            .enumerate()
+            .map(|(idx, ring)| (Some(idx), ring))
+            .chain(blades.iter().map(|blade| (None, blade)));
+        for (ring, pts) in lines {
+            for (start, end) in pts.iter().zip(pts.iter().skip(1)) {
+                if start.coords() != end.coords() {
+                    segments.push(RingSegment {
+                        start: start.clone(),
+                        end: end.clone(),
+                        ring,
+                    });
+                }
+            }
+        }
+        if segments.is_empty() {
+            return None;
+        }
+
+        let eps = node_tolerance(&segments);
+        let splits = split_segments(&segments, eps);
+
+        // Merge split points into nodes and link consecutive ones with edges
+        let mut index = NodeIndex::new(eps);
+        let mut edge_ids: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut edges = Vec::new();
+        let mut edge_delta = Vec::new();
+        let mut edge_ring = Vec::new();
+        let mut ring_nodes = vec![None; rings.len()];
+        for (seg, pts) in segments.iter().zip(splits) {
+            let ids: Vec<usize> = pts.iter().map(|pt| index.node(pt)).collect();
+            if let Some(ring) = seg.ring
+                && ring_nodes[ring].is_none()
+            {
+                ring_nodes[ring] = Some(ids[0]);
+            }
+            for pair in ids.windows(2) {
+                let (u, v) = (pair[0], pair[1]);
+                if u == v {
+                    continue;
+                }
+                let key = (u.min(v), u.max(v));
+                let edge = *edge_ids.entry(key).or_insert_with(|| {
+                    edges.push(key);
+                    edge_delta.push(0);
+                    edge_ring.push(false);
+                    edges.len() - 1
+                });
+                if seg.ring.is_some() {
+                    edge_delta[edge] += if u < v { 1 } else { -1 };
+                    edge_ring[edge] = true;
+                }
+            }
+        }
+        if edges.is_empty() {
+            return None;
+        }
+
+        let nodes = index.nodes;
+        let mut arrangement = Self {
+            outgoing: vec![Vec::new(); nodes.len()],
+            position: vec![0; 2 * edges.len()],
+            nodes,
+            edges,
+            edge_delta,
+            edge_ring,
+            ring_nodes,
+        };
+        arrangement.sort_outgoing();
+        Some(arrangement)
+    }
+
+    /// Remove the flagged edges, keeping the nodes
+    fn remove_edges(&mut self, removed: &[bool]) {
+        let kept = |e: &usize| !removed[*e];
+        let total = self.edges.len();
+        self.edges = (0..total).filter(kept).map(|e| self.edges[e]).collect();
+        self.edge_delta = (0..total)
+            .filter(kept)
+            .map(|e| self.edge_delta[e])
+            .collect();
+        self.edge_ring = (0..total).filter(kept).map(|e| self.edge_ring[e]).collect();
+        self.outgoing = vec![Vec::new(); self.nodes.len()];
+        self.position = vec![0; 2 * self.edges.len()];
+        self.sort_outgoing();
+    }
+
+    /// Sort the outgoing half-edges of every node by angle
+    fn sort_outgoing(&mut self) {
+        for he in 0..2 * self.edges.len() {
+            let origin = self.origin(he);
+            self.outgoing[origin].push(he);
+        }
+        for node in 0..self.nodes.len() {
+            let (x, y) = self.nodes[node].coords();
+            let mut angles: Vec<(f64, usize)> = self.outgoing[node]
+                .iter()
+                .map(|&he| {
+                    let (dx, dy) = self.nodes[self.target(he)].coords();
+                    ((dy - y).atan2(dx - x), he)
+                })
+                .collect();
+            angles.sort_by(|a, b| a.0.total_cmp(&b.0));
+            self.outgoing[node] = angles.into_iter().map(|(_, he)| he).collect();
+            for (pos, &he) in self.outgoing[node].iter().enumerate() {
+                self.position[he] = pos;
+            }
+        }
+    }
+
+    /// Node at the start of a half-edge
+    fn origin(&self, he: usize) -> usize {
+        let (u, v) = self.edges[he / 2];
+        if he.is_multiple_of(2) { u } else { v }
+    }
+
+    /// Node at the end of a half-edge
+    fn target(&self, he: usize) -> usize {
+        self.origin(twin(he))
+    }
+
+    /// Net number of ring segments running along the half-edge's direction
+    fn delta(&self, he: usize) -> i32 {
+        let delta = self.edge_delta[he / 2];
+        if he.is_multiple_of(2) { delta } else { -delta }
+    }
+
+    /// Next half-edge along the boundary of the face to the left of the given one
+    fn next(&self, he: usize) -> usize {
+        let node = self.target(he);
+        let out = &self.outgoing[node];
+        out[(self.position[twin(he)] + out.len() - 1) % out.len()]
+    }
+
+    /// Identify the faces of the arrangement. Returns the face to the left of each half-edge
+    /// and the signed area (positive counter-clockwise) of each face's boundary cycle.
+    fn faces(&self) -> (Vec<usize>, Vec<f64>) {
+        let total = 2 * self.edges.len();
+        let mut face_of = vec![usize::MAX; total];
+        let mut areas = Vec::new();
+        for start in 0..total {
+            if face_of[start] != usize::MAX {
+                continue;
+            }
+            let face = areas.len();
+            let mut area = 0.0;
+            let mut he = start;
+            while face_of[he] == usize::MAX {
+                face_of[he] = face;
+                let (x1, y1) = self.nodes[self.origin(he)].coords();
+                let (x2, y2) = self.nodes[self.target(he)].coords();
+                area += x1 * y2 - x2 * y1;
+                he = self.next(he);
+            }
+            areas.push(area / 2.0);
+        }
+        (face_of, areas)
+    }
+
+    /// Compute the winding number of the rings for every face of the arrangement.
+    ///
+    /// Within a connected component, winding numbers are propagated across edges: the winding
+    /// number to the left of a half-edge exceeds the one to its right by the half-edge's delta.
+    /// The outer face of each component gets its winding number from the rings in other
+    /// components.
+    fn face_windings(&self, rings: &[Vec<Point>], face_of: &[usize], areas: &[f64]) -> Vec<i32> {
+        let components = self.components();
+
+        // Outer face of each component: the one with the most negative area
+        let mut outer: HashMap<usize, usize> = HashMap::new();
+        for (he, &face) in face_of.iter().enumerate() {
+            let comp = components[self.origin(he)];
+            let current = outer.entry(comp).or_insert(face);
+            if areas[face] < areas[*current] {
+                *current = face;
+            }
+        }
+
+        let mut face_edges: Vec<Vec<usize>> = vec![Vec::new(); areas.len()];
+        for (he, &face) in face_of.iter().enumerate() {
+            face_edges[face].push(he);
+        }
+
+        let mut windings: Vec<Option<i32>> = vec![None; areas.len()];
+        for (&comp, &face) in outer.iter() {
+            let sample = &self.nodes[self.origin(face_edges[face][0])];
+            let mut winding = 0;
+            for (ring, node) in rings.iter().zip(&self.ring_nodes) {
+                if let Some(node) = node
+                    && components[*node] != comp
+                {
+                    winding += winding_number(ring, sample);
+                }
+            }
+
+            windings[face] = Some(winding);
+            let mut pending = vec![face];
+            while let Some(current) = pending.pop() {
+                let value = windings[current].unwrap();
+                for &he in &face_edges[current] {
+                    let other = face_of[twin(he)];
+                    if windings[other].is_none() {
+                        windings[other] = Some(value - self.delta(he));
+                        pending.push(other);
+                    }
+                }
+            }
+        }
+        windings.into_iter().map(|w| w.unwrap_or(0)).collect()
+    }
+
+    /// Label the connected component of each node
+    fn components(&self) -> Vec<usize> {
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        fn find(parent: &mut [usize], mut node: usize) -> usize {
+            while parent[node] != node {
+                parent[node] = parent[parent[node]];
+                node = parent[node];
+            }
+            node
+        }
+        for &(u, v) in &self.edges {
+            let (ru, rv) = (find(&mut parent, u), find(&mut parent, v));
+            parent[ru] = rv;
+        }
+        (0..self.nodes.len())
+            .map(|node| find(&mut parent, node))
+            .collect()
+    }
+}
+
+/// Compute the tolerance to merge nodes from the extent of the segments
+fn node_tolerance(segments: &[RingSegment]) -> f64 {
+    let mut extent: f64 = 0.0;
+    for seg in segments {
+        for pt in [&seg.start, &seg.end] {
+            let (x, y) = pt.coords();
+            extent = extent.max(x.abs()).max(y.abs());
+        }
+    }
+    if extent == 0.0 {
+        NODE_RTOL
+    } else {
+        extent * NODE_RTOL
+    }
+}
+
+/// Compute the points where each segment must be split (including its endpoints), sorted
+/// along the segment. These are the intersections with other segments and the endpoints of
+/// other segments lying on it.
+fn split_segments(segments: &[RingSegment], eps: f64) -> Vec<Vec<Point>> {
+    let mut splits: Vec<Vec<(f64, Point)>> = segments
+        .iter()
+        .map(|seg| vec![(0.0, seg.start.clone()), (1.0, seg.end.clone())])
+        .collect();
+
+    // Sweep over the segments sorted by minimum x coordinate to prune the pairs to test
+    let bounds: Vec<(f64, f64, f64, f64)> = segments
+        .iter()
+        .map(|seg| {
+            let (x1, y1) = seg.start.coords();
+            let (x2, y2) = seg.end.coords();
+            (x1.min(x2), x1.max(x2), y1.min(y2), y1.max(y2))
+        })
+        .collect();
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|&a, &b| bounds[a].0.total_cmp(&bounds[b].0));
+
+    for (pos, &i) in order.iter().enumerate() {
+        for &j in &order[pos + 1..] {
+            if bounds[j].0 > bounds[i].1 + eps {
+                break;
+            }
+            if bounds[j].2 > bounds[i].3 + eps || bounds[i].2 > bounds[j].3 + eps {
+                continue;
+            }
+            intersect_segments(&segments[i], &segments[j], eps, &mut splits, (i, j));
+        }
+    }
+
+    splits
+        .into_iter()
+        .map(|mut pts| {
+            pts.sort_by(|a, b| a.0.total_cmp(&b.0));
+            pts.into_iter().map(|(_, pt)| pt).collect()
+        })
+        .collect()
+}
+
+/// Record the points where two segments must be split because they cross or touch
+fn intersect_segments(
+    s1: &RingSegment,
+    s2: &RingSegment,
+    eps: f64,
+    splits: &mut [Vec<(f64, Point)>],
+    (i, j): (usize, usize),
+) {
+    // Endpoints of each segment lying on the other
+    for (seg, other, idx) in [(s1, s2, i), (s2, s1, j)] {
+        for pt in [&other.start, &other.end] {
+            if let Some(t) = interior_parameter(seg, pt, eps) {
+                splits[idx].push((t, pt.clone()));
+            }
+        }
+    }
+
+    let (ax, ay) = s1.start.coords();
+    let (bx, by) = s1.end.coords();
+    let (cx, cy) = s2.start.coords();
+    let (dx, dy) = s2.end.coords();
+    let (rx, ry) = (bx - ax, by - ay);
+    let (sx, sy) = (dx - cx, dy - cy);
+    let denom = rx * sy - ry * sx;
+    let len1 = (rx * rx + ry * ry).sqrt();
+    let len2 = (sx * sx + sy * sy).sqrt();
+    if denom.abs() <= 1e-14 * len1 * len2 {
+        // Parallel segments: overlaps are handled through the endpoints
+        return;
+    }
+
+    let t = ((cx - ax) * sy - (cy - ay) * sx) / denom;
+    let u = ((cx - ax) * ry - (cy - ay) * rx) / denom;
+    let (slack1, slack2) = (eps / len1, eps / len2);
+    if t <= slack1 || t >= 1.0 - slack1 || u <= slack2 || u >= 1.0 - slack2 {
+        // Not a crossing in the interior of both segments
+        return;
+    }
+    let pt = Point::new(ax + t * rx, ay + t * ry);
+    splits[i].push((t, pt.clone()));
+    splits[j].push((u, pt));
+}
+
+/// If the point lies on the interior of the segment (within `eps`), return its parameter
+/// along the segment.
+fn interior_parameter(seg: &RingSegment, pt: &Point, eps: f64) -> Option<f64> {
+    let (ax, ay) = seg.start.coords();
+    let (bx, by) = seg.end.coords();
+    let (px, py) = pt.coords();
+    let (rx, ry) = (bx - ax, by - ay);
+    let len_sq = rx * rx + ry * ry;
+    let t = ((px - ax) * rx + (py - ay) * ry) / len_sq;
+    let slack = eps / len_sq.sqrt();
+    if t <= slack || t >= 1.0 - slack {
+        return None;
+    }
+    let dist = ((px - ax) * ry - (py - ay) * rx).abs() / len_sq.sqrt();
+    if dist <= eps { Some(t) } else { None }
+}
+
+/// Spatial hash to merge points closer than a tolerance into a single node
+struct NodeIndex {
+    eps: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    nodes: Vec<Point>,
+}
+
+impl NodeIndex {
+    fn new(eps: f64) -> Self {
+        Self {
+            eps,
+            cells: HashMap::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Get the index of the node at the given point, creating it if needed
+    fn node(&mut self, pt: &Point) -> usize {
+        let (x, y) = pt.coords();
+        let cell_size = 2.0 * self.eps;
+        let (cx, cy) = (
+            (x / cell_size).floor() as i64,
+            (y / cell_size).floor() as i64,
+        );
+        for i in cx - 1..=cx + 1 {
+            for j in cy - 1..=cy + 1 {
+                if let Some(ids) = self.cells.get(&(i, j)) {
+                    for &id in ids {
+                        if self.nodes[id].l2_distance(pt) <= self.eps {
+                            return id;
+                        }
+                    }
+                }
+            }
+        }
+        self.nodes.push(pt.clone());
+        let id = self.nodes.len() - 1;
+        self.cells.entry((cx, cy)).or_default().push(id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::super::test_utils::square;
+    use super::*;
+
+    fn total_area(polys: &[Polygon]) -> f64 {
+        polys.iter().map(|p| p.area()).sum()
+    }
+
+    #[test]
+    fn test_union_overlapping() {
+        let polys = positive_region(&[square(0.0, 0.0, 2.0), square(1.0, 1.0, 2.0)]);
+        assert_eq!(polys.len(), 1);
+        assert!(core::approx(total_area(&polys), 7.0));
+        assert_eq!(polys[0].outer.len(), 9);
+        assert!(polys[0].inner.is_empty());
+    }
+
+    #[test]
+    fn test_union_disjoint_and_touching() {
+        let polys = positive_region(&[square(0.0, 0.0, 1.0), square(5.0, 5.0, 1.0)]);
+        assert_eq!(polys.len(), 2);
+        assert!(core::approx(total_area(&polys), 2.0));
+
+        // Sharing an edge
+        let polys = positive_region(&[square(0.0, 0.0, 1.0), square(1.0, 0.0, 1.0)]);
+        assert_eq!(polys.len(), 1);
+        assert_eq!(polys[0].outer.len(), 5);
+        assert!(core::approx(total_area(&polys), 2.0));
+    }
+
+    #[test]
+    fn test_difference() {
+        // Square with a clockwise square inside: a polygon with a hole
+        let mut inner = square(1.0, 1.0, 1.0);
+        inner.reverse();
+        let polys = positive_region(&[square(0.0, 0.0, 3.0), inner]);
+        assert_eq!(polys.len(), 1);
+        assert_eq!(polys[0].inner.len(), 1);
+        assert!(core::approx(total_area(&polys), 8.0));
+
+        // Cutting a square in two
+        let mut band = vec![
+            Point::new(1.0, -1.0),
+            Point::new(2.0, -1.0),
+            Point::new(2.0, 4.0),
+            Point::new(1.0, 4.0),
+            Point::new(1.0, -1.0),
+        ];
+        band.reverse();
+        let polys = positive_region(&[square(0.0, 0.0, 3.0), band]);
+        assert_eq!(polys.len(), 2);
+        assert!(core::approx(total_area(&polys), 6.0));
+    }
+
+    #[test]
+    fn test_self_intersecting_ring() {
+        // Bow-tie with both lobes wound in opposite directions
+        let bowtie = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 0.0),
+        ];
+        let polys = positive_region(&[bowtie]);
+        assert_eq!(polys.len(), 1);
+        assert!(core::approx(total_area(&polys), 1.0));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(positive_region(&[]).is_empty());
+        let mut cw = square(0.0, 0.0, 1.0);
+        cw.reverse();
+        assert!(positive_region(&[cw]).is_empty());
+    }
+}
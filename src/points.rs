@@ -1,4 +1,5 @@
-use super::core::{self, GeometricObject, display_for_geom};
+use super::core::{GeometricObject, Tolerance, display_for_geom};
+use super::predicates::{incircle, orient2d};
 
 /// A single Point on the Plane (2D)
 ///
@@ -15,7 +16,7 @@ pub struct Point {
 }
 
 /// A simple collection of points
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct MultiPoint {
     pub points: Vec<Point>,
 }
@@ -52,9 +53,28 @@ impl Point {
         (dx * dx + dy * dy).sqrt()
     }
 
-    /// Return true if the point is approximately equal to other.
+    /// Return the L2 distance to the closest point of the segment between `start` and `end`
+    pub fn distance_to_segment(&self, start: &Point, end: &Point) -> f64 {
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+        let len_sq = dx * dx + dy * dy;
+        if len_sq == 0.0 {
+            return self.l2_distance(start);
+        }
+
+        // Parameter of the projection onto the segment's line, clamped to the segment
+        let t = (((self.x - start.x) * dx + (self.y - start.y) * dy) / len_sq).clamp(0.0, 1.0);
+        self.l2_distance(&Point::new(start.x + t * dx, start.y + t * dy))
+    }
+
+    /// Return true if the point is approximately equal to other, with the current tolerance.
     pub fn is_close(&self, other: &Point) -> bool {
-        core::approx(self.x, other.x) && core::approx(self.y, other.y)
+        self.is_close_with(other, &Tolerance::current())
+    }
+
+    /// Return true if the point is approximately equal to other, with the given tolerance.
+    pub fn is_close_with(&self, other: &Point, tolerance: &Tolerance) -> bool {
+        tolerance.is_close(self.x, other.x) && tolerance.is_close(self.y, other.y)
     }
 
     /// Get coordinates as a tuple
@@ -110,12 +130,23 @@ impl GeometricObject for MultiPoint {
 
 display_for_geom!(MultiPoint);
 
-/// Determine the turn direction defined by three successive points
+/// Determine the turn direction defined by three successive points, with the current tolerance
 pub fn direction(p1: &Point, p2: &Point, p3: &Point) -> Turn {
-    let det = (p2.x * p3.y) - (p2.y * p3.x) - (p1.x * p3.y) + (p1.y * p3.x) + (p1.x * p2.y)
-        - (p1.y * p2.x);
+    direction_with(p1, p2, p3, &Tolerance::current())
+}
 
-    if core::approx(det, 0.0) {
+/// Determine the turn direction defined by three successive points, with the given tolerance
+///
+/// The turn is computed with the robust `orient2d` predicate. Points are considered in line
+/// when the height of their triangle is within the tolerance, relative to its longest side.
+pub fn direction_with(p1: &Point, p2: &Point, p3: &Point, tolerance: &Tolerance) -> Turn {
+    let det = orient2d(p1, p2, p3);
+    let longest = [(p1, p2), (p2, p3), (p3, p1)]
+        .iter()
+        .map(|(a, b)| a.l2_distance(b))
+        .fold(0.0, f64::max);
+
+    if det == 0.0 || det.abs() / longest < tolerance.atol + tolerance.rtol * longest {
         Turn::InLine
     } else if det < 0.0 {
         Turn::Right
@@ -124,6 +155,29 @@ pub fn direction(p1: &Point, p2: &Point, p3: &Point) -> Turn {
     }
 }
 
+/// Determine whether `pt` lies strictly inside the circle through three counter-clockwise points
+///
+/// The test is exact: cocircular points are considered outside of the circle.
+pub fn in_circle(a: &Point, b: &Point, c: &Point, pt: &Point) -> bool {
+    incircle(a, b, c, pt) > 0.0
+}
+
+/// Compute the center of the circle going through three points, if they are not collinear
+pub fn circumcenter(a: &Point, b: &Point, c: &Point) -> Option<Point> {
+    if direction(a, b, c) == Turn::InLine {
+        return None;
+    }
+    // Relative to `a`, for precision
+    let (bx, by) = (b.x - a.x, b.y - a.y);
+    let (cx, cy) = (c.x - a.x, c.y - a.y);
+    let det = 2.0 * (bx * cy - by * cx);
+    let (blift, clift) = (bx * bx + by * by, cx * cx + cy * cy);
+    Some(Point::new(
+        a.x + (cy * blift - by * clift) / det,
+        a.y + (bx * clift - cx * blift) / det,
+    ))
+}
+
 /// Sort a vector of points lexicographically
 pub fn sort_lex(mut pts: Vec<Point>) -> Vec<Point> {
     quick_sort(&mut pts);
@@ -159,8 +213,10 @@ pub fn quick_sort(pts: &mut [Point]) {
 }
 
 #[cfg(test)]
+#[allow(clippy::needless_range_loop, clippy::useless_vec)]
 mod tests {
 
+    use super::super::core;
     use super::*;
     use rand::rng;
     use rand::seq::SliceRandom;
@@ -196,6 +252,99 @@ mod tests {
         assert_eq!(direction(&p1, &p2, &p4), Turn::InLine);
     }
 
+    #[test]
+    fn test_direction_large_coordinates() {
+        // UTM-like coordinates: a 10 cm offset over 10 m is a clear turn
+        let p1 = Point::new(500000.0, 5000000.0);
+        let p2 = Point::new(500010.0, 5000000.0);
+        assert_eq!(
+            direction(&p1, &p2, &Point::new(500020.0, 5000000.1)),
+            Turn::Left
+        );
+        assert_eq!(
+            direction(&p1, &p2, &Point::new(500020.0, 4999999.9)),
+            Turn::Right
+        );
+        assert_eq!(
+            direction(&p1, &p2, &Point::new(500020.0, 5000000.0)),
+            Turn::InLine
+        );
+
+        // The result doesn't depend on where the points are
+        let shift = |pt: &Point| Point::new(pt.x - 500000.0, pt.y - 5000000.0);
+        let p3 = Point::new(500003.7, 5000001.3);
+        assert_eq!(
+            direction(&p1, &p2, &p3),
+            direction(&shift(&p1), &shift(&p2), &shift(&p3))
+        );
+    }
+
+    #[test]
+    fn test_direction_tolerance() {
+        let p1 = Point::new(0.0, 0.0);
+        let p2 = Point::new(1.0, 0.0);
+        let p3 = Point::new(2.0, 1e-8);
+        assert_eq!(direction(&p1, &p2, &p3), Turn::Left);
+
+        let coarse = Tolerance::new(1e-6, 0.0).unwrap();
+        assert_eq!(direction_with(&p1, &p2, &p3, &coarse), Turn::InLine);
+        assert_eq!(coarse.scope(|| direction(&p1, &p2, &p3)), Turn::InLine);
+    }
+
+    #[test]
+    fn test_direction_inline_criterion() {
+        // Points are in line when the height of their triangle is below atol + rtol * longest
+        // side, here 1e-12 + 2e-9. Before tolerances were configurable, the criterion was
+        // det / longest^2 < 1e-12, under which this turn was a clear Left.
+        let p1 = Point::new(0.0, 0.0);
+        let p2 = Point::new(1.0, 0.0);
+        let p3 = Point::new(2.0, 1e-11);
+        assert_eq!(direction(&p1, &p2, &p3), Turn::InLine);
+
+        // Just above the threshold
+        let p4 = Point::new(2.0, 5e-9);
+        assert_eq!(direction(&p1, &p2, &p4), Turn::Left);
+
+        // The absolute tolerance alone decides for short sides
+        let tight = Tolerance::new(0.0, 1e-12).unwrap();
+        assert_eq!(direction_with(&p1, &p2, &p3, &tight), Turn::Left);
+    }
+
+    #[test]
+    fn test_in_circle() {
+        let a = Point::new(0.0, 0.0);
+        let b = Point::new(1.0, 0.0);
+        let c = Point::new(0.0, 1.0);
+
+        assert!(in_circle(&a, &b, &c, &Point::new(0.5, 0.5)));
+        assert!(in_circle(&a, &b, &c, &Point::new(0.9, 0.9)));
+        assert!(!in_circle(&a, &b, &c, &Point::new(1.1, 1.1)));
+
+        // Cocircular point
+        assert!(!in_circle(&a, &b, &c, &Point::new(1.0, 1.0)));
+
+        // Far from the origin, the smallest step inside the circle is detected
+        let offset = Point::new(1048576.0, 1048576.0);
+        let shift = |pt: &Point| Point::new(pt.x + offset.x, pt.y + offset.y);
+        let step = offset.x.next_up() - offset.x;
+        let (a, b, c) = (shift(&a), shift(&b), shift(&c));
+        assert!(!in_circle(&a, &b, &c, &shift(&Point::new(1.0, 1.0))));
+        assert!(in_circle(&a, &b, &c, &shift(&Point::new(1.0 - step, 1.0))));
+    }
+
+    #[test]
+    fn test_circumcenter() {
+        let a = Point::new(0.0, 0.0);
+        let b = Point::new(2.0, 0.0);
+        let c = Point::new(0.0, 2.0);
+        assert!(
+            circumcenter(&a, &b, &c)
+                .unwrap()
+                .is_close(&Point::new(1.0, 1.0))
+        );
+        assert!(circumcenter(&a, &b, &Point::new(4.0, 0.0)).is_none());
+    }
+
     #[test]
     fn test_close_pts() {
         let p1 = Point::new(20.0, 20.0);
@@ -244,6 +393,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_distance_to_segment() {
+        let start = Point::new(0.0, 0.0);
+        let end = Point::new(2.0, 0.0);
+
+        assert!(core::approx(
+            Point::new(1.0, 1.0).distance_to_segment(&start, &end),
+            1.0
+        ));
+        assert!(core::approx(
+            Point::new(3.0, 0.0).distance_to_segment(&start, &end),
+            1.0
+        ));
+        assert!(core::approx(
+            Point::new(-3.0, 4.0).distance_to_segment(&start, &end),
+            5.0
+        ));
+        assert!(core::approx(
+            Point::new(1.0, 1.0).distance_to_segment(&start, &start),
+            2.0_f64.sqrt()
+        ));
+    }
+
     #[test]
     fn test_rotate() {
         let original = Point::new(0.0, 0.0);
@@ -1,13 +1,20 @@
-use super::core::{GeomResult, GeometricObject, GeometryError, display_for_geom};
+use super::core::{GeomResult, GeometricObject, GeometryError, Tolerance, display_for_geom};
+use super::linestring::{densify_points, resample_points};
 use super::points::*;
 use std::iter::Zip;
 use std::slice::Iter;
 
 /// Represents a polygon on the Plane
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Polygon {
     pub outer: Vec<Point>,
-    // TODO -  add inner rings
+    pub inner: Vec<Vec<Point>>,
+}
+
+/// A collection of polygons
+#[derive(Clone, Debug)]
+pub struct MultiPolygon {
+    pub polygons: Vec<Polygon>,
 }
 
 /// Represents the orientation of a Polygon's vertices.
@@ -20,47 +27,72 @@ pub enum Orientation {
 impl Polygon {
     /// Instantiate a polygon from a vector of points
     pub fn new(pts: Vec<Point>) -> GeomResult<Self> {
-        if pts.len() < 4 {
-            return Err(GeometryError::ParameterError(format!(
-                "Too few points to create a polygon: {}!",
-                pts.len() - 1
-            )));
-        } else if !pts[0].is_close(&pts[pts.len() - 1]) {
-            return Err(GeometryError::ParameterError(format!(
-                "To make polygon, the first and last points must match! got {:?} and {:?}",
-                pts[0].coords(),
-                pts[pts.len() - 1].coords(),
-            )));
+        Self::new_with(pts, &Tolerance::current())
+    }
+
+    /// Instantiate a polygon from a vector of points, checking that the ring is closed with the
+    /// given tolerance
+    pub fn new_with(pts: Vec<Point>, tolerance: &Tolerance) -> GeomResult<Self> {
+        check_ring(&pts, tolerance)?;
+        Ok(Self {
+            outer: pts,
+            inner: Vec::new(),
+        })
+    }
+
+    /// Instantiate a polygon from its outer ring and a vector of inner rings (holes)
+    ///
+    /// Example
+    /// ```rust
+    /// use geomlib::{GeometricObject, Point, Polygon};
+    /// let square = vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(0.0, 3.0),
+    ///     Point::new(3.0, 3.0),
+    ///     Point::new(3.0, 0.0),
+    ///     Point::new(0.0, 0.0),
+    /// ];
+    /// let hole = vec![
+    ///     Point::new(1.0, 1.0),
+    ///     Point::new(2.0, 1.0),
+    ///     Point::new(2.0, 2.0),
+    ///     Point::new(1.0, 2.0),
+    ///     Point::new(1.0, 1.0),
+    /// ];
+    /// let poly = Polygon::with_holes(square, vec![hole]).unwrap();
+    /// assert_eq!(poly.area(), 8.0);
+    /// ```
+    pub fn with_holes(outer: Vec<Point>, inner: Vec<Vec<Point>>) -> GeomResult<Self> {
+        Self::with_holes_with(outer, inner, &Tolerance::current())
+    }
+
+    /// Instantiate a polygon from its outer ring and a vector of inner rings (holes), checking
+    /// that the rings are closed with the given tolerance
+    pub fn with_holes_with(
+        outer: Vec<Point>,
+        inner: Vec<Vec<Point>>,
+        tolerance: &Tolerance,
+    ) -> GeomResult<Self> {
+        check_ring(&outer, tolerance)?;
+        for ring in &inner {
+            check_ring(ring, tolerance)?;
         }
-        Ok(Self { outer: pts })
+        Ok(Self { outer, inner })
     }
 
-    /// Use Ray Tracing to determine if a point lies in the polygon
+    /// Determine if a point lies in the polygon (or on its boundary).
+    ///
+    /// Uses the winding number of the outer ring around the point. Points inside a hole are
+    /// not contained in the polygon, but points on the boundary of a hole are.
+    ///
+    /// Every point on the boundary, whichever edge it lies on, is contained (with the current
+    /// tolerance).
     pub fn contains(&self, pt: &Point) -> bool {
-        let mut total_intersects: u32 = 0;
-        let (p_x, p_y) = pt.coords();
-        for seg_start in 0..self.outer.len() {
-            let seg_end = (seg_start + 1) % self.outer.len();
-            let (st_x, st_y) = self.outer[seg_start].coords();
-            let (e_x, e_y) = self.outer[seg_end].coords();
-
-            if st_x < p_x && e_x < p_x {
-                // Horizontal ray does not intersect edge
-                continue;
-            } else if pt.is_close(&self.outer[seg_end]) || pt.is_close(&self.outer[seg_start]) {
-                // Edge case - point is vertex
-                return true;
-            } else if p_y == st_y && p_y == e_y {
-                // Edge case - horizontal edge lies on ray
-                if st_x <= p_x && p_x <= e_x {
-                    return true;
-                }
-            } else if (p_y - st_y) * (p_y - e_y) < 0.0 {
-                // Intersects edge
-                total_intersects += 1;
-            }
+        if self.rings().any(|ring| on_ring(ring, pt)) {
+            return true;
         }
-        total_intersects % 2 != 0
+        winding_number(&self.outer, pt) != 0
+            && self.inner.iter().all(|ring| winding_number(ring, pt) == 0)
     }
 
     /// Determine if the polygon is convex (that is, all "turns") are in the same
@@ -85,63 +117,215 @@ impl Polygon {
         true
     }
 
-    /// Compute the "shoelace" sum over the polygon's edges. This is twice the oriented area of the
-    /// polygon.
-    fn shoelace(&self) -> f64 {
-        let mut val = 0.0;
-        for (pt, nxt) in self.edges() {
-            let (p1, p2) = pt.coords();
-            let (q1, q2) = nxt.coords();
-            val += (q1 - p1) * (q2 + p2);
-        }
-        val
-    }
-
-    /// Determine the orientation of the polygon's vertices with the shoelace method.
+    /// Determine the orientation of the polygon's (outer ring) vertices with the shoelace method.
     pub fn orientation(&self) -> Orientation {
-        if self.shoelace() > 0.0 {
+        if shoelace(&self.outer) > 0.0 {
             Orientation::Clockwise
         } else {
             Orientation::CounterClockwise
         }
     }
 
-    /// Reverse the polygon's vertices' orientation.
+    /// Reverse the polygon's vertices' orientation (for all rings).
     pub fn reverse_orientation(&mut self) {
         self.outer.reverse();
+        for ring in self.inner.iter_mut() {
+            ring.reverse();
+        }
     }
 
-    /// Returns an iterator over the edges of the polygon
+    /// Returns an iterator over the edges of the polygon's outer ring
     pub fn edges<'a>(&'a self) -> Zip<Iter<'a, Point>, Iter<'a, Point>> {
-        return self.outer.iter().zip(&self.outer[1..]);
+        self.outer.iter().zip(&self.outer[1..])
+    }
+
+    /// Returns an iterator over the rings of the polygon: the outer ring followed by the holes
+    pub fn rings(&self) -> impl Iterator<Item = &Vec<Point>> {
+        std::iter::once(&self.outer).chain(self.inner.iter())
+    }
+
+    /// Insert vertices in all the rings so that no edge is longer than `max_segment_length`,
+    /// see `LineString::densify`
+    pub fn densify(&self, max_segment_length: f64) -> GeomResult<Polygon> {
+        self.map_rings(|ring| densify_points(ring, max_segment_length))
+    }
+
+    /// Replace the vertices of each ring by `n_points` vertices evenly spaced along it, the
+    /// closing vertex included, see `LineString::resample`. Rings need at least 4 vertices.
+    pub fn resample(&self, n_points: usize) -> GeomResult<Polygon> {
+        self.map_rings(|ring| resample_points(ring, n_points))
+    }
+
+    fn map_rings(
+        &self,
+        mut f: impl FnMut(&[Point]) -> GeomResult<Vec<Point>>,
+    ) -> GeomResult<Polygon> {
+        let outer = f(&self.outer)?;
+        let inner = self
+            .inner
+            .iter()
+            .map(|ring| f(ring))
+            .collect::<GeomResult<Vec<Vec<Point>>>>()?;
+        Polygon::with_holes(outer, inner)
+    }
+}
+
+impl MultiPolygon {
+    /// Instantiate a collection of polygons
+    pub fn new(polygons: Vec<Polygon>) -> Self {
+        Self { polygons }
+    }
+
+    /// Determine if a point lies in any of the polygons
+    pub fn contains(&self, pt: &Point) -> bool {
+        self.polygons.iter().any(|poly| poly.contains(pt))
+    }
+}
+
+/// Verify that a sequence of points is a valid polygon ring, closed with the given tolerance
+fn check_ring(pts: &[Point], tolerance: &Tolerance) -> GeomResult<()> {
+    if pts.len() < 4 {
+        Err(GeometryError::ParameterError(format!(
+            "Too few points to create a polygon: {}!",
+            pts.len().saturating_sub(1)
+        )))
+    } else if !pts[0].is_close_with(&pts[pts.len() - 1], tolerance) {
+        Err(GeometryError::ParameterError(format!(
+            "To make polygon, the first and last points must match! got {:?} and {:?}",
+            pts[0].coords(),
+            pts[pts.len() - 1].coords(),
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Compute the "shoelace" sum over a closed ring's edges. This is twice the oriented area of the
+/// ring, positive for clockwise rings.
+pub(crate) fn shoelace(ring: &[Point]) -> f64 {
+    let mut val = 0.0;
+    for (pt, nxt) in ring.iter().zip(&ring[1..]) {
+        let (p1, p2) = pt.coords();
+        let (q1, q2) = nxt.coords();
+        val += (q1 - p1) * (q2 + p2);
+    }
+    val
+}
+
+/// Centroid of the area enclosed by a closed ring, if it is not degenerate
+pub(crate) fn ring_centroid(ring: &[Point]) -> Option<Point> {
+    let (ox, oy) = ring.first()?.coords();
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for (start, end) in ring.iter().zip(&ring[1..]) {
+        // Relative to the first vertex, for precision
+        let (x1, y1) = (start.coords().0 - ox, start.coords().1 - oy);
+        let (x2, y2) = (end.coords().0 - ox, end.coords().1 - oy);
+        let cross = x1 * y2 - x2 * y1;
+        area += cross;
+        cx += (x1 + x2) * cross;
+        cy += (y1 + y2) * cross;
+    }
+    if area == 0.0 {
+        return None;
+    }
+    Some(Point::new(ox + cx / (3.0 * area), oy + cy / (3.0 * area)))
+}
+
+/// Compute the winding number of a closed ring around a point.
+///
+/// The result is positive for counter-clockwise rings that wind around the point, negative for
+/// clockwise ones and zero if the point lies outside the ring. The result is unspecified for
+/// points on the ring itself.
+pub(crate) fn winding_number(ring: &[Point], pt: &Point) -> i32 {
+    let (_, p_y) = pt.coords();
+    let mut winding = 0;
+    for (start, end) in ring.iter().zip(&ring[1..]) {
+        let (_, st_y) = start.coords();
+        let (_, e_y) = end.coords();
+        if st_y <= p_y {
+            if e_y > p_y && direction(start, end, pt) == Turn::Left {
+                // Upward crossing with the point to the left of the edge
+                winding += 1;
+            }
+        } else if e_y <= p_y && direction(start, end, pt) == Turn::Right {
+            // Downward crossing with the point to the right of the edge
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Determine whether a point lies on the boundary of a closed ring
+pub(crate) fn on_ring(ring: &[Point], pt: &Point) -> bool {
+    let (x, y) = pt.coords();
+    ring.iter().zip(&ring[1..]).any(|(start, end)| {
+        let (x1, y1) = start.coords();
+        let (x2, y2) = end.coords();
+        let in_box = x1.min(x2) <= x && x <= x1.max(x2) && y1.min(y2) <= y && y <= y1.max(y2);
+        pt.is_close(start) || (in_box && direction(start, end, pt) == Turn::InLine)
+    })
+}
+
+impl Polygon {
+    /// WKT representation of the polygon's rings, without the type prefix
+    fn wkt_rings(&self) -> String {
+        let rings: Vec<String> = self
+            .rings()
+            .map(|ring| {
+                let coords: Vec<String> = ring
+                    .iter()
+                    .map(|pt| {
+                        let (x, y) = pt.coords();
+                        format!("{} {}", x, y)
+                    })
+                    .collect();
+                format!("({})", coords.join(", "))
+            })
+            .collect();
+        format!("({})", rings.join(", "))
     }
 }
 
 impl GeometricObject for Polygon {
     /// WKT representation of the polygon
     fn wkt(&self) -> String {
-        let mut outer_ring = String::new();
-        for pt in &self.outer {
-            let (x, y) = pt.coords();
-            outer_ring.push_str(&format!("{} {}, ", x, y));
-        }
-        let stripped = outer_ring.strip_suffix(", ").unwrap();
-
-        format!("POLYGON(({}))", stripped)
+        format!("POLYGON{}", self.wkt_rings())
     }
 
-    /// Compute the area of the polygon using the "Shoelace" sum method.
+    /// Compute the area of the polygon using the "Shoelace" sum method. The area of the holes
+    /// is subtracted from that of the outer ring.
     fn area(&self) -> f64 {
-        self.shoelace().abs() / 2.0
+        let holes: f64 = self.inner.iter().map(|ring| shoelace(ring).abs()).sum();
+        (shoelace(&self.outer).abs() - holes) / 2.0
     }
 }
 
 display_for_geom!(Polygon);
 
+impl GeometricObject for MultiPolygon {
+    /// WKT representation of the multipolygon
+    fn wkt(&self) -> String {
+        if self.polygons.is_empty() {
+            return String::from("MULTIPOLYGON EMPTY");
+        }
+        let polys: Vec<String> = self.polygons.iter().map(|p| p.wkt_rings()).collect();
+        format!("MULTIPOLYGON({})", polys.join(", "))
+    }
+
+    /// Total area of the polygons in the collection
+    fn area(&self) -> f64 {
+        self.polygons.iter().map(|p| p.area()).sum()
+    }
+}
+
+display_for_geom!(MultiPolygon);
+
 #[cfg(test)]
+#[allow(clippy::redundant_pattern_matching)]
 mod tests {
 
-    use super::super::{convex_hull, core};
+    use super::super::convex_hull;
+    use super::super::core::{self, Tolerance};
     use super::*;
     use rand::{Rng, rng};
 
@@ -201,6 +385,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_closure_tolerance() {
+        // Ring closed up to a gap which is only negligible at a coarser tolerance
+        let ring = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1e-7, 0.0),
+        ];
+        assert!(Polygon::new(ring.clone()).is_err());
+        let coarse = Tolerance::for_extent(1e6).unwrap();
+        assert!(Polygon::new_with(ring.clone(), &coarse).is_ok());
+        assert!(Polygon::with_holes_with(ring.clone(), vec![ring.clone()], &coarse).is_ok());
+        assert!(Polygon::with_holes(ring.clone(), vec![ring.clone()]).is_err());
+        assert!(coarse.scope(|| Polygon::new(ring)).is_ok());
+    }
+
+    #[test]
+    fn test_densify_resample() {
+        let square = |lo: f64, hi: f64| {
+            vec![
+                Point::new(lo, lo),
+                Point::new(lo, hi),
+                Point::new(hi, hi),
+                Point::new(hi, lo),
+                Point::new(lo, lo),
+            ]
+        };
+        let poly = Polygon::with_holes(square(0.0, 4.0), vec![square(1.0, 2.0)]).unwrap();
+
+        let dense = poly.densify(0.5).unwrap();
+        assert_eq!(dense.outer.len(), 33);
+        assert_eq!(dense.inner[0].len(), 9);
+        assert!(core::approx(dense.area(), poly.area()));
+        assert!(poly.densify(-1.0).is_err());
+
+        // Resampling the corners of the squares
+        let even = poly.resample(5).unwrap();
+        assert!(
+            even.outer
+                .iter()
+                .zip(&poly.outer)
+                .all(|(a, b)| a.is_close(b))
+        );
+        assert!(
+            even.inner[0]
+                .iter()
+                .zip(&poly.inner[0])
+                .all(|(a, b)| a.is_close(b))
+        );
+        assert_eq!(poly.resample(9).unwrap().outer.len(), 9);
+        assert!(poly.resample(3).is_err());
+    }
+
     #[test]
     fn test_contains() {
         let poly = Polygon::new(vec![
@@ -341,6 +579,102 @@ mod tests {
         assert_eq!(Orientation::CounterClockwise, poly2.orientation());
     }
 
+    #[test]
+    fn test_contains_non_convex() {
+        // "U" shape
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 3.0),
+            Point::new(2.0, 3.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 3.0),
+            Point::new(0.0, 3.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+
+        assert!(poly.contains(&Point::new(0.5, 2.0)));
+        assert!(poly.contains(&Point::new(2.5, 2.0)));
+        assert!(poly.contains(&Point::new(1.5, 0.5)));
+        assert!(poly.contains(&Point::new(1.5, 1.0)));
+        assert!(!poly.contains(&Point::new(1.5, 2.0)));
+        assert!(!poly.contains(&Point::new(-0.5, 2.0)));
+    }
+
+    #[test]
+    fn test_contains_boundary() {
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+
+        // Midpoints of the left, bottom, right and top edges, and points just outside of them
+        let boundary = [
+            (Point::new(0.0, 0.5), Point::new(-1e-6, 0.5)),
+            (Point::new(0.5, 0.0), Point::new(0.5, -1e-6)),
+            (Point::new(1.0, 0.5), Point::new(1.0 + 1e-6, 0.5)),
+            (Point::new(0.5, 1.0), Point::new(0.5, 1.0 + 1e-6)),
+        ];
+        for (on_edge, outside) in &boundary {
+            assert!(poly.contains(on_edge));
+            assert!(!poly.contains(outside));
+        }
+        assert!(poly.outer.iter().all(|vertex| poly.contains(vertex)));
+    }
+
+    #[test]
+    fn test_polygon_with_holes() {
+        let outer = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 4.0),
+            Point::new(4.0, 4.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 0.0),
+        ];
+        let hole = vec![
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(1.0, 2.0),
+            Point::new(1.0, 1.0),
+        ];
+        let poly = Polygon::with_holes(outer.clone(), vec![hole]).unwrap();
+
+        assert!(core::approx(poly.area(), 15.0));
+        assert!(!poly.contains(&Point::new(1.5, 1.5)));
+        assert!(poly.contains(&Point::new(1.0, 1.5)));
+        assert!(poly.contains(&Point::new(3.0, 3.0)));
+        assert_eq!(poly.rings().count(), 2);
+        assert_eq!(
+            poly.wkt(),
+            "POLYGON((0 0, 0 4, 4 4, 4 0, 0 0), (1 1, 2 1, 2 2, 1 2, 1 1))"
+        );
+
+        let open_hole = vec![
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(1.0, 2.0),
+        ];
+        assert!(Polygon::with_holes(outer, vec![open_hole]).is_err());
+    }
+
+    #[test]
+    fn test_multipolygon() {
+        let mp = MultiPolygon::new(vec![random_polygon(20), random_polygon(30)]);
+        let total = mp.polygons[0].area() + mp.polygons[1].area();
+        assert!(core::approx(mp.area(), total));
+        assert!(mp.wkt().starts_with("MULTIPOLYGON((("));
+        assert!(mp.contains(&mp.polygons[1].outer[0]));
+        assert_eq!(MultiPolygon::new(Vec::new()).wkt(), "MULTIPOLYGON EMPTY");
+    }
+
     #[test]
     fn test_reverse_orientation() {
         let mut poly = random_polygon(64);
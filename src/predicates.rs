@@ -0,0 +1,285 @@
+//! Robust geometric predicates, after Shewchuk's "Adaptive Precision Floating-Point Arithmetic
+//! and Fast Robust Geometric Predicates".
+//!
+//! Each predicate first evaluates its determinant in plain floating-point arithmetic together
+//! with a bound on the rounding error. Only when the result is too close to zero to trust its
+//! sign, the determinant is evaluated again exactly, with floating-point expansions: sums of
+//! non-overlapping doubles sorted by increasing magnitude.
+
+use super::points::Point;
+
+/// Machine epsilon as used by Shewchuk: half of the distance between 1 and the next double
+const EPSILON: f64 = f64::EPSILON / 2.0;
+/// Relative error bound of the plain evaluation of `orient2d`
+const CCW_ERR_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+/// Relative error bound of the plain evaluation of `incircle`
+const ICC_ERR_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// Twice the signed area of the triangle `a`, `b`, `c`: positive if the points are in
+/// counter-clockwise order, negative if clockwise and zero if they are collinear.
+///
+/// The sign of the result is exact, and its magnitude is accurate to a few ulps.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let a = Point::new(0.5, 0.5);
+/// let b = Point::new(12.0, 12.0);
+/// let c = Point::new(24.0, 24.0);
+/// assert_eq!(geomlib::orient2d(&a, &b, &c), 0.0);
+///
+/// // The smallest possible step away from the line is detected
+/// let d = Point::new(0.5, 0.5 + f64::EPSILON / 2.0);
+/// assert!(geomlib::orient2d(&d, &b, &c) > 0.0);
+/// ```
+pub fn orient2d(a: &Point, b: &Point, c: &Point) -> f64 {
+    let ((ax, ay), (bx, by), (cx, cy)) = (a.coords(), b.coords(), c.coords());
+    let left = (ax - cx) * (by - cy);
+    let right = (ay - cy) * (bx - cx);
+    let det = left - right;
+
+    // The terms have opposite signs, or one of them is zero: the sign is exact
+    let bound = if left > 0.0 {
+        if right <= 0.0 {
+            return det;
+        }
+        left + right
+    } else if left < 0.0 {
+        if right >= 0.0 {
+            return det;
+        }
+        -left - right
+    } else {
+        return det;
+    };
+    if det.abs() >= CCW_ERR_BOUND * bound {
+        return det;
+    }
+
+    let left = product(&difference(ax, cx), &difference(by, cy));
+    let right = product(&difference(ay, cy), &difference(bx, cx));
+    estimate(&sum(&left, &negate(&right)))
+}
+
+/// Determinant which is positive if `d` lies inside the circle through `a`, `b` and `c`,
+/// assuming these are in counter-clockwise order, negative if it lies outside and zero if the
+/// four points are cocircular. The sign is reversed if `a`, `b` and `c` are clockwise.
+///
+/// The sign of the result is exact, and its magnitude is accurate to a few ulps.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let a = Point::new(1.0, 0.0);
+/// let b = Point::new(0.0, 1.0);
+/// let c = Point::new(-1.0, 0.0);
+/// assert_eq!(geomlib::incircle(&a, &b, &c, &Point::new(0.0, -1.0)), 0.0);
+/// assert!(geomlib::incircle(&a, &b, &c, &Point::new(0.0, 0.5)) > 0.0);
+/// ```
+pub fn incircle(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let ((ax, ay), (bx, by), (cx, cy), (dx, dy)) = (a.coords(), b.coords(), c.coords(), d.coords());
+    let (adx, ady) = (ax - dx, ay - dy);
+    let (bdx, bdy) = (bx - dx, by - dy);
+    let (cdx, cdy) = (cx - dx, cy - dy);
+
+    let (bdxcdy, cdxbdy) = (bdx * cdy, cdx * bdy);
+    let (cdxady, adxcdy) = (cdx * ady, adx * cdy);
+    let (adxbdy, bdxady) = (adx * bdy, bdx * ady);
+    let alift = adx * adx + ady * ady;
+    let blift = bdx * bdx + bdy * bdy;
+    let clift = cdx * cdx + cdy * cdy;
+
+    let det = alift * (bdxcdy - cdxbdy) + blift * (cdxady - adxcdy) + clift * (adxbdy - bdxady);
+    let permanent = (bdxcdy.abs() + cdxbdy.abs()) * alift
+        + (cdxady.abs() + adxcdy.abs()) * blift
+        + (adxbdy.abs() + bdxady.abs()) * clift;
+    if det.abs() > ICC_ERR_BOUND * permanent {
+        return det;
+    }
+
+    let (adx, ady) = (difference(ax, dx), difference(ay, dy));
+    let (bdx, bdy) = (difference(bx, dx), difference(by, dy));
+    let (cdx, cdy) = (difference(cx, dx), difference(cy, dy));
+    let lift = |x: &[f64], y: &[f64]| sum(&product(x, x), &product(y, y));
+    let cross = |x1: &[f64], y2: &[f64], x2: &[f64], y1: &[f64]| {
+        sum(&product(x1, y2), &negate(&product(x2, y1)))
+    };
+
+    let aterm = product(&lift(&adx, &ady), &cross(&bdx, &cdy, &cdx, &bdy));
+    let bterm = product(&lift(&bdx, &bdy), &cross(&cdx, &ady, &adx, &cdy));
+    let cterm = product(&lift(&cdx, &cdy), &cross(&adx, &bdy, &bdx, &ady));
+    estimate(&sum(&sum(&aterm, &bterm), &cterm))
+}
+
+/// Exact sum of two doubles, as the rounded sum and its error
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    let b_virtual = x - a;
+    let a_virtual = x - b_virtual;
+    (x, (a - a_virtual) + (b - b_virtual))
+}
+
+/// Exact sum of two doubles, assuming that `|a| >= |b|`
+fn fast_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let x = a + b;
+    (x, b - (x - a))
+}
+
+/// Exact product of two doubles, as the rounded product and its error
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let x = a * b;
+    (x, a.mul_add(b, -x))
+}
+
+/// Exact difference of two doubles, as an expansion
+fn difference(a: f64, b: f64) -> Vec<f64> {
+    let (x, err) = two_sum(a, -b);
+    [err, x].into_iter().filter(|&v| v != 0.0).collect()
+}
+
+/// Add a double to an expansion
+fn grow(e: &[f64], b: f64) -> Vec<f64> {
+    let mut out = Vec::with_capacity(e.len() + 1);
+    let mut q = b;
+    for &component in e {
+        let (x, err) = two_sum(q, component);
+        if err != 0.0 {
+            out.push(err);
+        }
+        q = x;
+    }
+    if q != 0.0 {
+        out.push(q);
+    }
+    out
+}
+
+/// Sum of two expansions
+fn sum(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(e.to_vec(), |acc, &b| grow(&acc, b))
+}
+
+/// Product of an expansion and a double
+fn scale(e: &[f64], b: f64) -> Vec<f64> {
+    let Some((&first, rest)) = e.split_first() else {
+        return Vec::new();
+    };
+    let mut out = Vec::with_capacity(2 * e.len());
+    let (mut q, err) = two_product(first, b);
+    if err != 0.0 {
+        out.push(err);
+    }
+    for &component in rest {
+        let (high, low) = two_product(component, b);
+        let (partial, err) = two_sum(q, low);
+        if err != 0.0 {
+            out.push(err);
+        }
+        let (x, err) = fast_two_sum(high, partial);
+        if err != 0.0 {
+            out.push(err);
+        }
+        q = x;
+    }
+    if q != 0.0 {
+        out.push(q);
+    }
+    out
+}
+
+/// Product of two expansions
+fn product(e: &[f64], f: &[f64]) -> Vec<f64> {
+    f.iter().fold(Vec::new(), |acc, &b| sum(&acc, &scale(e, b)))
+}
+
+fn negate(e: &[f64]) -> Vec<f64> {
+    e.iter().map(|v| -v).collect()
+}
+
+/// Approximate value of an expansion, with the sign of its exact value
+fn estimate(e: &[f64]) -> f64 {
+    e.iter().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plain evaluation of the orientation determinant, for comparison
+    fn naive_orient2d(a: &Point, b: &Point, c: &Point) -> f64 {
+        let ((ax, ay), (bx, by), (cx, cy)) = (a.coords(), b.coords(), c.coords());
+        (ax - cx) * (by - cy) - (ay - cy) * (bx - cx)
+    }
+
+    #[test]
+    fn test_expansions() {
+        let e = difference(1.0, EPSILON * EPSILON);
+        assert_eq!(e.len(), 2);
+        assert_eq!(estimate(&sum(&e, &[EPSILON * EPSILON])), 1.0);
+
+        let square = product(&e, &e);
+        let expected = sum(&[1.0], &[-2.0 * EPSILON * EPSILON]);
+        let rest = sum(&square, &negate(&expected));
+        assert_eq!(estimate(&rest), EPSILON.powi(4));
+    }
+
+    #[test]
+    fn test_orient2d_grid() {
+        // Points on a tiny grid near a line: the plain determinant gets many signs wrong
+        let (b, c) = (Point::new(12.0, 12.0), Point::new(24.0, 24.0));
+        let mut naive_errors = 0;
+        for i in 0..64 {
+            for j in 0..64 {
+                let a = Point::new(0.5 + i as f64 * f64::EPSILON, 0.5 + j as f64 * f64::EPSILON);
+                let expected = (j as f64 - i as f64).signum();
+                let det = orient2d(&a, &b, &c);
+                if i == j {
+                    assert_eq!(det, 0.0);
+                } else {
+                    assert_eq!(det.signum(), expected);
+                }
+                let naive = naive_orient2d(&a, &b, &c);
+                if naive == 0.0 || (i != j && naive.signum() != expected) {
+                    naive_errors += 1;
+                }
+            }
+        }
+        assert!(naive_errors > 0);
+    }
+
+    #[test]
+    fn test_orient2d_large_coordinates() {
+        // UTM-like coordinates, with a point a tiny step away from a long line
+        let a = Point::new(500000.0, 5000000.0);
+        let b = Point::new(600000.0, 5100000.0);
+        let c = Point::new(550000.0, 5050000.0);
+        assert_eq!(orient2d(&a, &b, &c), 0.0);
+
+        let step = 5050000.0_f64.next_up() - 5050000.0;
+        let c = Point::new(550000.0, 5050000.0 + step);
+        assert!(orient2d(&a, &b, &c) > 0.0);
+        assert!(orient2d(&b, &a, &c) < 0.0);
+
+        // The magnitude is accurate far from the degenerate case
+        let d = Point::new(500000.0, 5100000.0);
+        assert_eq!(orient2d(&a, &b, &d), 1e10);
+    }
+
+    #[test]
+    fn test_incircle() {
+        // Unit circle translated far from the origin, where its points are still exact
+        let offset = 2.0_f64.powi(20);
+        let pt = |x: f64, y: f64| Point::new(offset + x, offset + y);
+        let (a, b, c) = (pt(1.0, 0.0), pt(0.0, 1.0), pt(-1.0, 0.0));
+        assert_eq!(incircle(&a, &b, &c, &pt(0.0, -1.0)), 0.0);
+
+        let step = offset.next_up() - offset;
+        assert!(incircle(&a, &b, &c, &pt(0.0, -1.0 + step)) > 0.0);
+        assert!(incircle(&a, &b, &c, &pt(0.0, -1.0 - step)) < 0.0);
+        // Clockwise triangle: the sign is reversed
+        assert!(incircle(&c, &b, &a, &pt(0.0, -1.0 + step)) < 0.0);
+
+        assert!(incircle(&a, &b, &c, &pt(0.0, 0.0)) > 0.0);
+        assert!(incircle(&a, &b, &c, &pt(2.0, 2.0)) < 0.0);
+    }
+}
@@ -0,0 +1,543 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::LineString;
+use super::points::{MultiPoint, Point};
+use super::polygons::{MultiPolygon, Polygon};
+use super::serialization::GeomWrapper;
+use super::transform::Transform;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+use std::fmt;
+use std::str::FromStr;
+
+/// Semi-major axis of the WGS84 ellipsoid, in meters
+pub(crate) const WGS84_A: f64 = 6_378_137.0;
+
+/// Flattening of the WGS84 ellipsoid
+pub(crate) const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Scale factor on the central meridian of UTM zones
+const UTM_K0: f64 = 0.9996;
+
+/// False easting of UTM zones, in meters
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+
+/// False northing of the UTM zones of the southern hemisphere, in meters
+const UTM_FALSE_NORTHING: f64 = 10_000_000.0;
+
+/// Coordinate reference systems that geometries can be projected between.
+///
+/// Geographic coordinates are given as longitude (x) and latitude (y) in degrees, and projected
+/// ones as easting (x) and northing (y) in meters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoordinateSystem {
+    /// Geographic coordinates on the WGS84 ellipsoid (EPSG:4326)
+    Wgs84,
+    /// Spherical "Web" Mercator projection used by web maps (EPSG:3857)
+    WebMercator,
+    /// Universal Transverse Mercator zone (1 to 60) on the WGS84 ellipsoid, in the northern
+    /// (EPSG:326xx) or southern (EPSG:327xx) hemisphere
+    Utm { zone: u8, north: bool },
+}
+
+impl CoordinateSystem {
+    /// Coordinate system with the given EPSG identifier
+    pub fn from_srid(srid: u32) -> GeomResult<Self> {
+        match srid {
+            4326 => Ok(Self::Wgs84),
+            3857 => Ok(Self::WebMercator),
+            32601..=32660 => Ok(Self::Utm {
+                zone: (srid - 32600) as u8,
+                north: true,
+            }),
+            32701..=32760 => Ok(Self::Utm {
+                zone: (srid - 32700) as u8,
+                north: false,
+            }),
+            _ => Err(GeometryError::ParameterError(format!(
+                "Unsupported SRID: {srid}"
+            ))),
+        }
+    }
+
+    /// EPSG identifier of the coordinate system
+    pub fn srid(&self) -> u32 {
+        match self {
+            Self::Wgs84 => 4326,
+            Self::WebMercator => 3857,
+            Self::Utm { zone, north: true } => 32600 + *zone as u32,
+            Self::Utm { zone, north: false } => 32700 + *zone as u32,
+        }
+    }
+
+    /// UTM zone containing a point given by its longitude and latitude, including the
+    /// exceptions around Norway and Svalbard
+    pub fn utm_zone(lon_lat: &Point) -> Self {
+        let (lon, lat) = lon_lat.coords();
+        let mut zone = (((lon + 180.0) / 6.0).floor() as i64 + 1).clamp(1, 60) as u8;
+        if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+            zone = 32;
+        } else if (72.0..84.0).contains(&lat) && (0.0..42.0).contains(&lon) {
+            zone = match lon {
+                lon if lon < 9.0 => 31,
+                lon if lon < 21.0 => 33,
+                lon if lon < 33.0 => 35,
+                _ => 37,
+            };
+        }
+        Self::Utm {
+            zone,
+            north: lat >= 0.0,
+        }
+    }
+
+    /// Convert a point from this coordinate system to longitude and latitude
+    pub fn unproject(&self, pt: &Point) -> GeomResult<Point> {
+        match self {
+            Self::Wgs84 => check_geographic(pt).map(|_| pt.clone()),
+            Self::WebMercator => Ok(web_mercator_to_geographic(pt)),
+            Self::Utm { zone, north } => Ok(utm_to_geographic(pt, *zone, *north)),
+        }
+    }
+
+    /// Convert a point from longitude and latitude to this coordinate system
+    pub fn project(&self, pt: &Point) -> GeomResult<Point> {
+        check_geographic(pt)?;
+        match self {
+            Self::Wgs84 => Ok(pt.clone()),
+            Self::WebMercator => geographic_to_web_mercator(pt),
+            Self::Utm { zone, north } => Ok(geographic_to_utm(pt, *zone, *north)),
+        }
+    }
+
+    /// Convert a point from this coordinate system to another one
+    pub fn convert(&self, pt: &Point, to: &Self) -> GeomResult<Point> {
+        if self == to {
+            return Ok(pt.clone());
+        }
+        to.project(&self.unproject(pt)?)
+    }
+}
+
+impl fmt::Display for CoordinateSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EPSG:{}", self.srid())
+    }
+}
+
+impl FromStr for CoordinateSystem {
+    type Err = GeometryError;
+
+    /// Parse an EPSG identifier, optionally prefixed with "EPSG:"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        let code = code
+            .strip_prefix("EPSG:")
+            .or_else(|| code.strip_prefix("epsg:"))
+            .unwrap_or(code);
+        let srid = code
+            .parse()
+            .map_err(|_| GeometryError::ParsingError(format!("Invalid SRID: {s}")))?;
+        Self::from_srid(srid)
+    }
+}
+
+/// Check that a point has a valid longitude and latitude
+pub(crate) fn check_geographic(pt: &Point) -> GeomResult<()> {
+    let (lon, lat) = pt.coords();
+    if (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat) {
+        Ok(())
+    } else {
+        Err(GeometryError::ParameterError(format!(
+            "Invalid geographic coordinates: longitude {lon}, latitude {lat}"
+        )))
+    }
+}
+
+/// Project longitude and latitude to Web Mercator. The poles can't be projected.
+fn geographic_to_web_mercator(pt: &Point) -> GeomResult<Point> {
+    let (lon, lat) = pt.coords();
+    if lat.abs() >= 90.0 {
+        return Err(GeometryError::ParameterError(String::from(
+            "The poles can't be projected to Web Mercator",
+        )));
+    }
+    Ok(Point::new(
+        WGS84_A * lon.to_radians(),
+        WGS84_A * (FRAC_PI_4 + lat.to_radians() / 2.0).tan().ln(),
+    ))
+}
+
+/// Longitude and latitude of a point projected to Web Mercator
+fn web_mercator_to_geographic(pt: &Point) -> Point {
+    let (x, y) = pt.coords();
+    Point::new(
+        (x / WGS84_A).to_degrees(),
+        (2.0 * (y / WGS84_A).exp().atan() - FRAC_PI_2).to_degrees(),
+    )
+}
+
+/// Third flattening of the WGS84 ellipsoid
+fn third_flattening() -> f64 {
+    WGS84_F / (2.0 - WGS84_F)
+}
+
+/// Eccentricity of the WGS84 ellipsoid
+pub(crate) fn eccentricity() -> f64 {
+    (WGS84_F * (2.0 - WGS84_F)).sqrt()
+}
+
+/// Coefficients of the series expansions of the transverse Mercator projection in the third
+/// flattening `n` (Krüger, to the sixth order as given by Karney): `(alpha, beta)` for the
+/// forward and the inverse projections.
+fn kruger_series() -> ([f64; 6], [f64; 6]) {
+    let n = third_flattening();
+    let [n2, n3, n4, n5, n6] = [2, 3, 4, 5, 6].map(|k| n.powi(k));
+    (
+        [
+            n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0 - 127.0 * n5 / 288.0
+                + 7891.0 * n6 / 37800.0,
+            13.0 * n2 / 48.0 - 3.0 * n3 / 5.0 + 557.0 * n4 / 1440.0 + 281.0 * n5 / 630.0
+                - 1983433.0 * n6 / 1935360.0,
+            61.0 * n3 / 240.0 - 103.0 * n4 / 140.0
+                + 15061.0 * n5 / 26880.0
+                + 167603.0 * n6 / 181440.0,
+            49561.0 * n4 / 161280.0 - 179.0 * n5 / 168.0 + 6601661.0 * n6 / 7257600.0,
+            34729.0 * n5 / 80640.0 - 3418889.0 * n6 / 1995840.0,
+            212378941.0 * n6 / 319334400.0,
+        ],
+        [
+            n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0 - n4 / 360.0 - 81.0 * n5 / 512.0
+                + 96199.0 * n6 / 604800.0,
+            n2 / 48.0 + n3 / 15.0 - 437.0 * n4 / 1440.0 + 46.0 * n5 / 105.0
+                - 1118711.0 * n6 / 3870720.0,
+            17.0 * n3 / 480.0 - 37.0 * n4 / 840.0 - 209.0 * n5 / 4480.0 + 5569.0 * n6 / 90720.0,
+            4397.0 * n4 / 161280.0 - 11.0 * n5 / 504.0 - 830251.0 * n6 / 7257600.0,
+            4583.0 * n5 / 161280.0 - 108847.0 * n6 / 3991680.0,
+            20648693.0 * n6 / 638668800.0,
+        ],
+    )
+}
+
+/// Scale of the transverse Mercator projection: `k0` times the rectifying radius
+fn utm_scale() -> f64 {
+    let n = third_flattening();
+    UTM_K0 * WGS84_A / (1.0 + n) * (1.0 + n.powi(2) / 4.0 + n.powi(4) / 64.0 + n.powi(6) / 256.0)
+}
+
+/// Longitude of the central meridian of a UTM zone, in radians
+fn central_meridian(zone: u8) -> f64 {
+    (6.0 * zone as f64 - 183.0).to_radians()
+}
+
+/// Tangent of the conformal latitude, from the tangent of the geographic latitude
+fn conformal_tan(tau: f64) -> f64 {
+    let e = eccentricity();
+    let sigma = (e * (e * tau / tau.hypot(1.0)).atanh()).sinh();
+    tau * sigma.hypot(1.0) - sigma * tau.hypot(1.0)
+}
+
+/// Tangent of the geographic latitude, from the tangent of the conformal latitude, by
+/// Newton's method
+fn geographic_tan(tau_p: f64) -> f64 {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let mut tau = tau_p / (1.0 - e2);
+    for _ in 0..5 {
+        let derivative = (1.0 - e2) * conformal_tan(tau).hypot(1.0) * tau.hypot(1.0)
+            / (1.0 + (1.0 - e2) * tau * tau);
+        let step = (conformal_tan(tau) - tau_p) / derivative;
+        tau -= step;
+        if step.abs() <= 1e-15 * tau.abs().max(1.0) {
+            break;
+        }
+    }
+    tau
+}
+
+/// Project longitude and latitude to a UTM zone
+fn geographic_to_utm(pt: &Point, zone: u8, north: bool) -> Point {
+    let (lon, lat) = pt.coords();
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians() - central_meridian(zone));
+    let (alpha, _) = kruger_series();
+
+    // Conformal latitude, then Gauss-Schreiber transverse Mercator on the sphere
+    let tau_p = conformal_tan(phi.tan());
+    let xi = tau_p.atan2(lambda.cos());
+    let eta = (lambda.sin() / tau_p.hypot(lambda.cos())).asinh();
+
+    let (mut easting, mut northing) = (eta, xi);
+    for (j, coef) in alpha.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        easting += coef * (k * xi).cos() * (k * eta).sinh();
+        northing += coef * (k * xi).sin() * (k * eta).cosh();
+    }
+    let false_northing = if north { 0.0 } else { UTM_FALSE_NORTHING };
+    Point::new(
+        UTM_FALSE_EASTING + utm_scale() * easting,
+        false_northing + utm_scale() * northing,
+    )
+}
+
+/// Longitude and latitude of a point projected to a UTM zone
+fn utm_to_geographic(pt: &Point, zone: u8, north: bool) -> Point {
+    let (x, y) = pt.coords();
+    let false_northing = if north { 0.0 } else { UTM_FALSE_NORTHING };
+    let xi = (y - false_northing) / utm_scale();
+    let eta = (x - UTM_FALSE_EASTING) / utm_scale();
+    let (_, beta) = kruger_series();
+
+    let (mut xi_p, mut eta_p) = (xi, eta);
+    for (j, coef) in beta.iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi_p -= coef * (k * xi).sin() * (k * eta).cosh();
+        eta_p -= coef * (k * xi).cos() * (k * eta).sinh();
+    }
+    let tau_p = xi_p.sin() / eta_p.sinh().hypot(xi_p.cos());
+    let phi = geographic_tan(tau_p).atan();
+    let lambda = central_meridian(zone) + eta_p.sinh().atan2(xi_p.cos());
+    Point::new(lambda.to_degrees(), phi.to_degrees())
+}
+
+/// Geometries whose coordinates can be converted between coordinate systems.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{CoordinateSystem, LineString, Point, Project};
+/// let route = LineString::new(vec![Point::new(2.35, 48.85), Point::new(2.36, 48.86)]).unwrap();
+///
+/// let (projected, utm) = route.project_to_utm(&CoordinateSystem::Wgs84).unwrap();
+/// assert_eq!(utm.srid(), 32631);
+///
+/// let back = projected.project(&utm, &CoordinateSystem::Wgs84).unwrap();
+/// assert!(back.points[0].is_close(&route.points[0]));
+/// ```
+pub trait Project: Sized {
+    /// Apply a fallible function to all the vertices of the geometry
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self>;
+
+    /// Convert the coordinates of the geometry from a coordinate system to another
+    fn project(&self, from: &CoordinateSystem, to: &CoordinateSystem) -> GeomResult<Self> {
+        self.try_map_points(|pt| from.convert(pt, to))
+    }
+
+    /// Project the geometry to the UTM zone containing its centroid. Returns the projected
+    /// geometry and the zone.
+    fn project_to_utm(&self, from: &CoordinateSystem) -> GeomResult<(Self, CoordinateSystem)>
+    where
+        Self: Transform,
+    {
+        let center = self.centroid().ok_or_else(|| {
+            GeometryError::OperationError(String::from(
+                "Cannot find the UTM zone of an empty geometry",
+            ))
+        })?;
+        let zone = CoordinateSystem::utm_zone(&from.unproject(&center)?);
+        Ok((self.project(from, &zone)?, zone))
+    }
+}
+
+impl Project for Point {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        f(self)
+    }
+}
+
+impl Project for MultiPoint {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        Ok(MultiPoint::new(
+            self.points.iter().map(f).collect::<GeomResult<_>>()?,
+        ))
+    }
+}
+
+impl Project for LineString {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        Ok(LineString {
+            points: self.points.iter().map(f).collect::<GeomResult<_>>()?,
+        })
+    }
+}
+
+impl Project for Polygon {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        let map = |ring: &Vec<Point>| ring.iter().map(&f).collect::<GeomResult<Vec<_>>>();
+        Ok(Polygon {
+            outer: map(&self.outer)?,
+            inner: self.inner.iter().map(map).collect::<GeomResult<_>>()?,
+        })
+    }
+}
+
+impl Project for MultiPolygon {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        Ok(MultiPolygon::new(
+            self.polygons
+                .iter()
+                .map(|poly| poly.try_map_points(&f))
+                .collect::<GeomResult<_>>()?,
+        ))
+    }
+}
+
+impl Project for GeomWrapper {
+    fn try_map_points<F: Fn(&Point) -> GeomResult<Point>>(&self, f: F) -> GeomResult<Self> {
+        Ok(match self {
+            GeomWrapper::Point(pt) => GeomWrapper::Point(pt.try_map_points(f)?),
+            GeomWrapper::MultiPoint(mp) => GeomWrapper::MultiPoint(mp.try_map_points(f)?),
+            GeomWrapper::LineString(ls) => GeomWrapper::LineString(ls.try_map_points(f)?),
+            GeomWrapper::Polygon(poly) => GeomWrapper::Polygon(poly.try_map_points(f)?),
+            GeomWrapper::MultiPolygon(mp) => GeomWrapper::MultiPolygon(mp.try_map_points(f)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::*;
+
+    fn assert_near(pt: &Point, x: f64, y: f64, tolerance: f64) {
+        let (px, py) = pt.coords();
+        assert!(
+            (px - x).abs() < tolerance && (py - y).abs() < tolerance,
+            "({px}, {py}) is not close to ({x}, {y})"
+        );
+    }
+
+    #[test]
+    fn test_srid() {
+        for srid in [4326, 3857, 32601, 32633, 32660, 32701, 32760] {
+            assert_eq!(CoordinateSystem::from_srid(srid).unwrap().srid(), srid);
+        }
+        assert_eq!(
+            CoordinateSystem::from_srid(32733).unwrap(),
+            CoordinateSystem::Utm {
+                zone: 33,
+                north: false
+            }
+        );
+        assert!(CoordinateSystem::from_srid(32661).is_err());
+        assert!(CoordinateSystem::from_srid(2154).is_err());
+
+        assert_eq!(
+            "EPSG:3857".parse::<CoordinateSystem>().unwrap(),
+            CoordinateSystem::WebMercator
+        );
+        assert_eq!(
+            "4326".parse::<CoordinateSystem>().unwrap(),
+            CoordinateSystem::Wgs84
+        );
+        assert!("wgs".parse::<CoordinateSystem>().is_err());
+        assert_eq!(CoordinateSystem::WebMercator.to_string(), "EPSG:3857");
+    }
+
+    #[test]
+    fn test_utm_zone() {
+        let zone = |lon, lat| CoordinateSystem::utm_zone(&Point::new(lon, lat)).srid();
+        assert_eq!(zone(2.35, 48.85), 32631);
+        assert_eq!(zone(-74.0, 40.7), 32618);
+        assert_eq!(zone(151.2, -33.9), 32756);
+        assert_eq!(zone(180.0, 10.0), 32660);
+        assert_eq!(zone(-180.0, 10.0), 32601);
+        // Norway and Svalbard
+        assert_eq!(zone(5.3, 60.4), 32632);
+        assert_eq!(zone(15.6, 78.2), 32633);
+    }
+
+    #[test]
+    fn test_web_mercator() {
+        let merc = CoordinateSystem::WebMercator;
+        let projected = merc.project(&Point::new(10.0, 0.0)).unwrap();
+        assert_near(&projected, 1_113_194.907_932_7, 0.0, 1e-6);
+        let projected = merc
+            .project(&Point::new(-180.0, 85.051_128_779_806_59))
+            .unwrap();
+        assert_near(
+            &projected,
+            -20_037_508.342_789_24,
+            20_037_508.342_789_24,
+            1e-3,
+        );
+
+        let pt = Point::new(-122.42, 37.77);
+        let back = merc.unproject(&merc.project(&pt).unwrap()).unwrap();
+        assert_near(&back, -122.42, 37.77, 1e-9);
+
+        assert!(merc.project(&Point::new(0.0, 90.0)).is_err());
+        assert!(merc.project(&Point::new(200.0, 0.0)).is_err());
+    }
+
+    #[test]
+    fn test_utm() {
+        let zone31 = CoordinateSystem::Utm {
+            zone: 31,
+            north: true,
+        };
+        // On the central meridian, the northing is the scaled meridian arc length
+        let projected = zone31.project(&Point::new(3.0, 0.0)).unwrap();
+        assert_near(&projected, 500_000.0, 0.0, 1e-6);
+        let projected = zone31.project(&Point::new(3.0, 45.0)).unwrap();
+        assert_near(&projected, 500_000.0, UTM_K0 * 4_984_944.378, 1e-3);
+
+        // Null Island, on the edge of the zone
+        let projected = zone31.project(&Point::new(0.0, 0.0)).unwrap();
+        assert_near(&projected, 166_021.443_1, 0.0, 1e-3);
+
+        // Symmetric about the central meridian and the equator
+        let east = zone31.project(&Point::new(5.0, 30.0)).unwrap();
+        let west = zone31.project(&Point::new(1.0, 30.0)).unwrap();
+        assert!(core::approx(
+            east.coords().0 - 500_000.0,
+            500_000.0 - west.coords().0
+        ));
+        assert!(core::approx(east.coords().1, west.coords().1));
+        let south = CoordinateSystem::Utm {
+            zone: 31,
+            north: false,
+        };
+        let mirrored = south.project(&Point::new(5.0, -30.0)).unwrap();
+        assert!(core::approx(
+            mirrored.coords().1,
+            UTM_FALSE_NORTHING - east.coords().1
+        ));
+
+        // Round trips, including far from the central meridian
+        for (lon, lat) in [(2.2945, 48.8583), (-1.0, 60.0), (8.9, -45.0), (3.0, 80.0)] {
+            let pt = Point::new(lon, lat);
+            let utm = CoordinateSystem::utm_zone(&Point::new(3.0, lat));
+            let back = utm.unproject(&utm.project(&pt).unwrap()).unwrap();
+            assert_near(&back, lon, lat, 1e-11);
+        }
+    }
+
+    #[test]
+    fn test_project_geometries() {
+        let square = Polygon::new(vec![
+            Point::new(2.0, 48.0),
+            Point::new(2.01, 48.0),
+            Point::new(2.01, 48.01),
+            Point::new(2.0, 48.01),
+            Point::new(2.0, 48.0),
+        ])
+        .unwrap();
+        let (projected, utm) = square.project_to_utm(&CoordinateSystem::Wgs84).unwrap();
+        assert_eq!(utm.srid(), 32631);
+        // About 745m by 1112m
+        assert!((projected.area() - 745.0 * 1112.0).abs() < 0.01 * projected.area());
+
+        let merc = projected
+            .project(&utm, &CoordinateSystem::WebMercator)
+            .unwrap();
+        let back = merc
+            .project(&CoordinateSystem::WebMercator, &CoordinateSystem::Wgs84)
+            .unwrap();
+        for (pt, orig) in back.outer.iter().zip(&square.outer) {
+            assert!(pt.is_close(orig) || pt.l2_distance(orig) < 1e-9);
+        }
+
+        let wrapped = GeomWrapper::Point(Point::new(200.0, 0.0));
+        assert!(
+            wrapped
+                .project(&CoordinateSystem::Wgs84, &CoordinateSystem::WebMercator)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,438 @@
+use super::core::{GeomResult, GeometryError};
+use super::envelope::{Bounded, Envelope};
+use super::points::Point;
+
+/// Default maximum number of objects in a node before it is split
+const DEFAULT_CAPACITY: usize = 8;
+/// Default maximum depth of the tree
+const DEFAULT_MAX_DEPTH: usize = 16;
+
+/// Region quadtree over objects with bounding boxes, such as points, inside fixed bounds.
+///
+/// Each node covers a quarter of its parent's region. Objects are stored in the deepest node
+/// whose region contains their envelope: points always reach the leaves, while larger objects
+/// can stay in the internal nodes. A leaf holding more objects than the capacity is split, until
+/// the maximum depth is reached, and nodes are merged back as objects are removed. This keeps
+/// the tree cheap to update, e.g. for moving objects.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Envelope, Point, QuadTree};
+/// let mut tree = QuadTree::new(Envelope::new(0.0, 0.0, 100.0, 100.0)).unwrap();
+/// for i in 0..100 {
+///     tree.insert(Point::new(i as f64, (i * 7 % 100) as f64)).unwrap();
+/// }
+/// assert!(tree.insert(Point::new(-1.0, 0.0)).is_err());
+///
+/// let query = Envelope::new(10.0, 10.0, 20.0, 50.0);
+/// let found = tree.query_envelope(&query);
+/// assert!(found.iter().all(|pt| query.contains_point(pt)));
+/// let count = found.len();
+///
+/// // Move a point
+/// let old = Envelope::from_point(&Point::new(3.0, 21.0));
+/// let moved = tree.move_item(&old, |_| true, Point::new(15.0, 15.0)).unwrap();
+/// assert!(moved.is_some());
+/// assert_eq!(tree.query_envelope(&query).len(), count + 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuadTree<T> {
+    root: QuadNode<T>,
+    capacity: usize,
+    max_depth: usize,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct QuadNode<T> {
+    /// Region covered by the node
+    bounds: Envelope,
+    /// Objects stored in this node, with their envelopes
+    items: Vec<(Envelope, T)>,
+    /// Quadrants of the region, if the node was split
+    children: Option<Box<[QuadNode<T>; 4]>>,
+}
+
+impl<T: Bounded> QuadTree<T> {
+    /// Instantiate an empty tree covering the given bounds, with the default capacity and depth
+    pub fn new(bounds: Envelope) -> GeomResult<Self> {
+        Self::with_limits(bounds, DEFAULT_CAPACITY, DEFAULT_MAX_DEPTH)
+    }
+
+    /// Instantiate an empty tree covering the given bounds, whose nodes are split when they
+    /// hold more than `capacity` objects, up to `max_depth` levels below the root
+    pub fn with_limits(bounds: Envelope, capacity: usize, max_depth: usize) -> GeomResult<Self> {
+        if bounds.is_empty() || !(bounds.width() > 0.0 && bounds.height() > 0.0) {
+            return Err(GeometryError::ParameterError(String::from(
+                "The bounds of a quadtree must have a positive area",
+            )));
+        }
+        if capacity == 0 {
+            return Err(GeometryError::ParameterError(String::from(
+                "The capacity of quadtree nodes must be positive",
+            )));
+        }
+        Ok(Self {
+            root: QuadNode::new(bounds),
+            capacity,
+            max_depth,
+            len: 0,
+        })
+    }
+
+    /// Region covered by the tree
+    pub fn bounds(&self) -> Envelope {
+        self.root.bounds
+    }
+
+    /// Number of objects in the tree
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over all the objects in the tree, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut items = Vec::with_capacity(self.len);
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            items.extend(node.items.iter().map(|(_, item)| item));
+            if let Some(children) = &node.children {
+                stack.extend(children.iter());
+            }
+        }
+        items.into_iter()
+    }
+
+    /// Insert an object, whose envelope must lie within the bounds of the tree
+    pub fn insert(&mut self, item: T) -> GeomResult<()> {
+        let envelope = self.check_bounds(&item)?;
+        self.root
+            .insert((envelope, item), self.capacity, self.max_depth, 0);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Remove an object whose envelope intersects `envelope` and for which `matches` returns
+    /// true, and return it. Returns None if there is no such object.
+    pub fn remove(&mut self, envelope: &Envelope, matches: impl Fn(&T) -> bool) -> Option<T> {
+        let removed = self.root.remove(envelope, &matches, self.capacity)?;
+        self.len -= 1;
+        Some(removed)
+    }
+
+    /// Replace an object whose envelope intersects `envelope` and for which `matches` returns
+    /// true by another one, e.g. the same object at a new position, and return the old one.
+    ///
+    /// Returns None and leaves the tree unchanged if there is no such object, and an error if
+    /// the new object lies outside of the bounds of the tree.
+    pub fn move_item(
+        &mut self,
+        envelope: &Envelope,
+        matches: impl Fn(&T) -> bool,
+        item: T,
+    ) -> GeomResult<Option<T>> {
+        let new_envelope = self.check_bounds(&item)?;
+        let Some(old) = self.root.remove(envelope, &matches, self.capacity) else {
+            return Ok(None);
+        };
+        self.root
+            .insert((new_envelope, item), self.capacity, self.max_depth, 0);
+        Ok(Some(old))
+    }
+
+    /// Objects whose envelope intersects the given envelope
+    pub fn query_envelope(&self, envelope: &Envelope) -> Vec<&T> {
+        let mut out = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if !node.bounds.intersects(envelope) {
+                continue;
+            }
+            out.extend(
+                node.items
+                    .iter()
+                    .filter(|(env, _)| env.intersects(envelope))
+                    .map(|(_, item)| item),
+            );
+            if let Some(children) = &node.children {
+                stack.extend(children.iter());
+            }
+        }
+        out
+    }
+
+    /// Objects whose envelope contains the given point
+    pub fn query_point(&self, pt: &Point) -> Vec<&T> {
+        self.query_envelope(&Envelope::from_point(pt))
+    }
+
+    /// Envelope of an object, if it lies within the bounds of the tree
+    fn check_bounds(&self, item: &T) -> GeomResult<Envelope> {
+        let envelope = item.envelope();
+        if !self.root.bounds.contains(&envelope) {
+            return Err(GeometryError::ParameterError(format!(
+                "Object with envelope {envelope} outside of the quadtree bounds {}",
+                self.root.bounds
+            )));
+        }
+        Ok(envelope)
+    }
+}
+
+impl<T> QuadNode<T> {
+    fn new(bounds: Envelope) -> Self {
+        Self {
+            bounds,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    /// Index of the child whose region contains the envelope, if any
+    fn quadrant(&self, envelope: &Envelope) -> Option<usize> {
+        let children = self.children.as_ref()?;
+        children.iter().position(|c| c.bounds.contains(envelope))
+    }
+
+    fn insert(&mut self, entry: (Envelope, T), capacity: usize, max_depth: usize, depth: usize) {
+        if let Some(q) = self.quadrant(&entry.0) {
+            let children = self.children.as_mut().unwrap();
+            children[q].insert(entry, capacity, max_depth, depth + 1);
+            return;
+        }
+        self.items.push(entry);
+        if self.children.is_none() && self.items.len() > capacity && depth < max_depth {
+            self.split(capacity, max_depth, depth);
+        }
+    }
+
+    /// Split the node in quadrants, and move down the objects which fit in one
+    fn split(&mut self, capacity: usize, max_depth: usize, depth: usize) {
+        let Envelope {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        } = self.bounds;
+        let (mid_x, mid_y) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+        self.children = Some(Box::new([
+            QuadNode::new(Envelope::new(min_x, min_y, mid_x, mid_y)),
+            QuadNode::new(Envelope::new(mid_x, min_y, max_x, mid_y)),
+            QuadNode::new(Envelope::new(min_x, mid_y, mid_x, max_y)),
+            QuadNode::new(Envelope::new(mid_x, mid_y, max_x, max_y)),
+        ]));
+        for entry in std::mem::take(&mut self.items) {
+            self.insert(entry, capacity, max_depth, depth);
+        }
+    }
+
+    fn remove(
+        &mut self,
+        envelope: &Envelope,
+        matches: &impl Fn(&T) -> bool,
+        capacity: usize,
+    ) -> Option<T> {
+        let removed = match self
+            .items
+            .iter()
+            .position(|(env, item)| env.intersects(envelope) && matches(item))
+        {
+            Some(pos) => self.items.swap_remove(pos).1,
+            None => self
+                .children
+                .as_mut()?
+                .iter_mut()
+                .filter(|child| child.bounds.intersects(envelope))
+                .find_map(|child| child.remove(envelope, matches, capacity))?,
+        };
+
+        // Merge the quadrants back once they hold few enough objects
+        if let Some(children) = &self.children
+            && children.iter().all(|child| child.children.is_none())
+            && self.items.len() + children.iter().map(|c| c.items.len()).sum::<usize>() <= capacity
+        {
+            let children = self.children.take().unwrap();
+            for child in *children {
+                self.items.extend(child.items);
+            }
+        }
+        Some(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, rng};
+
+    /// Tracked object with an identifier
+    #[derive(Clone, Debug, PartialEq)]
+    struct Tracked {
+        id: usize,
+        x: f64,
+        y: f64,
+    }
+
+    impl Bounded for Tracked {
+        fn envelope(&self) -> Envelope {
+            Envelope::new(self.x, self.y, self.x, self.y)
+        }
+    }
+
+    fn random_tracked(total: usize) -> Vec<Tracked> {
+        let mut random = rng();
+        (0..total)
+            .map(|id| Tracked {
+                id,
+                x: random.random_range(0.0..64.0),
+                y: random.random_range(0.0..64.0),
+            })
+            .collect()
+    }
+
+    /// Verify that the objects are in the deepest node containing them, and that leaves are not
+    /// overfull unless at the maximum depth. Returns the number of objects in the subtree.
+    fn check_node<T: Bounded>(node: &QuadNode<T>, tree: &QuadTree<T>, depth: usize) -> usize {
+        for (env, item) in &node.items {
+            assert_eq!(*env, item.envelope());
+            assert!(node.bounds.contains(env));
+            assert!(node.quadrant(env).is_none());
+        }
+        match &node.children {
+            None => {
+                assert!(node.items.len() <= tree.capacity || depth == tree.max_depth);
+                node.items.len()
+            }
+            Some(children) => {
+                let total: usize = children
+                    .iter()
+                    .map(|child| check_node(child, tree, depth + 1))
+                    .sum();
+                assert!(total > 0 || !node.items.is_empty());
+                total + node.items.len()
+            }
+        }
+    }
+
+    fn check_tree<T: Bounded>(tree: &QuadTree<T>) {
+        assert_eq!(check_node(&tree.root, tree, 0), tree.len());
+        assert_eq!(tree.iter().count(), tree.len());
+    }
+
+    fn check_queries(tree: &QuadTree<Tracked>, objects: &[Tracked]) {
+        let mut random = rng();
+        for _ in 0..20 {
+            let (x, y) = (
+                random.random_range(0.0..64.0),
+                random.random_range(0.0..64.0),
+            );
+            let query = Envelope::new(x, y, x + 8.0, y + 4.0);
+            let mut found: Vec<usize> = tree.query_envelope(&query).iter().map(|t| t.id).collect();
+            found.sort();
+            let expected: Vec<usize> = objects
+                .iter()
+                .filter(|t| query.intersects(&t.envelope()))
+                .map(|t| t.id)
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_insert_query() {
+        let objects = random_tracked(1000);
+        let mut tree = QuadTree::with_limits(Envelope::new(0.0, 0.0, 64.0, 64.0), 4, 8).unwrap();
+        for obj in &objects {
+            tree.insert(obj.clone()).unwrap();
+        }
+        assert_eq!(tree.len(), 1000);
+        check_tree(&tree);
+        check_queries(&tree, &objects);
+
+        let obj = &objects[10];
+        let found = tree.query_point(&Point::new(obj.x, obj.y));
+        assert!(found.contains(&obj));
+    }
+
+    #[test]
+    fn test_move_remove() {
+        let mut objects = random_tracked(500);
+        let mut tree = QuadTree::new(Envelope::new(0.0, 0.0, 64.0, 64.0)).unwrap();
+        for obj in &objects {
+            tree.insert(obj.clone()).unwrap();
+        }
+
+        // Move all the objects a few times
+        let mut random = rng();
+        for _ in 0..3 {
+            for obj in objects.iter_mut() {
+                let moved = Tracked {
+                    id: obj.id,
+                    x: (obj.x + random.random_range(-4.0..4.0)).clamp(0.0, 64.0),
+                    y: (obj.y + random.random_range(-4.0..4.0)).clamp(0.0, 64.0),
+                };
+                let old = tree
+                    .move_item(&obj.envelope(), |t| t.id == obj.id, moved.clone())
+                    .unwrap();
+                assert_eq!(old.as_ref(), Some(&*obj));
+                *obj = moved;
+            }
+            assert_eq!(tree.len(), 500);
+            check_tree(&tree);
+            check_queries(&tree, &objects);
+        }
+
+        // Out of bounds: the object is not moved
+        let obj = objects[0].clone();
+        let outside = Tracked {
+            x: 65.0,
+            ..obj.clone()
+        };
+        assert!(
+            tree.move_item(&obj.envelope(), |t| t.id == obj.id, outside)
+                .is_err()
+        );
+        assert_eq!(tree.query_point(&Point::new(obj.x, obj.y)).len(), 1);
+
+        for obj in objects.drain(..400) {
+            assert_eq!(tree.remove(&obj.envelope(), |t| t.id == obj.id), Some(obj));
+        }
+        assert_eq!(tree.len(), 100);
+        check_tree(&tree);
+        check_queries(&tree, &objects);
+
+        for obj in objects.drain(..) {
+            assert!(tree.remove(&obj.envelope(), |t| t.id == obj.id).is_some());
+        }
+        assert!(tree.is_empty());
+        assert!(tree.root.children.is_none());
+    }
+
+    #[test]
+    fn test_boxes_and_depth() {
+        let bounds = Envelope::new(-1.0, -1.0, 1.0, 1.0);
+        let mut tree = QuadTree::with_limits(bounds, 2, 3).unwrap();
+        // Boxes across the middle stay at the root
+        for i in 0..5 {
+            let t = i as f64 / 10.0;
+            tree.insert(Envelope::new(-0.5 - t, -0.5, 0.5, 0.5 + t))
+                .unwrap();
+        }
+        // Identical points can't be separated beyond the maximum depth
+        for _ in 0..10 {
+            tree.insert(Envelope::new(0.7, 0.7, 0.7, 0.7)).unwrap();
+        }
+        check_tree(&tree);
+        assert_eq!(tree.root.items.len(), 5);
+        assert_eq!(tree.query_point(&Point::new(0.7, 0.7)).len(), 10);
+        assert_eq!(tree.query_point(&Point::new(0.0, 0.0)).len(), 5);
+        assert_eq!(tree.query_envelope(&bounds).len(), 15);
+
+        assert!(QuadTree::<Point>::new(Envelope::new(0.0, 0.0, 0.0, 1.0)).is_err());
+        assert!(QuadTree::<Point>::with_limits(bounds, 0, 4).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::LineString;
+use super::points::Point;
+
+impl LineString {
+    /// Total length of the linestring
+    pub fn length(&self) -> f64 {
+        self.edges()
+            .map(|(start, end)| start.l2_distance(end))
+            .sum()
+    }
+
+    /// Point at the given distance along the linestring from its start. Distances out of the
+    /// linestring are clamped to its endpoints.
+    ///
+    /// Along with `interpolate_fraction`, `locate_point` and `substring`, this provides linear
+    /// referencing as in PostGIS `ST_LineInterpolatePoint`, `ST_LineLocatePoint` and
+    /// `ST_LineSubstring`.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{LineString, Point};
+    /// let route = LineString::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(3.0, 0.0),
+    ///     Point::new(3.0, 4.0),
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(route.length(), 7.0);
+    /// assert_eq!(route.interpolate(5.0).unwrap().coords(), (3.0, 2.0));
+    /// assert_eq!(route.interpolate_fraction(0.5).unwrap().coords(), (3.0, 0.5));
+    ///
+    /// // Snap an event next to the route
+    /// let fraction = route.locate_point(&Point::new(4.0, 1.0));
+    /// assert_eq!(fraction, 4.0 / 7.0);
+    ///
+    /// let part = route.substring(0.0, fraction).unwrap();
+    /// assert_eq!(part.points.len(), 3);
+    /// assert_eq!(part.length(), 4.0);
+    /// ```
+    pub fn interpolate(&self, distance: f64) -> GeomResult<Point> {
+        if distance.is_nan() {
+            return Err(GeometryError::ParameterError(String::from(
+                "The distance to interpolate at must be a number",
+            )));
+        }
+        let mut travelled = 0.0;
+        if distance > 0.0 {
+            for seg in self.segments() {
+                let length = seg.length();
+                if travelled + length >= distance && length > 0.0 {
+                    return Ok(seg.point_at((distance - travelled) / length));
+                }
+                travelled += length;
+            }
+        }
+        let end = if distance > 0.0 {
+            self.points.last()
+        } else {
+            self.points.first()
+        };
+        Ok(end.unwrap().clone())
+    }
+
+    /// Point at the given fraction of the length of the linestring, from 0 at its start to 1 at
+    /// its end
+    pub fn interpolate_fraction(&self, fraction: f64) -> GeomResult<Point> {
+        check_fraction(fraction)?;
+        self.interpolate(fraction * self.length())
+    }
+
+    /// Fraction of the length of the linestring at which its point closest to `pt` lies. If
+    /// several points are the closest, the first one along the linestring is used.
+    pub fn locate_point(&self, pt: &Point) -> f64 {
+        let total = self.length();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let mut travelled = 0.0;
+        let (mut closest, mut measure) = (f64::INFINITY, 0.0);
+        for seg in self.segments() {
+            let length = seg.length();
+            let t = seg.project(pt).clamp(0.0, 1.0);
+            let distance = seg.point_at(t).l2_distance(pt);
+            if distance < closest {
+                closest = distance;
+                measure = travelled + t * length;
+            }
+            travelled += length;
+        }
+        (measure / total).min(1.0)
+    }
+
+    /// Part of the linestring between two fractions of its length, with `start <= end`. The
+    /// vertices in between are kept; equal fractions give a degenerate linestring, with two
+    /// copies of the point.
+    pub fn substring(&self, start: f64, end: f64) -> GeomResult<LineString> {
+        check_fraction(start)?;
+        check_fraction(end)?;
+        if start > end {
+            return Err(GeometryError::ParameterError(format!(
+                "The start of the substring must not be after its end, got {start} > {end}"
+            )));
+        }
+        let total = self.length();
+        let (from, to) = (start * total, end * total);
+
+        let mut points = vec![self.interpolate(from)?];
+        let mut travelled = 0.0;
+        for seg in self.segments() {
+            travelled += seg.length();
+            if travelled >= to {
+                break;
+            }
+            if travelled > from {
+                points.push(seg.end.clone());
+            }
+        }
+        points.push(self.interpolate(to)?);
+        LineString::new(points)
+    }
+}
+
+fn check_fraction(fraction: f64) -> GeomResult<()> {
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(())
+    } else {
+        Err(GeometryError::ParameterError(format!(
+            "Fractions of the length must be between 0 and 1, got {fraction}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> LineString {
+        LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 4.0),
+            Point::new(0.0, 4.0),
+        ])
+        .unwrap()
+    }
+
+    fn coords(line: &LineString) -> Vec<(f64, f64)> {
+        line.points.iter().map(|p| p.coords()).collect()
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let line = route();
+        assert_eq!(line.length(), 10.0);
+        assert_eq!(line.interpolate(0.0).unwrap().coords(), (0.0, 0.0));
+        assert_eq!(line.interpolate(3.0).unwrap().coords(), (3.0, 0.0));
+        assert_eq!(line.interpolate(8.5).unwrap().coords(), (1.5, 4.0));
+        assert_eq!(line.interpolate(10.0).unwrap().coords(), (0.0, 4.0));
+        // Clamped
+        assert_eq!(line.interpolate(-2.0).unwrap().coords(), (0.0, 0.0));
+        assert_eq!(line.interpolate(12.0).unwrap().coords(), (0.0, 4.0));
+        assert!(line.interpolate(f64::NAN).is_err());
+
+        assert_eq!(line.interpolate_fraction(0.4).unwrap().coords(), (3.0, 1.0));
+        assert!(line.interpolate_fraction(1.5).is_err());
+    }
+
+    #[test]
+    fn test_locate_point() {
+        let line = route();
+        assert_eq!(line.locate_point(&Point::new(-1.0, -1.0)), 0.0);
+        assert_eq!(line.locate_point(&Point::new(5.0, 2.0)), 0.5);
+        assert_eq!(line.locate_point(&Point::new(-3.0, 9.0)), 1.0);
+        // Equidistant from the first and last edges: the first one is used
+        assert_eq!(line.locate_point(&Point::new(1.0, 2.0)), 0.1);
+
+        // Round trip
+        for i in 0..=20 {
+            let fraction = i as f64 / 20.0;
+            let pt = line.interpolate_fraction(fraction).unwrap();
+            assert!((line.locate_point(&pt) - fraction).abs() < 1e-12);
+        }
+
+        let degenerate = LineString::new(vec![Point::new(1.0, 1.0); 2]).unwrap();
+        assert_eq!(degenerate.locate_point(&Point::new(5.0, 2.0)), 0.0);
+        assert_eq!(degenerate.interpolate(1.0).unwrap().coords(), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_substring() {
+        let line = route();
+        assert_eq!(
+            coords(&line.substring(0.1, 0.85).unwrap()),
+            vec![(1.0, 0.0), (3.0, 0.0), (3.0, 0.0), (3.0, 4.0), (1.5, 4.0)]
+        );
+        // Starting and ending at vertices
+        assert_eq!(
+            coords(&line.substring(0.3, 0.7).unwrap()),
+            vec![(3.0, 0.0), (3.0, 4.0)]
+        );
+        assert_eq!(coords(&line.substring(0.0, 1.0).unwrap()).len(), 5);
+        assert_eq!(
+            coords(&line.substring(0.5, 0.5).unwrap()),
+            vec![(3.0, 2.0), (3.0, 2.0)]
+        );
+        assert!(line.substring(0.6, 0.5).is_err());
+        assert!(line.substring(-0.1, 0.5).is_err());
+    }
+}
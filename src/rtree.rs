@@ -0,0 +1,652 @@
+use super::core::{GeomResult, GeometryError};
+use super::envelope::{Bounded, Envelope};
+use super::points::Point;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Default maximum number of entries in a node of an R-tree
+const DEFAULT_MAX_ENTRIES: usize = 16;
+
+/// R-tree spatial index over objects with bounding boxes.
+///
+/// The tree can be bulk-loaded with the Sort-Tile-Recursive algorithm, which packs the objects
+/// in well-balanced nodes, and updated with incremental insertions and deletions. Queries return
+/// the objects whose envelope intersects an envelope or contains a point, which should then be
+/// tested exactly, or the nearest object to a point.
+///
+/// Objects with an empty envelope, like empty geometries, are stored but never found by queries.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Point, Polygon, RTree};
+/// let squares: Vec<Polygon> = (0..100)
+///     .map(|i| {
+///         let (x, y) = ((i % 10) as f64, (i / 10) as f64);
+///         let corners = [(0.0, 0.0), (0.0, 0.5), (0.5, 0.5), (0.5, 0.0), (0.0, 0.0)];
+///         Polygon::new(corners.iter().map(|(dx, dy)| Point::new(x + dx, y + dy)).collect())
+///             .unwrap()
+///     })
+///     .collect();
+/// let tree = RTree::bulk_load(squares);
+///
+/// let pt = Point::new(3.25, 7.25);
+/// let hits: Vec<&Polygon> = tree
+///     .query_point(&pt)
+///     .into_iter()
+///     .filter(|square| square.contains(&pt))
+///     .collect();
+/// assert_eq!(hits.len(), 1);
+///
+/// let nearest = tree.nearest(&Point::new(3.6, 7.25)).unwrap();
+/// assert!(nearest.contains(&pt));
+/// ```
+#[derive(Clone, Debug)]
+pub struct RTree<T> {
+    root: Node<T>,
+    max_entries: usize,
+    len: usize,
+}
+
+/// Object stored in a leaf, with its envelope
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    envelope: Envelope,
+    item: T,
+}
+
+#[derive(Clone, Debug)]
+enum Children<T> {
+    Leaf(Vec<Entry<T>>),
+    Internal(Vec<Node<T>>),
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    envelope: Envelope,
+    children: Children<T>,
+}
+
+impl<T: Bounded> RTree<T> {
+    /// Instantiate an empty tree
+    pub fn new() -> Self {
+        Self {
+            root: Node::leaf(Vec::new()),
+            max_entries: DEFAULT_MAX_ENTRIES,
+            len: 0,
+        }
+    }
+
+    /// Instantiate an empty tree with at most `max_entries` entries per node, which must be at
+    /// least 4
+    pub fn with_max_entries(max_entries: usize) -> GeomResult<Self> {
+        if max_entries < 4 {
+            return Err(GeometryError::ParameterError(format!(
+                "R-tree nodes must hold at least 4 entries, got {max_entries}"
+            )));
+        }
+        Ok(Self {
+            max_entries,
+            ..Self::new()
+        })
+    }
+
+    /// Build a tree from a set of objects with the Sort-Tile-Recursive algorithm
+    pub fn bulk_load(items: Vec<T>) -> Self {
+        let mut tree = Self::new();
+        tree.load(items);
+        tree
+    }
+
+    /// Build a tree with at most `max_entries` entries per node from a set of objects, with the
+    /// Sort-Tile-Recursive algorithm
+    pub fn bulk_load_with_max_entries(items: Vec<T>, max_entries: usize) -> GeomResult<Self> {
+        let mut tree = Self::with_max_entries(max_entries)?;
+        tree.load(items);
+        Ok(tree)
+    }
+
+    /// Replace the content of the tree with a packed tree of the given objects
+    fn load(&mut self, items: Vec<T>) {
+        self.len = items.len();
+        let entries = items
+            .into_iter()
+            .map(|item| Entry {
+                envelope: item.envelope(),
+                item,
+            })
+            .collect();
+        let mut nodes: Vec<Node<T>> = str_pack(entries, self.max_entries, |e| e.envelope)
+            .into_iter()
+            .map(Node::leaf)
+            .collect();
+        while nodes.len() > 1 {
+            nodes = str_pack(nodes, self.max_entries, |node| node.envelope)
+                .into_iter()
+                .map(Node::internal)
+                .collect();
+        }
+        self.root = nodes.pop().unwrap_or_else(|| Node::leaf(Vec::new()));
+    }
+
+    /// Number of objects in the tree
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bounding box of all the objects in the tree
+    pub fn envelope(&self) -> Envelope {
+        self.root.envelope
+    }
+
+    /// Iterate over all the objects in the tree, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut items = Vec::with_capacity(self.len);
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            match &node.children {
+                Children::Leaf(entries) => items.extend(entries.iter().map(|e| &e.item)),
+                Children::Internal(nodes) => stack.extend(nodes),
+            }
+        }
+        items.into_iter()
+    }
+
+    /// Insert an object in the tree
+    pub fn insert(&mut self, item: T) {
+        let entry = Entry {
+            envelope: item.envelope(),
+            item,
+        };
+        self.insert_entry(entry);
+        self.len += 1;
+    }
+
+    fn insert_entry(&mut self, entry: Entry<T>) {
+        if let Some(sibling) = self.root.insert(entry, self.max_entries) {
+            // The root was split: grow the tree by one level
+            let root = std::mem::replace(&mut self.root, Node::leaf(Vec::new()));
+            self.root = Node::internal(vec![root, sibling]);
+        }
+    }
+
+    /// Remove an object whose envelope intersects `envelope` and for which `matches` returns
+    /// true, and return it. Returns None if there is no such object.
+    ///
+    /// Nodes left with too few entries are dissolved and their objects inserted again, so that
+    /// the tree stays balanced.
+    pub fn remove(&mut self, envelope: &Envelope, matches: impl Fn(&T) -> bool) -> Option<T> {
+        let mut orphans = Vec::new();
+        let min_entries = self.max_entries / 2;
+        let removed = self
+            .root
+            .remove(envelope, &matches, min_entries, &mut orphans)?;
+        self.len -= 1;
+
+        // Shrink the tree while the root has a single child
+        loop {
+            match &mut self.root.children {
+                Children::Internal(nodes) if nodes.len() <= 1 => {
+                    self.root = nodes.pop().unwrap_or_else(|| Node::leaf(Vec::new()));
+                }
+                _ => break,
+            }
+        }
+        for entry in orphans {
+            self.insert_entry(entry);
+        }
+        Some(removed)
+    }
+
+    /// Objects whose envelope intersects the given envelope
+    pub fn query_envelope(&self, envelope: &Envelope) -> Vec<&T> {
+        let mut out = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            if !node.envelope.intersects(envelope) {
+                continue;
+            }
+            match &node.children {
+                Children::Leaf(entries) => out.extend(
+                    entries
+                        .iter()
+                        .filter(|e| e.envelope.intersects(envelope))
+                        .map(|e| &e.item),
+                ),
+                Children::Internal(nodes) => stack.extend(nodes),
+            }
+        }
+        out
+    }
+
+    /// Objects whose envelope contains the given point: candidates for an exact test
+    pub fn query_point(&self, pt: &Point) -> Vec<&T> {
+        self.query_envelope(&Envelope::from_point(pt))
+    }
+
+    /// Object closest to the given point, according to `Bounded::distance_to`. Returns None if
+    /// the tree is empty.
+    ///
+    /// The nodes and objects are visited by increasing distance of their envelopes, which are
+    /// lower bounds of the distances to the objects, until an object is closer than all the
+    /// remaining envelopes.
+    pub fn nearest(&self, pt: &Point) -> Option<&T> {
+        let mut heap = BinaryHeap::new();
+        heap.push(Candidate {
+            distance: self.root.envelope.distance_to(pt),
+            target: Target::Node(&self.root),
+        });
+        while let Some(Candidate { target, .. }) = heap.pop() {
+            match target {
+                Target::Item(item) => return Some(item),
+                Target::Entry(entry) => heap.push(Candidate {
+                    distance: entry.item.distance_to(pt),
+                    target: Target::Item(&entry.item),
+                }),
+                Target::Node(node) => match &node.children {
+                    Children::Leaf(entries) => heap.extend(entries.iter().map(|e| Candidate {
+                        distance: e.envelope.distance_to(pt),
+                        target: Target::Entry(e),
+                    })),
+                    Children::Internal(nodes) => heap.extend(nodes.iter().map(|n| Candidate {
+                        distance: n.envelope.distance_to(pt),
+                        target: Target::Node(n),
+                    })),
+                },
+            }
+        }
+        None
+    }
+}
+
+impl<T: Bounded> Default for RTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Bounded> FromIterator<T> for RTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::bulk_load(iter.into_iter().collect())
+    }
+}
+
+impl<T> Node<T> {
+    fn leaf(entries: Vec<Entry<T>>) -> Self {
+        let envelope = union_all(entries.iter().map(|e| e.envelope));
+        Self {
+            envelope,
+            children: Children::Leaf(entries),
+        }
+    }
+
+    fn internal(nodes: Vec<Node<T>>) -> Self {
+        let envelope = union_all(nodes.iter().map(|n| n.envelope));
+        Self {
+            envelope,
+            children: Children::Internal(nodes),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match &self.children {
+            Children::Leaf(entries) => entries.len(),
+            Children::Internal(nodes) => nodes.len(),
+        }
+    }
+
+    fn update_envelope(&mut self) {
+        self.envelope = match &self.children {
+            Children::Leaf(entries) => union_all(entries.iter().map(|e| e.envelope)),
+            Children::Internal(nodes) => union_all(nodes.iter().map(|n| n.envelope)),
+        };
+    }
+
+    /// Insert an entry in the subtree. If the node overflows, it is split and the new sibling
+    /// is returned, to be added to the parent.
+    fn insert(&mut self, entry: Entry<T>, max_entries: usize) -> Option<Node<T>> {
+        self.envelope = self.envelope.union(&entry.envelope);
+        match &mut self.children {
+            Children::Leaf(entries) => {
+                entries.push(entry);
+                if entries.len() <= max_entries {
+                    return None;
+                }
+                let sibling = split(entries, |e| e.envelope);
+                self.update_envelope();
+                Some(Node::leaf(sibling))
+            }
+            Children::Internal(nodes) => {
+                let best = choose_subtree(nodes, &entry.envelope);
+                let sibling = nodes[best].insert(entry, max_entries)?;
+                nodes.push(sibling);
+                if nodes.len() <= max_entries {
+                    return None;
+                }
+                let sibling = split(nodes, |n| n.envelope);
+                self.update_envelope();
+                Some(Node::internal(sibling))
+            }
+        }
+    }
+
+    /// Remove a matching entry from the subtree. Children left with less than `min_entries`
+    /// entries are removed, and their entries collected in `orphans`.
+    fn remove(
+        &mut self,
+        envelope: &Envelope,
+        matches: &impl Fn(&T) -> bool,
+        min_entries: usize,
+        orphans: &mut Vec<Entry<T>>,
+    ) -> Option<T> {
+        let removed = match &mut self.children {
+            Children::Leaf(entries) => {
+                let pos = entries
+                    .iter()
+                    .position(|e| e.envelope.intersects(envelope) && matches(&e.item))?;
+                entries.swap_remove(pos).item
+            }
+            Children::Internal(nodes) => {
+                let (i, item) = nodes
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(_, node)| node.envelope.intersects(envelope))
+                    .find_map(|(i, node)| {
+                        node.remove(envelope, matches, min_entries, orphans)
+                            .map(|item| (i, item))
+                    })?;
+                if nodes[i].len() < min_entries {
+                    nodes.swap_remove(i).collect_entries(orphans);
+                }
+                item
+            }
+        };
+        self.update_envelope();
+        Some(removed)
+    }
+
+    /// Move all the entries of the subtree to `out`
+    fn collect_entries(self, out: &mut Vec<Entry<T>>) {
+        match self.children {
+            Children::Leaf(entries) => out.extend(entries),
+            Children::Internal(nodes) => {
+                for node in nodes {
+                    node.collect_entries(out);
+                }
+            }
+        }
+    }
+}
+
+/// Candidate of the nearest neighbour search
+struct Candidate<'a, T> {
+    distance: f64,
+    target: Target<'a, T>,
+}
+
+enum Target<'a, T> {
+    Node(&'a Node<T>),
+    /// Entry whose distance is bounded by its envelope
+    Entry(&'a Entry<T>),
+    /// Object with its exact distance
+    Item(&'a T),
+}
+
+impl<T> PartialEq for Candidate<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Candidate<'_, T> {}
+
+impl<T> PartialOrd for Candidate<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Candidate<'_, T> {
+    /// Reversed ordering so that the binary heap pops the closest candidate first. At equal
+    /// distances objects come first, so that the search stops as early as possible.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rank = |target: &Target<T>| match target {
+            Target::Item(_) => 0,
+            Target::Entry(_) => 1,
+            Target::Node(_) => 2,
+        };
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| rank(&other.target).cmp(&rank(&self.target)))
+    }
+}
+
+fn union_all(envelopes: impl Iterator<Item = Envelope>) -> Envelope {
+    envelopes.fold(Envelope::EMPTY, |acc, env| acc.union(&env))
+}
+
+/// Coordinates of the center of an envelope, sortable even if it is empty
+fn center(envelope: &Envelope) -> (f64, f64) {
+    (
+        (envelope.min_x + envelope.max_x) / 2.0,
+        (envelope.min_y + envelope.max_y) / 2.0,
+    )
+}
+
+/// Index of the child needing the least enlargement to include an envelope, then the smallest
+fn choose_subtree<T>(nodes: &[Node<T>], envelope: &Envelope) -> usize {
+    let area = |env: &Envelope| env.width() * env.height();
+    let cost = |node: &Node<T>| {
+        let current = area(&node.envelope);
+        (area(&node.envelope.union(envelope)) - current, current)
+    };
+    (0..nodes.len())
+        .min_by(|&a, &b| {
+            let ((grow_a, area_a), (grow_b, area_b)) = (cost(&nodes[a]), cost(&nodes[b]));
+            grow_a.total_cmp(&grow_b).then(area_a.total_cmp(&area_b))
+        })
+        .unwrap()
+}
+
+/// Split an overflowing node in two halves along the axis with the largest spread of centers,
+/// and return the second half
+fn split<E>(items: &mut Vec<E>, envelope: impl Fn(&E) -> Envelope) -> Vec<E> {
+    let centers = union_all(items.iter().map(|item| {
+        let (x, y) = center(&envelope(item));
+        Envelope::new(x, y, x, y)
+    }));
+    if centers.width() >= centers.height() {
+        items.sort_by(|a, b| center(&envelope(a)).0.total_cmp(&center(&envelope(b)).0));
+    } else {
+        items.sort_by(|a, b| center(&envelope(a)).1.total_cmp(&center(&envelope(b)).1));
+    }
+    items.split_off(items.len() / 2)
+}
+
+/// Sort-Tile-Recursive packing: group items in nodes of at most `max_entries`, by sorting them
+/// in vertical slices along x, then along y within each slice.
+fn str_pack<E>(
+    mut items: Vec<E>,
+    max_entries: usize,
+    envelope: impl Fn(&E) -> Envelope,
+) -> Vec<Vec<E>> {
+    let groups = items.len().div_ceil(max_entries);
+    let slices = (groups as f64).sqrt().ceil() as usize;
+    items.sort_by(|a, b| center(&envelope(a)).0.total_cmp(&center(&envelope(b)).0));
+
+    let mut out = Vec::with_capacity(groups);
+    for mut slice in chunks(items, slices * max_entries) {
+        slice.sort_by(|a, b| center(&envelope(a)).1.total_cmp(&center(&envelope(b)).1));
+        out.extend(chunks(slice, max_entries));
+    }
+    out
+}
+
+/// Split a vector in chunks of the given size, the last one being possibly smaller
+fn chunks<E>(items: Vec<E>, size: usize) -> Vec<Vec<E>> {
+    let mut out = Vec::with_capacity(items.len().div_ceil(size));
+    let mut iter = items.into_iter();
+    loop {
+        let chunk: Vec<E> = iter.by_ref().take(size).collect();
+        if chunk.is_empty() {
+            return out;
+        }
+        out.push(chunk);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::GeometricObject;
+    use super::super::polygons::Polygon;
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn random_boxes(total: usize) -> Vec<Envelope> {
+        let mut random = rng();
+        (0..total)
+            .map(|_| {
+                let (x, y): (f64, f64) = (random.random_range(0.0..100.0), random.random());
+                let y = y * 100.0;
+                let (w, h) = (random.random_range(0.0..5.0), random.random_range(0.0..5.0));
+                Envelope::new(x, y, x + w, y + h)
+            })
+            .collect()
+    }
+
+    /// Verify the structure of a subtree and return its depth
+    fn check_node<T: Bounded>(node: &Node<T>, max_entries: usize, is_root: bool) -> usize {
+        assert!(node.len() <= max_entries);
+        assert!(is_root || node.len() > 0);
+        match &node.children {
+            Children::Leaf(entries) => {
+                for entry in entries {
+                    assert_eq!(entry.envelope, entry.item.envelope());
+                    assert!(node.envelope.contains(&entry.envelope));
+                }
+                1
+            }
+            Children::Internal(nodes) => {
+                let depths: Vec<usize> = nodes
+                    .iter()
+                    .map(|child| {
+                        assert!(node.envelope.contains(&child.envelope));
+                        check_node(child, max_entries, false)
+                    })
+                    .collect();
+                // All the leaves are at the same depth
+                assert!(depths.iter().all(|&d| d == depths[0]));
+                depths[0] + 1
+            }
+        }
+    }
+
+    fn check_tree<T: Bounded>(tree: &RTree<T>) {
+        check_node(&tree.root, tree.max_entries, true);
+        assert_eq!(tree.iter().count(), tree.len());
+    }
+
+    fn check_queries(tree: &RTree<Envelope>, boxes: &[Envelope]) {
+        let mut random = rng();
+        for _ in 0..20 {
+            let (x, y) = (
+                random.random_range(0.0..100.0),
+                random.random_range(0.0..100.0),
+            );
+            let query = Envelope::new(x, y, x + 10.0, y + 10.0);
+            let expected = boxes.iter().filter(|b| b.intersects(&query)).count();
+            assert_eq!(tree.query_envelope(&query).len(), expected);
+
+            let pt = Point::new(x, y);
+            let expected = boxes.iter().filter(|b| b.contains_point(&pt)).count();
+            assert_eq!(tree.query_point(&pt).len(), expected);
+
+            let closest = boxes
+                .iter()
+                .map(|b| b.distance_to(&pt))
+                .fold(f64::INFINITY, f64::min);
+            assert_eq!(tree.nearest(&pt).unwrap().distance_to(&pt), closest);
+        }
+    }
+
+    #[test]
+    fn test_bulk_load() {
+        let boxes = random_boxes(1000);
+        let tree = RTree::bulk_load(boxes.clone());
+        assert_eq!(tree.len(), 1000);
+        check_tree(&tree);
+        check_queries(&tree, &boxes);
+
+        let tree = RTree::bulk_load_with_max_entries(boxes.clone(), 4).unwrap();
+        check_tree(&tree);
+        check_queries(&tree, &boxes);
+        assert!(RTree::bulk_load_with_max_entries(boxes, 3).is_err());
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let boxes = random_boxes(500);
+        let mut tree = RTree::with_max_entries(6).unwrap();
+        for b in &boxes {
+            tree.insert(*b);
+        }
+        assert_eq!(tree.len(), 500);
+        check_tree(&tree);
+        check_queries(&tree, &boxes);
+
+        let (removed, kept) = boxes.split_at(300);
+        for b in removed {
+            assert_eq!(tree.remove(b, |item| item == b), Some(*b));
+            check_tree(&tree);
+        }
+        assert_eq!(tree.len(), 200);
+        check_queries(&tree, kept);
+        assert!(
+            tree.remove(&removed[0], |item| item == &removed[0])
+                .is_none()
+        );
+
+        for b in kept {
+            assert!(tree.remove(b, |item| item == b).is_some());
+        }
+        assert!(tree.is_empty());
+        assert!(tree.envelope().is_empty());
+        check_tree(&tree);
+        assert!(tree.nearest(&Point::new(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_polygons() {
+        // Triangles pointing up, whose envelopes overlap their neighbours'
+        let triangles: RTree<Polygon> = (0..50)
+            .map(|i| {
+                let x = i as f64;
+                Polygon::new(vec![
+                    Point::new(x, 0.0),
+                    Point::new(x + 1.0, 2.0),
+                    Point::new(x + 2.0, 0.0),
+                    Point::new(x, 0.0),
+                ])
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(triangles.envelope(), Envelope::new(0.0, 0.0, 51.0, 2.0));
+
+        let pt = Point::new(10.0, 1.5);
+        let candidates = triangles.query_point(&pt);
+        assert_eq!(candidates.len(), 3);
+        let hits: Vec<&&Polygon> = candidates.iter().filter(|t| t.contains(&pt)).collect();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].outer[0].coords(), (9.0, 0.0));
+
+        // The closest envelope is not the closest triangle
+        let pt = Point::new(20.0, 3.0);
+        let nearest = triangles.nearest(&pt).unwrap();
+        assert_eq!(nearest.outer[1].coords(), (20.0, 2.0));
+        assert_eq!(nearest.area(), 2.0);
+    }
+}
@@ -0,0 +1,346 @@
+use super::core::{self, GeomResult, GeometryError};
+use super::ops::segments_intersect;
+use super::points::{Point, Turn, direction};
+use super::predicates::orient2d;
+
+/// Line segment between two points
+///
+/// Examples
+/// ```rust
+/// use geomlib::{Point, Segment, SegmentIntersection};
+/// let seg = Segment::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0));
+/// assert_eq!(seg.length(), 4.0);
+/// assert_eq!(seg.midpoint().coords(), (2.0, 0.0));
+/// assert_eq!(seg.closest_point(&Point::new(5.0, 3.0)).coords(), (4.0, 0.0));
+///
+/// let other = Segment::new(Point::new(6.0, 0.0), Point::new(3.0, 0.0));
+/// match seg.intersection(&other) {
+///     SegmentIntersection::Overlap(overlap) => {
+///         assert_eq!(overlap.start.coords(), (3.0, 0.0));
+///         assert_eq!(overlap.end.coords(), (4.0, 0.0));
+///     }
+///     _ => panic!("The segments overlap"),
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+/// Infinite line through two distinct points, oriented from the first to the second
+#[derive(Clone, Debug)]
+pub struct Line {
+    origin: Point,
+    through: Point,
+}
+
+/// Intersection of two segments
+#[derive(Clone, Debug)]
+pub enum SegmentIntersection {
+    None,
+    Point(Point),
+    /// Part shared by collinear segments, oriented like the first segment
+    Overlap(Segment),
+}
+
+impl Segment {
+    /// Instantiate a new segment
+    pub fn new(start: Point, end: Point) -> Self {
+        Self { start, end }
+    }
+
+    pub fn length(&self) -> f64 {
+        self.start.l2_distance(&self.end)
+    }
+
+    pub fn midpoint(&self) -> Point {
+        let (x1, y1) = self.start.coords();
+        let (x2, y2) = self.end.coords();
+        Point::new((x1 + x2) / 2.0, (y1 + y2) / 2.0)
+    }
+
+    /// Segment with the same endpoints, in the opposite direction
+    pub fn reversed(&self) -> Self {
+        Self::new(self.end.clone(), self.start.clone())
+    }
+
+    /// Line through the segment, if its endpoints are distinct
+    pub fn line(&self) -> GeomResult<Line> {
+        Line::new(self.start.clone(), self.end.clone())
+    }
+
+    /// Point at the fraction `t` of the segment, from 0 at its start to 1 at its end. Values
+    /// out of this range give points on the segment's line, beyond its endpoints.
+    pub fn point_at(&self, t: f64) -> Point {
+        let (x1, y1) = self.start.coords();
+        let (x2, y2) = self.end.coords();
+        Point::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1))
+    }
+
+    /// Fraction of the segment at which the orthogonal projection of `pt` onto its line lies,
+    /// see `point_at`. Returns 0 for a degenerate segment.
+    pub fn project(&self, pt: &Point) -> f64 {
+        let (x1, y1) = self.start.coords();
+        let (x2, y2) = self.end.coords();
+        let (x, y) = pt.coords();
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let len_sq = dx * dx + dy * dy;
+        if len_sq == 0.0 {
+            return 0.0;
+        }
+        ((x - x1) * dx + (y - y1) * dy) / len_sq
+    }
+
+    /// Point of the segment closest to `pt`
+    pub fn closest_point(&self, pt: &Point) -> Point {
+        self.point_at(self.project(pt).clamp(0.0, 1.0))
+    }
+
+    /// Distance from `pt` to the closest point of the segment
+    pub fn distance_to_point(&self, pt: &Point) -> f64 {
+        pt.distance_to_segment(&self.start, &self.end)
+    }
+
+    /// Determine whether the segments share at least a point, see `segments_intersect`
+    pub fn intersects(&self, other: &Segment) -> bool {
+        segments_intersect(self, other)
+    }
+
+    /// Compute the intersection of two segments: nothing, a single point, or the segment they
+    /// share if they are collinear and overlap.
+    ///
+    /// Segments touching at an endpoint, or collinear segments with a single common endpoint,
+    /// intersect at that point. Collinearity is decided with the current tolerance.
+    pub fn intersection(&self, other: &Segment) -> SegmentIntersection {
+        if !self.intersects(other) {
+            return SegmentIntersection::None;
+        }
+        let (a, b) = (&self.start, &self.end);
+        let (c, d) = (&other.start, &other.end);
+        let (d1, d2) = (direction(a, b, c), direction(a, b, d));
+        if d1 == Turn::InLine && d2 == Turn::InLine {
+            return self.overlap(other);
+        }
+
+        // Touching at an endpoint lying on the other segment
+        let on = |pt: &Point, seg: &Segment| {
+            direction(&seg.start, &seg.end, pt) == Turn::InLine
+                && (0.0..=1.0).contains(&seg.project(pt))
+        };
+        for (pt, seg) in [(c, self), (d, self), (a, other), (b, other)] {
+            if on(pt, seg) {
+                return SegmentIntersection::Point(pt.clone());
+            }
+        }
+
+        // Proper crossing, at the ratio of the distances of a and b to the other line
+        let (o3, o4) = (orient2d(c, d, a), orient2d(c, d, b));
+        SegmentIntersection::Point(self.point_at(o3 / (o3 - o4)))
+    }
+
+    /// Part shared by two intersecting collinear segments, measured along the longest
+    fn overlap(&self, other: &Segment) -> SegmentIntersection {
+        let (base, seg) = if self.length() >= other.length() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let (ts, te) = (base.project(&seg.start), base.project(&seg.end));
+        let (low, high) = if ts <= te {
+            ((ts, &seg.start), (te, &seg.end))
+        } else {
+            ((te, &seg.end), (ts, &seg.start))
+        };
+        let first = if low.0 > 0.0 { low.1 } else { &base.start };
+        let last = if high.0 < 1.0 { high.1 } else { &base.end };
+        if first.is_close(last) {
+            return SegmentIntersection::Point(first.clone());
+        }
+
+        let overlap = Segment::new(first.clone(), last.clone());
+        if self.project(first) <= self.project(last) {
+            SegmentIntersection::Overlap(overlap)
+        } else {
+            SegmentIntersection::Overlap(overlap.reversed())
+        }
+    }
+}
+
+impl Line {
+    /// Instantiate the line through two points, which must be distinct
+    pub fn new(origin: Point, through: Point) -> GeomResult<Self> {
+        if origin.coords() == through.coords() {
+            return Err(GeometryError::ParameterError(String::from(
+                "A line must go through two distinct points",
+            )));
+        }
+        Ok(Self { origin, through })
+    }
+
+    pub fn origin(&self) -> &Point {
+        &self.origin
+    }
+
+    pub fn through(&self) -> &Point {
+        &self.through
+    }
+
+    /// Side of the line on which `pt` lies, looking along the line
+    pub fn side(&self, pt: &Point) -> Turn {
+        direction(&self.origin, &self.through, pt)
+    }
+
+    /// Determine whether `pt` lies on the line, with the current tolerance
+    pub fn contains(&self, pt: &Point) -> bool {
+        self.side(pt) == Turn::InLine
+    }
+
+    /// Orthogonal projection of `pt` onto the line, which is the closest point of the line
+    pub fn project(&self, pt: &Point) -> Point {
+        let seg = Segment::new(self.origin.clone(), self.through.clone());
+        seg.point_at(seg.project(pt))
+    }
+
+    /// Distance from `pt` to the line
+    pub fn distance_to(&self, pt: &Point) -> f64 {
+        orient2d(&self.origin, &self.through, pt).abs() / self.origin.l2_distance(&self.through)
+    }
+
+    /// Compute the intersection point of two lines, None if they are parallel
+    pub fn intersection(&self, other: &Line) -> Option<Point> {
+        let (x1, y1) = self.origin.coords();
+        let (x2, y2) = self.through.coords();
+        let (u1, v1) = other.origin.coords();
+        let (u2, v2) = other.through.coords();
+        let det = (x2 - x1) * (v2 - v1) - (y2 - y1) * (u2 - u1);
+        if core::approx(det, 0.0) {
+            return None;
+        }
+        let t = ((u1 - x1) * (v2 - v1) - (v1 - y1) * (u2 - u1)) / det;
+        Some(Point::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(x1: f64, y1: f64, x2: f64, y2: f64) -> Segment {
+        Segment::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    /// Coordinates of the intersection, as a list of points
+    fn intersection(s1: &Segment, s2: &Segment) -> Vec<(f64, f64)> {
+        match s1.intersection(s2) {
+            SegmentIntersection::None => vec![],
+            SegmentIntersection::Point(pt) => vec![pt.coords()],
+            SegmentIntersection::Overlap(seg) => vec![seg.start.coords(), seg.end.coords()],
+        }
+    }
+
+    #[test]
+    fn test_measures() {
+        let seg = segment(1.0, 1.0, 4.0, 5.0);
+        assert_eq!(seg.length(), 5.0);
+        assert_eq!(seg.midpoint().coords(), (2.5, 3.0));
+        assert_eq!(seg.project(&Point::new(4.0, 5.0)), 1.0);
+        assert_eq!(seg.project(&Point::new(-2.0, -3.0)), -1.0);
+        assert_eq!(
+            seg.closest_point(&Point::new(-2.0, -3.0)).coords(),
+            (1.0, 1.0)
+        );
+        assert!(
+            seg.closest_point(&Point::new(6.5, 0.0))
+                .is_close(&Point::new(2.5, 3.0))
+        );
+        assert!(core::approx(
+            seg.distance_to_point(&Point::new(6.5, 0.0)),
+            5.0
+        ));
+
+        let point = segment(2.0, 2.0, 2.0, 2.0);
+        assert_eq!(point.length(), 0.0);
+        assert_eq!(
+            point.closest_point(&Point::new(0.0, 0.0)).coords(),
+            (2.0, 2.0)
+        );
+        assert!(point.line().is_err());
+    }
+
+    #[test]
+    fn test_point_intersections() {
+        let seg = segment(0.0, 0.0, 4.0, 4.0);
+        assert_eq!(
+            intersection(&seg, &segment(0.0, 4.0, 4.0, 0.0)),
+            vec![(2.0, 2.0)]
+        );
+        // Touching at endpoints, or an endpoint inside the other segment
+        assert_eq!(
+            intersection(&seg, &segment(4.0, 4.0, 6.0, 0.0)),
+            vec![(4.0, 4.0)]
+        );
+        assert_eq!(
+            intersection(&segment(1.0, 1.0, 3.0, 0.0), &seg),
+            vec![(1.0, 1.0)]
+        );
+        // Collinear, sharing only an endpoint
+        assert_eq!(
+            intersection(&seg, &segment(6.0, 6.0, 4.0, 4.0)),
+            vec![(4.0, 4.0)]
+        );
+        // Disjoint: parallel, collinear and crossing lines
+        assert!(intersection(&seg, &segment(1.0, 0.0, 5.0, 4.0)).is_empty());
+        assert!(intersection(&seg, &segment(5.0, 5.0, 6.0, 6.0)).is_empty());
+        assert!(intersection(&seg, &segment(5.0, 0.0, 6.0, -1.0)).is_empty());
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let seg = segment(0.0, 0.0, 4.0, 4.0);
+        assert_eq!(
+            intersection(&seg, &segment(3.0, 3.0, 1.0, 1.0)),
+            vec![(1.0, 1.0), (3.0, 3.0)]
+        );
+        assert_eq!(
+            intersection(&seg, &segment(6.0, 6.0, 2.0, 2.0)),
+            vec![(2.0, 2.0), (4.0, 4.0)]
+        );
+        // Longer other segment, in the opposite direction
+        assert_eq!(
+            intersection(&seg, &segment(8.0, 8.0, -1.0, -1.0)),
+            vec![(0.0, 0.0), (4.0, 4.0)]
+        );
+        assert_eq!(
+            intersection(&seg.reversed(), &segment(-1.0, -1.0, 2.0, 2.0)),
+            vec![(2.0, 2.0), (0.0, 0.0)]
+        );
+        // Vertical, and a degenerate segment inside another
+        assert_eq!(
+            intersection(&segment(1.0, 0.0, 1.0, 3.0), &segment(1.0, 5.0, 1.0, 2.0)),
+            vec![(1.0, 2.0), (1.0, 3.0)]
+        );
+        assert_eq!(
+            intersection(&segment(2.0, 2.0, 2.0, 2.0), &seg),
+            vec![(2.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        let line = segment(0.0, 1.0, 2.0, 1.0).line().unwrap();
+        assert_eq!(line.side(&Point::new(5.0, 3.0)), Turn::Left);
+        assert!(line.contains(&Point::new(-7.0, 1.0)));
+        assert_eq!(line.project(&Point::new(-3.0, 4.0)).coords(), (-3.0, 1.0));
+        assert_eq!(line.distance_to(&Point::new(-3.0, 4.0)), 3.0);
+
+        let other = Line::new(Point::new(5.0, 0.0), Point::new(6.0, 2.0)).unwrap();
+        assert!(
+            line.intersection(&other)
+                .unwrap()
+                .is_close(&Point::new(5.5, 1.0))
+        );
+        let parallel = Line::new(Point::new(0.0, 0.0), Point::new(-1.0, 0.0)).unwrap();
+        assert!(line.intersection(&parallel).is_none());
+    }
+}
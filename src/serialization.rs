@@ -11,6 +11,7 @@ pub enum GeomWrapper {
     Point(Point),
     MultiPoint(MultiPoint),
     LineString(LineString),
+    MultiPolygon(MultiPolygon),
 }
 
 type ParserResult<'a, T> = GeomResult<(T, &'a str)>;
@@ -20,6 +20,7 @@ enum GeomType {
     Point,
     MultiPoint,
     LineString,
+    MultiPolygon,
 }
 
 /// Macro to verify the starting characters of a string.
@@ -92,6 +93,10 @@ pub fn parse_wkt(raw_str: String) -> GeomResult<GeomWrapper> {
             let (ls, tail) = parse_linestring(rest)?;
             (GeomWrapper::LineString(ls), tail)
         }
+        (GeomType::MultiPolygon, rest) => {
+            let (mp, tail) = parse_multipolygon(rest)?;
+            (GeomWrapper::MultiPolygon(mp), tail)
+        }
     };
     if !trailing.trim().is_empty() {
         Err(GeometryError::ParsingError(String::from(
@@ -113,6 +118,7 @@ fn identify_type<'a>(raw_str: &'a str) -> ParserResult<'a, GeomType> {
             "POINT" => Ok((GeomType::Point, &raw_str[end..])),
             "MULTIPOINT" => Ok((GeomType::MultiPoint, &raw_str[end..])),
             "LINESTRING" => Ok((GeomType::LineString, &raw_str[end..])),
+            "MULTIPOLYGON" => Ok((GeomType::MultiPolygon, &raw_str[end..])),
             _ => Err(GeometryError::ParsingError(format!(
                 "Unsupported Geometry: {trimmed}"
             ))),
@@ -244,15 +250,30 @@ fn parse_coordinate_list<'a>(raw_str: &'a str) -> ParserResult<'a, Vec<Point>> {
     }
 }
 
-// Parse a polygon from the given wkt string with type prefix removed
+// Parse a polygon (outer ring and holes) from the given wkt string with type prefix removed
 fn parse_polygon<'a>(raw_str: &'a str) -> ParserResult<'a, Polygon> {
-    let trimmed = check_string_prefix!(raw_str, '(');
-    let (outer_ring, mut rest) = parse_coordinate_list(trimmed)?;
-    rest = check_string_prefix!(rest, ')');
-    Ok((Polygon::new(outer_ring)?, rest))
+    let (mut rings, rest) = parse_series(parse_coordinate_list, raw_str)?;
+    let holes = rings.split_off(1);
+    let outer = rings.pop().unwrap();
+    Ok((Polygon::with_holes(outer, holes)?, rest))
+}
+
+/// Parse a list of polygons from a string with type prefix removed
+fn parse_multipolygon<'a>(raw_str: &'a str) -> ParserResult<'a, MultiPolygon> {
+    let trimmed = raw_str.trim_start();
+    if let Some(rest) = trimmed.strip_prefix("EMPTY") {
+        return Ok((MultiPolygon::new(Vec::new()), rest));
+    }
+    let (polygons, rest) = parse_series(parse_polygon, trimmed)?;
+    Ok((MultiPolygon::new(polygons), rest))
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::manual_range_contains,
+    clippy::redundant_pattern_matching,
+    clippy::single_match
+)]
 mod tests {
     use super::ops::convex_hull;
 
@@ -495,6 +516,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_polygon_with_holes() {
+        let wkt = "POLYGON((0 0, 0 4, 4 4, 4 0, 0 0), (1 1, 2 1, 2 2, 1 2, 1 1))";
+        match parse_wkt(String::from(wkt)) {
+            Ok(GeomWrapper::Polygon(poly)) => {
+                assert_eq!(poly.outer.len(), 5);
+                assert_eq!(poly.inner.len(), 1);
+                assert!(poly.inner[0][2].is_close(&Point::new(2.0, 2.0)));
+                assert_eq!(poly.wkt(), wkt);
+            }
+            Ok(_) => panic!("Expected a polygon!"),
+            Err(err) => panic!("Unable to parse polygon: {err}"),
+        }
+
+        if parse_wkt(String::from(
+            "POLYGON((0 0, 0 4, 4 4, 0 0), (1 1, 2 1, 2 2))",
+        ))
+        .is_ok()
+        {
+            panic!("Parsed invalid polygon (hole not closed)!");
+        }
+    }
+
+    #[test]
+    fn test_parse_multipolygon() {
+        let wkt = "MULTIPOLYGON(((0 0, 0 1, 1 1, 0 0)), ((5 5, 5 9, 9 9, 9 5, 5 5), (6 6, 7 6, 7 7, 6 6)))";
+        match parse_wkt(String::from(wkt)) {
+            Ok(GeomWrapper::MultiPolygon(mp)) => {
+                assert_eq!(mp.polygons.len(), 2);
+                assert!(mp.polygons[0].inner.is_empty());
+                assert_eq!(mp.polygons[1].inner.len(), 1);
+                assert_eq!(mp.wkt(), wkt);
+            }
+            Ok(_) => panic!("Expected a multipolygon!"),
+            Err(err) => panic!("Unable to parse multipolygon: {err}"),
+        }
+
+        match parse_wkt(String::from("MULTIPOLYGON EMPTY")) {
+            Ok(GeomWrapper::MultiPolygon(mp)) => assert!(mp.polygons.is_empty()),
+            _ => panic!("Unable to parse empty multipolygon"),
+        }
+
+        if parse_wkt(String::from("MULTIPOLYGON((0 0, 0 1, 1 1, 0 0))")).is_ok() {
+            panic!("Parsed invalid multipolygon (missing parentheses)!");
+        }
+    }
+
     #[test]
     fn test_parse_multipoint_valid() {
         match parse_wkt(String::from("MULTIPOINT(0 0, 1 0, 0.5 0.5, 0 1)")) {
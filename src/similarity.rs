@@ -0,0 +1,225 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::densify_points;
+use super::points::*;
+
+/// Compute the directed (one-sided) discrete Hausdorff distance from `a` to `b`.
+///
+/// This is the largest distance from a vertex of `a` to its nearest vertex in `b`. Note that
+/// this is not symmetric in general. If `densify` is given, both vertex sequences are first
+/// densified so that no segment is longer than the given value, which makes the result approach
+/// the continuous Hausdorff distance between the curves.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, LineString, Point};
+/// let a = LineString::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]).unwrap();
+/// let b = LineString::new(vec![Point::new(0.0, 1.0), Point::new(1.0, 1.0)]).unwrap();
+///
+/// let dist = geomlib::directed_hausdorff(&a.points, &b.points, None).unwrap();
+/// assert!(geomlib::approx(dist, 1.0));
+/// ```
+pub fn directed_hausdorff(a: &[Point], b: &[Point], densify: Option<f64>) -> GeomResult<f64> {
+    let (a, b) = prepare_sequences(a, b, densify)?;
+    Ok(directed_hausdorff_raw(&a, &b))
+}
+
+/// Compute the (symmetric) discrete Hausdorff distance between two vertex sequences.
+///
+/// The symmetric Hausdorff distance is the maximum of the directed distances in both directions.
+/// Vertex sequences can be taken directly from a `LineString`'s points or from a polygon's ring.
+/// See `directed_hausdorff` for the meaning of `densify`.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point, Polygon};
+/// let square = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(0.0, 1.0),
+///     Point::new(1.0, 1.0),
+///     Point::new(1.0, 0.0),
+///     Point::new(0.0, 0.0),
+/// ]).unwrap();
+/// let shifted = Polygon::new(vec![
+///     Point::new(0.5, 0.0),
+///     Point::new(0.5, 1.0),
+///     Point::new(1.5, 1.0),
+///     Point::new(1.5, 0.0),
+///     Point::new(0.5, 0.0),
+/// ]).unwrap();
+///
+/// let dist = geomlib::hausdorff_distance(&square.outer, &shifted.outer, None).unwrap();
+/// assert!(geomlib::approx(dist, 0.5));
+/// ```
+pub fn hausdorff_distance(a: &[Point], b: &[Point], densify: Option<f64>) -> GeomResult<f64> {
+    let (a, b) = prepare_sequences(a, b, densify)?;
+    Ok(directed_hausdorff_raw(&a, &b).max(directed_hausdorff_raw(&b, &a)))
+}
+
+/// Compute the discrete Fréchet distance between two vertex sequences.
+///
+/// The discrete Fréchet distance takes into account the order of the vertices: it is the
+/// smallest "leash length" needed to traverse both sequences from start to end, moving
+/// forwards only. It is computed with the dynamic programming method of Eiter and Mannila.
+/// See `directed_hausdorff` for the meaning of `densify`.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, LineString, Point};
+/// let a = LineString::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)]).unwrap();
+/// let b = LineString::new(vec![Point::new(1.0, 0.0), Point::new(0.0, 0.0)]).unwrap();
+///
+/// // Same shape, opposite directions
+/// let dist = geomlib::frechet_distance(&a.points, &b.points, None).unwrap();
+/// assert!(geomlib::approx(dist, 1.0));
+/// ```
+pub fn frechet_distance(a: &[Point], b: &[Point], densify: Option<f64>) -> GeomResult<f64> {
+    let (a, b) = prepare_sequences(a, b, densify)?;
+
+    // Only keep the previous row of the coupling table in memory
+    let mut prev = vec![0.0_f64; b.len()];
+    let mut current = vec![0.0_f64; b.len()];
+    for (i, p) in a.iter().enumerate() {
+        for (j, q) in b.iter().enumerate() {
+            let dist = p.l2_distance(q);
+            current[j] = match (i, j) {
+                (0, 0) => dist,
+                (0, _) => current[j - 1].max(dist),
+                (_, 0) => prev[0].max(dist),
+                _ => prev[j].min(prev[j - 1]).min(current[j - 1]).max(dist),
+            };
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+    Ok(prev[b.len() - 1])
+}
+
+/// Validate the inputs of the distance functions and densify them if required
+fn prepare_sequences(
+    a: &[Point],
+    b: &[Point],
+    densify: Option<f64>,
+) -> GeomResult<(Vec<Point>, Vec<Point>)> {
+    if a.is_empty() || b.is_empty() {
+        return Err(GeometryError::ParameterError(String::from(
+            "Cannot compute distances with an empty vertex sequence",
+        )));
+    }
+    match densify {
+        None => Ok((a.to_vec(), b.to_vec())),
+        Some(max_len) => Ok((densify_points(a, max_len)?, densify_points(b, max_len)?)),
+    }
+}
+
+/// Directed Hausdorff distance between two non-empty point sequences
+fn directed_hausdorff_raw(a: &[Point], b: &[Point]) -> f64 {
+    let mut max_dist: f64 = 0.0;
+    for p in a {
+        let mut min_dist = f64::INFINITY;
+        for q in b {
+            min_dist = min_dist.min(p.l2_distance(q));
+            if min_dist <= max_dist {
+                // Cannot increase the maximum: skip the rest of the sequence
+                break;
+            }
+        }
+        max_dist = max_dist.max(min_dist);
+    }
+    max_dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core;
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn random_sequence(total: usize) -> Vec<Point> {
+        let mut random = rng();
+        (0..total)
+            .map(|_| Point::new(random.random(), random.random()))
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_sequences() {
+        let pts = random_sequence(50);
+        assert_eq!(hausdorff_distance(&pts, &pts, None).unwrap(), 0.0);
+        assert_eq!(frechet_distance(&pts, &pts, None).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_directed_hausdorff() {
+        let a = vec![Point::new(0.0, 0.0), Point::new(1.0, 0.0)];
+        let b = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(3.0, 0.0),
+        ];
+
+        // All of a's vertices are in b, but not the other way around
+        assert_eq!(directed_hausdorff(&a, &b, None).unwrap(), 0.0);
+        assert!(core::approx(directed_hausdorff(&b, &a, None).unwrap(), 2.0));
+        assert!(core::approx(hausdorff_distance(&a, &b, None).unwrap(), 2.0));
+        assert!(core::approx(hausdorff_distance(&b, &a, None).unwrap(), 2.0));
+    }
+
+    #[test]
+    fn test_densified_hausdorff() {
+        // Every vertex of the first curve is a vertex of the second, but the curves differ
+        let a = vec![Point::new(0.0, 0.0), Point::new(2.0, 0.0)];
+        let b = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 0.0),
+        ];
+        assert!(core::approx(directed_hausdorff(&a, &b, None).unwrap(), 0.0));
+
+        let dense = directed_hausdorff(&a, &b, Some(0.01)).unwrap();
+        let expected = 0.5_f64.sqrt();
+        assert!((dense - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_frechet_distance() {
+        let a = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 0.0),
+        ];
+        let b = vec![
+            Point::new(0.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+        ];
+        assert!(core::approx(frechet_distance(&a, &b, None).unwrap(), 1.0));
+
+        // Reversing one of the sequences changes the Fréchet distance, not the Hausdorff distance
+        let mut rev = a.clone();
+        rev.reverse();
+        assert!(core::approx(frechet_distance(&a, &rev, None).unwrap(), 2.0));
+        assert!(core::approx(
+            hausdorff_distance(&a, &rev, None).unwrap(),
+            0.0
+        ));
+    }
+
+    #[test]
+    fn test_frechet_bounds_hausdorff() {
+        for _ in 0..20 {
+            let a = random_sequence(30);
+            let b = random_sequence(45);
+            let haus = hausdorff_distance(&a, &b, None).unwrap();
+            let frechet = frechet_distance(&a, &b, None).unwrap();
+            assert!(frechet >= haus);
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let pts = random_sequence(5);
+        assert!(hausdorff_distance(&pts, &[], None).is_err());
+        assert!(frechet_distance(&[], &pts, None).is_err());
+        assert!(hausdorff_distance(&pts, &pts, Some(0.0)).is_err());
+        assert!(frechet_distance(&pts, &pts, Some(-1.0)).is_err());
+    }
+}
@@ -0,0 +1,691 @@
+use super::core::{GeomResult, GeometryError};
+use super::linestring::LineString;
+use super::ops::segments_intersect;
+use super::points::*;
+use super::polygons::Polygon;
+use super::segment::Segment;
+use super::sweep::segment_intersections;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Algorithms available to simplify lines and polygons
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimplifyMethod {
+    /// Douglas-Peucker: the tolerance is the maximum distance from removed vertices to the
+    /// simplified line.
+    DouglasPeucker,
+    /// Visvalingam-Whyatt: the tolerance is the minimum effective area (triangle area) a vertex
+    /// must have to be kept.
+    VisvalingamWhyatt,
+}
+
+/// Simplify a sequence of points with the Douglas-Peucker algorithm.
+///
+/// Removes vertices from the sequence while keeping all removed vertices within `tolerance` of
+/// the simplified line. The first and last points are always preserved.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(1.0, 0.05),
+///     Point::new(2.0, -0.05),
+///     Point::new(3.0, 0.0),
+/// ];
+/// let simple = geomlib::douglas_peucker(&points, 0.1);
+/// assert_eq!(simple.len(), 2);
+/// ```
+pub fn douglas_peucker(points: &[Point], tolerance: f64) -> Vec<Point> {
+    select(points, &dp_indices(points, tolerance))
+}
+
+/// Simplify a sequence of points with the Visvalingam-Whyatt algorithm.
+///
+/// Repeatedly removes the vertex with the smallest effective area (the area of the triangle it
+/// forms with its neighbours) until all remaining vertices have an area of at least `min_area`.
+/// The first and last points are always preserved.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point};
+/// let points = vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(1.0, 0.05),
+///     Point::new(2.0, 0.0),
+///     Point::new(3.0, 2.0),
+///     Point::new(4.0, 0.0),
+/// ];
+/// let simple = geomlib::visvalingam_whyatt(&points, 0.5);
+/// assert_eq!(simple.len(), 4);
+/// ```
+pub fn visvalingam_whyatt(points: &[Point], min_area: f64) -> Vec<Point> {
+    select(points, &vw_indices(points, min_area, 2))
+}
+
+impl LineString {
+    /// Simplify the linestring with the given method and tolerance.
+    ///
+    /// The result may contain self-intersections that were not present in the original
+    /// linestring. Use `simplify_preserve_topology` to avoid this.
+    pub fn simplify(&self, method: SimplifyMethod, tolerance: f64) -> GeomResult<LineString> {
+        check_tolerance(tolerance)?;
+        let kept = simplify_indices(&self.points, method, tolerance, false);
+        LineString::new(select(&self.points, &kept))
+    }
+
+    /// Simplify the linestring, ensuring that no new self-intersections are introduced.
+    pub fn simplify_preserve_topology(
+        &self,
+        method: SimplifyMethod,
+        tolerance: f64,
+    ) -> GeomResult<LineString> {
+        check_tolerance(tolerance)?;
+        let rings = [self.points.as_slice()];
+        let mut kept = vec![simplify_indices(&self.points, method, tolerance, false)];
+        repair_topology(&rings, &mut kept, false);
+        LineString::new(select(&self.points, &kept[0]))
+    }
+}
+
+impl Polygon {
+    /// Simplify the polygon's rings with the given method and tolerance.
+    ///
+    /// The simplified rings always keep at least three distinct vertices, but they may
+    /// self-intersect or intersect each other. Use `simplify_preserve_topology` to avoid this.
+    pub fn simplify(&self, method: SimplifyMethod, tolerance: f64) -> GeomResult<Polygon> {
+        check_tolerance(tolerance)?;
+        let kept: Vec<Vec<usize>> = self
+            .rings()
+            .map(|ring| simplify_indices(ring, method, tolerance, true))
+            .collect();
+        self.with_kept_vertices(&kept)
+    }
+
+    /// Simplify the polygon, ensuring that no new intersections between its rings' edges
+    /// are introduced.
+    pub fn simplify_preserve_topology(
+        &self,
+        method: SimplifyMethod,
+        tolerance: f64,
+    ) -> GeomResult<Polygon> {
+        check_tolerance(tolerance)?;
+        let rings: Vec<&[Point]> = self.rings().map(|ring| ring.as_slice()).collect();
+        let mut kept: Vec<Vec<usize>> = rings
+            .iter()
+            .map(|ring| simplify_indices(ring, method, tolerance, true))
+            .collect();
+        repair_topology(&rings, &mut kept, true);
+        self.with_kept_vertices(&kept)
+    }
+
+    /// Build a polygon from the vertices kept in each of this polygon's rings
+    fn with_kept_vertices(&self, kept: &[Vec<usize>]) -> GeomResult<Polygon> {
+        let mut rings = self.rings().zip(kept).map(|(ring, idx)| select(ring, idx));
+        let outer = rings.next().unwrap();
+        Polygon::with_holes(outer, rings.collect())
+    }
+}
+
+/// Verify that the tolerance for a simplification is valid
+fn check_tolerance(tolerance: f64) -> GeomResult<()> {
+    if tolerance.is_nan() || tolerance < 0.0 {
+        Err(GeometryError::ParameterError(format!(
+            "Simplification tolerance must be non-negative, got {tolerance}"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Get the points at the given indices
+fn select(points: &[Point], indices: &[usize]) -> Vec<Point> {
+    indices.iter().map(|&i| points[i].clone()).collect()
+}
+
+/// Compute the indices of the vertices kept when simplifying a sequence of points. If `closed`
+/// is true, the sequence is treated as a polygon ring and at least 3 distinct vertices are kept.
+fn simplify_indices(
+    points: &[Point],
+    method: SimplifyMethod,
+    tolerance: f64,
+    closed: bool,
+) -> Vec<usize> {
+    match (method, closed) {
+        (SimplifyMethod::DouglasPeucker, false) => dp_indices(points, tolerance),
+        (SimplifyMethod::DouglasPeucker, true) => dp_ring_indices(points, tolerance),
+        (SimplifyMethod::VisvalingamWhyatt, false) => vw_indices(points, tolerance, 2),
+        (SimplifyMethod::VisvalingamWhyatt, true) => vw_indices(points, tolerance, 4),
+    }
+}
+
+/// Find the vertex strictly between `start` and `end` furthest from the segment joining them.
+fn furthest_vertex(points: &[Point], start: usize, end: usize) -> Option<(usize, f64)> {
+    let mut furthest = None;
+    for i in start + 1..end {
+        let dist = points[i].distance_to_segment(&points[start], &points[end]);
+        match furthest {
+            Some((_, max_dist)) if max_dist >= dist => (),
+            _ => furthest = Some((i, dist)),
+        }
+    }
+    furthest
+}
+
+/// Mark the vertices kept by the Douglas-Peucker algorithm in the range `start..=end`
+fn dp_mark(points: &[Point], start: usize, end: usize, tolerance: f64, keep: &mut [bool]) {
+    // Use an explicit stack to avoid deep recursion on long lines
+    let mut pending = vec![(start, end)];
+    while let Some((first, last)) = pending.pop() {
+        if let Some((idx, dist)) = furthest_vertex(points, first, last)
+            && dist > tolerance
+        {
+            keep[idx] = true;
+            pending.push((first, idx));
+            pending.push((idx, last));
+        }
+    }
+}
+
+/// Indices of the vertices kept by the Douglas-Peucker algorithm
+fn dp_indices(points: &[Point], tolerance: f64) -> Vec<usize> {
+    if points.len() < 3 {
+        return (0..points.len()).collect();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    dp_mark(points, 0, points.len() - 1, tolerance, &mut keep);
+
+    (0..points.len()).filter(|&i| keep[i]).collect()
+}
+
+/// Indices of the vertices kept by the Douglas-Peucker algorithm on a closed ring
+fn dp_ring_indices(points: &[Point], tolerance: f64) -> Vec<usize> {
+    let last = points.len() - 1;
+    if last < 4 {
+        return (0..points.len()).collect();
+    }
+
+    // Split the ring at the vertex furthest from the start, which is always kept
+    let mut split = 1;
+    for i in 1..last {
+        if points[i].l2_distance(&points[0]) > points[split].l2_distance(&points[0]) {
+            split = i;
+        }
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[split] = true;
+    keep[last] = true;
+    dp_mark(points, 0, split, tolerance, &mut keep);
+    dp_mark(points, split, last, tolerance, &mut keep);
+
+    if keep.iter().filter(|&&k| k).count() < 4 {
+        // Collapsed to a line: keep the most significant vertex from either half
+        let candidates = [
+            furthest_vertex(points, 0, split),
+            furthest_vertex(points, split, last),
+        ];
+        let best = candidates
+            .into_iter()
+            .flatten()
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((idx, _)) = best {
+            keep[idx] = true;
+        }
+    }
+
+    (0..points.len()).filter(|&i| keep[i]).collect()
+}
+
+/// Vertex candidate for removal in the Visvalingam-Whyatt algorithm
+struct VwCandidate {
+    area: f64,
+    index: usize,
+}
+
+impl PartialEq for VwCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VwCandidate {}
+
+impl PartialOrd for VwCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VwCandidate {
+    /// Reversed ordering so that the binary heap pops the smallest area first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .total_cmp(&self.area)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+/// Area of the triangle defined by three points
+fn triangle_area(p1: &Point, p2: &Point, p3: &Point) -> f64 {
+    let (x1, y1) = p1.coords();
+    let (x2, y2) = p2.coords();
+    let (x3, y3) = p3.coords();
+    ((x2 - x1) * (y3 - y1) - (x3 - x1) * (y2 - y1)).abs() / 2.0
+}
+
+/// Indices of the vertices kept by the Visvalingam-Whyatt algorithm. At least `min_keep`
+/// vertices are kept.
+fn vw_indices(points: &[Point], min_area: f64, min_keep: usize) -> Vec<usize> {
+    let total = points.len();
+    if total < 3 {
+        return (0..total).collect();
+    }
+
+    let mut prev: Vec<usize> = (0..total).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..total).map(|i| (i + 1).min(total - 1)).collect();
+    let mut areas = vec![f64::INFINITY; total];
+    let mut heap = BinaryHeap::with_capacity(total);
+    for i in 1..total - 1 {
+        areas[i] = triangle_area(&points[i - 1], &points[i], &points[i + 1]);
+        heap.push(VwCandidate {
+            area: areas[i],
+            index: i,
+        });
+    }
+
+    let mut remaining = total;
+    let mut removed = vec![false; total];
+    while let Some(VwCandidate { area, index }) = heap.pop() {
+        if removed[index] || area != areas[index] {
+            // Outdated entry
+            continue;
+        }
+        if area >= min_area || remaining <= min_keep {
+            break;
+        }
+        removed[index] = true;
+        remaining -= 1;
+
+        let (p, n) = (prev[index], next[index]);
+        next[p] = n;
+        prev[n] = p;
+
+        // Update neighbours. Their areas can't be smaller than the removed one's, so
+        // that the removal order is consistent with the effective areas.
+        for nb in [p, n] {
+            if nb == 0 || nb == total - 1 {
+                continue;
+            }
+            let new_area = triangle_area(&points[prev[nb]], &points[nb], &points[next[nb]]);
+            areas[nb] = new_area.max(area);
+            heap.push(VwCandidate {
+                area: areas[nb],
+                index: nb,
+            });
+        }
+    }
+
+    (0..total).filter(|&i| !removed[i]).collect()
+}
+
+/// Segment of a simplified sequence, given as indices into the original vertices.
+struct KeptSegment {
+    ring: usize,
+    position: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Re-insert vertices in the simplified sequences until no simplified segment intersects any
+/// other segment improperly.
+///
+/// `rings` are the original point sequences and `kept` the indices of the vertices kept in each
+/// one. Only segments that replace several original segments are refined, so any
+/// intersections present in the original geometry are left untouched. The candidate pairs of
+/// segments are found with a sweep (see `segment_intersections`) on each pass.
+fn repair_topology(rings: &[&[Point]], kept: &mut [Vec<usize>], closed: bool) {
+    loop {
+        let mut segments = Vec::new();
+        for (ring, indices) in kept.iter().enumerate() {
+            for (position, pair) in indices.windows(2).enumerate() {
+                segments.push(KeptSegment {
+                    ring,
+                    position,
+                    start: pair[0],
+                    end: pair[1],
+                });
+            }
+        }
+
+        let lines: Vec<Segment> = segments
+            .iter()
+            .map(|s| {
+                let pts = rings[s.ring];
+                Segment::new(pts[s.start].clone(), pts[s.end].clone())
+            })
+            .collect();
+        let mut pairs = Vec::new();
+        for crossing in segment_intersections(&lines) {
+            for (k, &i) in crossing.segments.iter().enumerate() {
+                pairs.extend(crossing.segments[k + 1..].iter().map(|&j| (i, j)));
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let mut to_split = Vec::new();
+        for (i, j) in pairs {
+            let (s1, s2) = (&segments[i], &segments[j]);
+            let shortcut1 = s1.end - s1.start > 1;
+            let shortcut2 = s2.end - s2.start > 1;
+            if !(shortcut1 || shortcut2) || !conflicts(rings, kept, s1, s2, closed) {
+                continue;
+            }
+            if shortcut1 {
+                to_split.push((s1.ring, s1.position));
+            }
+            if shortcut2 {
+                to_split.push((s2.ring, s2.position));
+            }
+        }
+
+        if to_split.is_empty() {
+            return;
+        }
+
+        // Insert the furthest original vertex in each conflicting segment, back to front
+        // so that positions remain valid.
+        to_split.sort();
+        to_split.dedup();
+        for &(ring, position) in to_split.iter().rev() {
+            let (start, end) = (kept[ring][position], kept[ring][position + 1]);
+            if let Some((idx, _)) = furthest_vertex(rings[ring], start, end) {
+                kept[ring].insert(position + 1, idx);
+            }
+        }
+    }
+}
+
+/// Determine whether two segments of the simplified sequences intersect improperly, that is,
+/// other than at a shared endpoint of consecutive segments.
+fn conflicts(
+    rings: &[&[Point]],
+    kept: &[Vec<usize>],
+    s1: &KeptSegment,
+    s2: &KeptSegment,
+    closed: bool,
+) -> bool {
+    let pts = rings[s1.ring];
+    let (a, b) = (&pts[s1.start], &pts[s1.end]);
+    let other = rings[s2.ring];
+    let (c, d) = (&other[s2.start], &other[s2.end]);
+
+    if s1.ring == s2.ring {
+        let total = kept[s1.ring].len() - 1;
+        if s2.position == s1.position + 1 {
+            return folds_back(a, b, d);
+        }
+        if closed && s1.position == 0 && s2.position == total - 1 {
+            // First and last segments of a ring meet at the closing vertex
+            return folds_back(b, a, c);
+        }
+    }
+    segments_intersect(
+        &Segment::new(a.clone(), b.clone()),
+        &Segment::new(c.clone(), d.clone()),
+    )
+}
+
+/// Determine whether consecutive segments `a -> b` and `b -> c` overlap beyond their shared
+/// vertex `b`.
+fn folds_back(a: &Point, b: &Point, c: &Point) -> bool {
+    if direction(a, b, c) != Turn::InLine {
+        return false;
+    }
+    let (ax, ay) = a.coords();
+    let (bx, by) = b.coords();
+    let (cx, cy) = c.coords();
+    (ax - bx) * (cx - bx) + (ay - by) * (cy - by) > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::GeometricObject;
+    use super::*;
+    use rand::{Rng, rng};
+
+    /// Generate a noisy polyline close to the X axis
+    fn noisy_line(total: usize, noise: f64) -> Vec<Point> {
+        let mut random = rng();
+        (0..total)
+            .map(|i| Point::new(i as f64, (random.random::<f64>() - 0.5) * noise))
+            .collect()
+    }
+
+    #[test]
+    fn test_douglas_peucker() {
+        let pts = noisy_line(200, 0.1);
+        let simple = douglas_peucker(&pts, 0.1);
+        assert_eq!(simple.len(), 2);
+        assert!(simple[0].is_close(&pts[0]));
+        assert!(simple[1].is_close(&pts[199]));
+
+        // Zero tolerance only removes collinear vertices
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+        ];
+        assert_eq!(douglas_peucker(&pts, 0.0).len(), 3);
+    }
+
+    #[test]
+    fn test_douglas_peucker_tolerance() {
+        let pts = noisy_line(300, 2.0);
+        let tolerance = 0.5;
+        let simple = douglas_peucker(&pts, tolerance);
+        assert!(simple.len() < pts.len());
+
+        // All original points are within tolerance of the simplified line
+        for pt in &pts {
+            let dist = simple
+                .iter()
+                .zip(simple.iter().skip(1))
+                .map(|(a, b)| pt.distance_to_segment(a, b))
+                .fold(f64::INFINITY, f64::min);
+            assert!(dist <= tolerance + 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_visvalingam_whyatt() {
+        let pts = noisy_line(200, 0.1);
+        let simple = visvalingam_whyatt(&pts, 20.0);
+        assert_eq!(simple.len(), 2);
+
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.1),
+            Point::new(2.0, 0.0),
+            Point::new(3.0, 2.0),
+            Point::new(4.0, 0.0),
+        ];
+        let simple = visvalingam_whyatt(&pts, 0.5);
+        assert_eq!(simple.len(), 4);
+        assert!(simple[1].is_close(&Point::new(2.0, 0.0)));
+        assert!(simple[2].is_close(&Point::new(3.0, 2.0)));
+        assert_eq!(visvalingam_whyatt(&pts, 0.0).len(), 5);
+    }
+
+    #[test]
+    fn test_simplify_polygon() {
+        // Circle with many vertices
+        let total = 360;
+        let mut ring: Vec<Point> = (0..total)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / total as f64;
+                Point::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        ring.push(ring[0].clone());
+        let circle = Polygon::new(ring).unwrap();
+
+        for method in [
+            SimplifyMethod::DouglasPeucker,
+            SimplifyMethod::VisvalingamWhyatt,
+        ] {
+            let simple = circle.simplify(method, 0.01).unwrap();
+            assert!(simple.outer.len() < circle.outer.len());
+            assert!((simple.area() - circle.area()).abs() < 0.1);
+            assert!(simple.is_convex());
+
+            // Huge tolerances still produce a valid polygon
+            let triangle = circle.simplify(method, 100.0).unwrap();
+            assert_eq!(triangle.outer.len(), 4);
+            assert!(triangle.area() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_preserve_topology() {
+        // Small bump with a spike reaching into it: removing the bump crosses the spike
+        let ls = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(5.0, 0.4),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, -3.0),
+            Point::new(6.0, -3.0),
+            Point::new(5.0, 0.2),
+            Point::new(4.0, -3.0),
+        ])
+        .unwrap();
+        let simple = ls.simplify(SimplifyMethod::DouglasPeucker, 0.5).unwrap();
+        assert!(has_self_intersections(&simple.points));
+
+        let preserved = ls
+            .simplify_preserve_topology(SimplifyMethod::DouglasPeucker, 0.5)
+            .unwrap();
+        assert!(!has_self_intersections(&preserved.points));
+        assert!(preserved.total_vertices() <= ls.total_vertices());
+    }
+
+    #[test]
+    fn test_preserve_topology_polygon() {
+        // Comb-shaped polygon with thin teeth
+        let mut ring = vec![Point::new(0.0, 0.0)];
+        for i in 0..10 {
+            let x = i as f64;
+            ring.push(Point::new(x, 5.0));
+            ring.push(Point::new(x + 0.5, 5.0));
+            ring.push(Point::new(x + 0.5, 1.0));
+            ring.push(Point::new(x + 1.0, 1.0));
+        }
+        ring.push(Point::new(10.0, 0.0));
+        ring.push(Point::new(0.0, 0.0));
+        let comb = Polygon::new(ring).unwrap();
+
+        for method in [
+            SimplifyMethod::DouglasPeucker,
+            SimplifyMethod::VisvalingamWhyatt,
+        ] {
+            let simple = comb.simplify_preserve_topology(method, 3.0).unwrap();
+            let mut pts = simple.outer.clone();
+            pts.pop();
+            assert!(!has_self_intersections_ring(&pts));
+            assert!(simple.area() > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_preserve_topology_long_line() {
+        // High resolution, x-monotone line: the simplified line must not intersect itself
+        let ls = LineString::new(noisy_line(20000, 2.0)).unwrap();
+        for method in [
+            SimplifyMethod::DouglasPeucker,
+            SimplifyMethod::VisvalingamWhyatt,
+        ] {
+            let simple = ls.simplify_preserve_topology(method, 0.5).unwrap();
+            assert!(simple.points.len() < ls.points.len());
+            assert!(simple.self_intersections().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_simplify_polygon_with_holes() {
+        let outer = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 10.0),
+            Point::new(5.0, 10.02),
+            Point::new(10.0, 10.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 0.0),
+        ];
+        let hole = vec![
+            Point::new(4.0, 4.0),
+            Point::new(5.0, 4.05),
+            Point::new(6.0, 4.0),
+            Point::new(6.0, 6.0),
+            Point::new(4.0, 6.0),
+            Point::new(4.0, 4.0),
+        ];
+        let poly = Polygon::with_holes(outer, vec![hole]).unwrap();
+        for method in [
+            SimplifyMethod::DouglasPeucker,
+            SimplifyMethod::VisvalingamWhyatt,
+        ] {
+            let simple = poly.simplify_preserve_topology(method, 0.2).unwrap();
+            assert_eq!(simple.outer.len(), 5);
+            assert_eq!(simple.inner.len(), 1);
+            assert_eq!(simple.inner[0].len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_invalid_tolerance() {
+        let ls = LineString::new(noisy_line(10, 1.0)).unwrap();
+        assert!(ls.simplify(SimplifyMethod::DouglasPeucker, -1.0).is_err());
+        assert!(
+            ls.simplify_preserve_topology(SimplifyMethod::VisvalingamWhyatt, f64::NAN)
+                .is_err()
+        );
+    }
+
+    /// Brute force check for intersections between non-consecutive segments of a line
+    fn has_self_intersections(pts: &[Point]) -> bool {
+        for i in 0..pts.len() - 1 {
+            for j in i + 2..pts.len() - 1 {
+                let s1 = Segment::new(pts[i].clone(), pts[i + 1].clone());
+                let s2 = Segment::new(pts[j].clone(), pts[j + 1].clone());
+                if segments_intersect(&s1, &s2) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Brute force check for intersections between non-adjacent edges of a ring given
+    /// without the closing vertex.
+    fn has_self_intersections_ring(pts: &[Point]) -> bool {
+        let n = pts.len();
+        for i in 0..n {
+            for j in i + 2..n {
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                let s1 = Segment::new(pts[i].clone(), pts[(i + 1) % n].clone());
+                let s2 = Segment::new(pts[j].clone(), pts[(j + 1) % n].clone());
+                if segments_intersect(&s1, &s2) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
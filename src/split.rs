@@ -0,0 +1,296 @@
+use super::linestring::LineString;
+use super::overlay::split_region;
+use super::points::Point;
+use super::polygons::{Polygon, shoelace};
+use super::sweep::crossings_between;
+
+/// Point at which to cut a linestring: on its segment `segment`, at the fraction `t` of it
+struct Cut {
+    segment: usize,
+    t: f64,
+    point: Point,
+}
+
+impl LineString {
+    /// Split the linestring at the given points, into the parts between them.
+    ///
+    /// Points which are not on the linestring (with the current tolerance) are ignored, as are
+    /// points at its ends. A point where the linestring goes several times splits it at each
+    /// passage. The parts are given in order along the linestring and share their endpoints.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{LineString, Point};
+    /// let line = LineString::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(4.0, 0.0),
+    ///     Point::new(4.0, 4.0),
+    /// ])
+    /// .unwrap();
+    /// let parts = line.split_at_points(&[Point::new(1.0, 0.0), Point::new(4.0, 0.0)]);
+    /// assert_eq!(parts.len(), 3);
+    /// assert_eq!(parts[1].points.len(), 2);
+    /// assert_eq!(parts[2].points[0].coords(), (4.0, 0.0));
+    /// ```
+    pub fn split_at_points(&self, points: &[Point]) -> Vec<LineString> {
+        let mut cuts = Vec::new();
+        for (segment, seg) in self.segments().enumerate() {
+            for pt in points {
+                let t = seg.project(pt).clamp(0.0, 1.0);
+                if seg.point_at(t).is_close(pt) {
+                    cuts.push(Cut {
+                        segment,
+                        t,
+                        point: pt.clone(),
+                    });
+                }
+            }
+        }
+        self.split_at_cuts(cuts)
+    }
+
+    /// Split the linestring at every point where it meets the blade, see `split_at_points`.
+    ///
+    /// Where the blade runs along the linestring, it is split at both ends of the shared part.
+    pub fn split(&self, blade: &LineString) -> Vec<LineString> {
+        let segments: Vec<_> = self.segments().collect();
+        let blade_segments: Vec<_> = blade.segments().collect();
+        let cuts = crossings_between(&segments, &blade_segments)
+            .into_iter()
+            .map(|(segment, _, point)| Cut {
+                segment,
+                t: segments[segment].project(&point).clamp(0.0, 1.0),
+                point,
+            })
+            .collect();
+        self.split_at_cuts(cuts)
+    }
+
+    fn split_at_cuts(&self, mut cuts: Vec<Cut>) -> Vec<LineString> {
+        cuts.sort_by(|a, b| a.segment.cmp(&b.segment).then(a.t.total_cmp(&b.t)));
+        let mut cuts = cuts.into_iter().peekable();
+        let mut parts = Vec::new();
+        let mut current = vec![self.points[0].clone()];
+        for (segment, (_, end)) in self.edges().enumerate() {
+            while let Some(cut) = cuts.next_if(|cut| cut.segment == segment) {
+                // Cuts at the start of the part, e.g. at a vertex, don't make a new part
+                if current.last().unwrap().is_close(&cut.point) {
+                    continue;
+                }
+                current.push(cut.point.clone());
+                parts.push(std::mem::replace(&mut current, vec![cut.point]));
+            }
+            if !(current.len() == 1 && current[0].is_close(end)) {
+                current.push(end.clone());
+            }
+        }
+        if current.len() > 1 {
+            parts.push(current);
+        }
+        parts
+            .into_iter()
+            .map(|points| LineString { points })
+            .collect()
+    }
+}
+
+impl Polygon {
+    /// Split the polygon along a blade linestring, e.g. a parcel by a road centerline.
+    ///
+    /// The polygon is cut wherever the blade crosses it from boundary to boundary, holes
+    /// included. Closed blades inside the polygon cut out the area they enclose. Parts of the
+    /// blade outside of the polygon, dangling inside it or joining its boundary to a hole
+    /// without separating anything are ignored. The parts are counter-clockwise polygons, with
+    /// the holes of the polygon they contain, and their areas add up to that of the polygon.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{GeometricObject, LineString, Point, Polygon};
+    /// let parcel = Polygon::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(0.0, 4.0),
+    ///     Point::new(6.0, 4.0),
+    ///     Point::new(6.0, 0.0),
+    ///     Point::new(0.0, 0.0),
+    /// ])
+    /// .unwrap();
+    /// let road = LineString::new(vec![Point::new(2.0, -1.0), Point::new(2.0, 5.0)]).unwrap();
+    /// let parts = parcel.split(&road);
+    /// assert_eq!(parts.len(), 2);
+    /// let mut areas: Vec<f64> = parts.iter().map(|p| p.area()).collect();
+    /// areas.sort_by(f64::total_cmp);
+    /// assert_eq!(areas, vec![8.0, 16.0]);
+    /// ```
+    pub fn split(&self, blade: &LineString) -> Vec<Polygon> {
+        // Outer ring counter-clockwise and holes clockwise, for a positive winding inside
+        let rings: Vec<Vec<Point>> = self
+            .rings()
+            .enumerate()
+            .map(|(idx, ring)| {
+                let mut ring = ring.clone();
+                if (idx == 0) == (shoelace(&ring) > 0.0) {
+                    ring.reverse();
+                }
+                ring
+            })
+            .collect();
+        split_region(&rings, std::slice::from_ref(&blade.points))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::convex_hull;
+    use super::super::core::{self, GeometricObject};
+    use super::super::test_utils::square;
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn line(coords: &[(f64, f64)]) -> LineString {
+        LineString::new(coords.iter().map(|&(x, y)| Point::new(x, y)).collect()).unwrap()
+    }
+
+    fn coords(parts: &[LineString]) -> Vec<Vec<(f64, f64)>> {
+        parts
+            .iter()
+            .map(|part| part.points.iter().map(|p| p.coords()).collect())
+            .collect()
+    }
+
+    fn total_area(polys: &[Polygon]) -> f64 {
+        polys.iter().map(|p| p.area()).sum()
+    }
+
+    #[test]
+    fn test_split_at_points() {
+        let zigzag = line(&[(0.0, 0.0), (2.0, 2.0), (4.0, 0.0), (6.0, 2.0)]);
+        let parts = zigzag.split_at_points(&[
+            Point::new(3.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.0, 0.0),
+            Point::new(6.0, 2.0),
+            Point::new(5.0, 0.0),
+        ]);
+        assert_eq!(
+            coords(&parts),
+            vec![
+                vec![(0.0, 0.0), (1.0, 1.0)],
+                vec![(1.0, 1.0), (2.0, 2.0), (3.0, 1.0)],
+                vec![(3.0, 1.0), (4.0, 0.0), (6.0, 2.0)],
+            ]
+        );
+        assert_eq!(zigzag.split_at_points(&[]).len(), 1);
+
+        // At a vertex and through a repeated vertex
+        let line = line(&[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert_eq!(
+            coords(&line.split_at_points(&[Point::new(1.0, 0.0)])),
+            vec![vec![(0.0, 0.0), (1.0, 0.0)], vec![(1.0, 0.0), (2.0, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn test_split_by_line() {
+        // Loop crossing itself, cut by a vertical line and by itself at the crossing
+        let loop_line = line(&[(0.0, 0.0), (4.0, 4.0), (4.0, 0.0), (0.0, 4.0)]);
+        let blade = line(&[(1.0, -1.0), (1.0, 5.0)]);
+        let parts = loop_line.split(&blade);
+        assert_eq!(
+            coords(&parts),
+            vec![
+                vec![(0.0, 0.0), (1.0, 1.0)],
+                vec![(1.0, 1.0), (4.0, 4.0), (4.0, 0.0), (1.0, 3.0)],
+                vec![(1.0, 3.0), (0.0, 4.0)],
+            ]
+        );
+        assert_eq!(loop_line.split_at_points(&[Point::new(2.0, 2.0)]).len(), 3);
+
+        // Overlapping blade, and a blade missing the line
+        let straight = line(&[(0.0, 0.0), (10.0, 0.0)]);
+        let overlap = straight.split(&line(&[(2.0, 0.0), (5.0, 0.0), (5.0, 3.0)]));
+        assert_eq!(overlap.len(), 3);
+        assert_eq!(overlap[1].points[1].coords(), (5.0, 0.0));
+        assert_eq!(straight.split(&line(&[(0.0, 1.0), (5.0, 1.0)])).len(), 1);
+    }
+
+    #[test]
+    fn test_split_polygon() {
+        let poly = Polygon::new(square(0.0, 0.0, 4.0)).unwrap();
+
+        // Blade zigzagging through the square, with its ends outside and dangling inside
+        let blade = line(&[(-1.0, 1.0), (5.0, 1.0), (5.0, 3.0), (2.0, 3.0), (2.0, 2.0)]);
+        let parts = poly.split(&blade);
+        assert_eq!(parts.len(), 2);
+        assert!(core::approx(total_area(&parts), 16.0));
+        let mut areas: Vec<f64> = parts.iter().map(|p| p.area()).collect();
+        areas.sort_by(f64::total_cmp);
+        assert!(core::approx(areas[0], 4.0));
+
+        // Blade not crossing the polygon
+        let parts = poly.split(&line(&[(5.0, 0.0), (5.0, 4.0)]));
+        assert_eq!(parts.len(), 1);
+        assert!(core::approx(parts[0].area(), 16.0));
+        let parts = poly.split(&line(&[(1.0, 1.0), (3.0, 3.0)]));
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn test_split_polygon_with_holes() {
+        let mut hole = square(1.0, 1.0, 2.0);
+        hole.reverse();
+        let poly = Polygon::with_holes(square(0.0, 0.0, 6.0), vec![hole]).unwrap();
+
+        // Through the hole: two parts, each with a notch
+        let parts = poly.split(&line(&[(2.0, -1.0), (2.0, 7.0)]));
+        assert_eq!(parts.len(), 2);
+        assert!(core::approx(total_area(&parts), 32.0));
+        assert!(parts.iter().all(|p| p.inner.is_empty()));
+
+        // Beside the hole: it stays in one of the parts
+        let parts = poly.split(&line(&[(5.0, -1.0), (5.0, 7.0)]));
+        assert_eq!(parts.len(), 2);
+        assert!(core::approx(total_area(&parts), 32.0));
+        assert_eq!(parts.iter().map(|p| p.inner.len()).sum::<usize>(), 1);
+
+        // From the outer boundary to the hole only: nothing is separated
+        let parts = poly.split(&line(&[(-1.0, 2.0), (2.0, 2.0)]));
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].inner.len(), 1);
+        assert!(core::approx(parts[0].area(), 32.0));
+
+        // Closed blade around the hole: a ring and the rest
+        let closed = LineString::new(square(0.5, 0.5, 3.0)).unwrap();
+        let parts = poly.split(&closed);
+        assert_eq!(parts.len(), 2);
+        assert!(core::approx(total_area(&parts), 32.0));
+        assert!(parts.iter().all(|p| p.inner.len() == 1));
+    }
+
+    #[test]
+    fn test_split_random_polygons() {
+        let mut random = rng();
+        for _ in 0..20 {
+            let pts: Vec<Point> = (0..30)
+                .map(|_| {
+                    Point::new(
+                        random.random_range(0.0..10.0),
+                        random.random_range(0.0..10.0),
+                    )
+                })
+                .collect();
+            let poly = convex_hull(&pts).unwrap();
+            let blade: Vec<Point> = (0..6)
+                .map(|_| {
+                    Point::new(
+                        random.random_range(-2.0..12.0),
+                        random.random_range(-2.0..12.0),
+                    )
+                })
+                .collect();
+            let parts = poly.split(&LineString::new(blade).unwrap());
+            assert!(!parts.is_empty());
+            assert!((total_area(&parts) - poly.area()).abs() < 1e-9 * poly.area());
+        }
+    }
+}
@@ -0,0 +1,586 @@
+use super::linestring::LineString;
+use super::points::{Point, Turn, direction};
+use super::predicates::orient2d;
+use super::segment::Segment;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Point where two or more segments meet, found by `segment_intersections`
+#[derive(Clone, Debug)]
+pub struct Crossing {
+    pub point: Point,
+    /// Indices of the segments going through the point, sorted
+    pub segments: Vec<usize>,
+}
+
+/// Find all the points where two or more segments of a set meet, with the Bentley–Ottmann
+/// sweep-line algorithm, in O((n + k) log n) expected time for n segments and k intersection
+/// points.
+///
+/// A line sweeps the plane from left to right, stopping at the endpoints of the segments and
+/// at their intersections, in lexicographic order. The segments crossing the line are kept
+/// ordered from bottom to top in a balanced search tree (a treap), so that only neighbours need
+/// to be tested for intersections.
+/// Vertical segments are swept from bottom to top, as if the line were slightly tilted.
+///
+/// Segments touching at an endpoint intersect. Collinear segments overlapping each other are
+/// reported at the endpoints of their overlap. The crossings are returned in sweep order.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, Point, Segment};
+/// let segments = vec![
+///     Segment::new(Point::new(0.0, 0.0), Point::new(2.0, 2.0)),
+///     Segment::new(Point::new(0.0, 2.0), Point::new(2.0, 0.0)),
+///     Segment::new(Point::new(1.0, -1.0), Point::new(1.0, 3.0)),
+/// ];
+/// let crossings = geomlib::segment_intersections(&segments);
+/// assert_eq!(crossings.len(), 1);
+/// assert_eq!(crossings[0].point.coords(), (1.0, 1.0));
+/// assert_eq!(crossings[0].segments, vec![0, 1, 2]);
+/// ```
+pub fn segment_intersections(segments: &[Segment]) -> Vec<Crossing> {
+    Sweep::new(segments).run()
+}
+
+/// Find the intersections between two sets of segments, e.g. two road networks, ignoring the
+/// intersections within each set.
+///
+/// Returns the index of the segment in the first set, the index of the segment in the second
+/// set and the intersection point, in sweep order. Collinear segments overlapping each other
+/// are reported at both endpoints of their overlap.
+pub fn crossings_between(first: &[Segment], second: &[Segment]) -> Vec<(usize, usize, Point)> {
+    let mut pairs = Vec::new();
+    for crossing in Sweep::new(first.iter().chain(second)).run() {
+        let split = crossing.segments.partition_point(|&s| s < first.len());
+        let (left, right) = crossing.segments.split_at(split);
+        for &i in left {
+            for &j in right {
+                pairs.push((i, j - first.len(), crossing.point.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+impl LineString {
+    /// Find the points where the linestring intersects itself, see `segment_intersections`.
+    ///
+    /// Successive edges meeting at their common vertex are not reported, nor are the first and
+    /// last edges of a closed linestring meeting at its start.
+    pub fn self_intersections(&self) -> Vec<Crossing> {
+        let edges: Vec<Segment> = self.segments().collect();
+        let last = edges.len() - 1;
+        let closed = self.points[0].coords() == self.points[last + 1].coords();
+        segment_intersections(&edges)
+            .into_iter()
+            .filter(|crossing| {
+                let (first, indices) = (crossing.segments[0], &crossing.segments);
+                let joint = |k: usize| self.points[k].coords() == crossing.point.coords();
+                let successive = indices.iter().enumerate().all(|(n, &s)| s == first + n)
+                    && (first + 1..first + indices.len()).all(joint);
+                let start = closed && indices == &[0, last] && joint(0);
+                !(successive || start)
+            })
+            .collect()
+    }
+}
+
+/// Event point of the sweep, ordered lexicographically
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct EventKey(f64, f64);
+
+impl EventKey {
+    fn new(pt: &Point) -> Self {
+        // Adding zero turns -0.0 into 0.0, so that both are the same event
+        let (x, y) = pt.coords();
+        Self(x + 0.0, y + 0.0)
+    }
+}
+
+impl Eq for EventKey {}
+
+impl PartialOrd for EventKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EventKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .total_cmp(&other.0)
+            .then_with(|| self.1.total_cmp(&other.1))
+    }
+}
+
+/// Segment oriented in the direction of the sweep
+struct Swept<'a> {
+    left: &'a Point,
+    right: &'a Point,
+}
+
+impl Swept<'_> {
+    fn is_vertical(&self) -> bool {
+        self.left.coords().0 == self.right.coords().0
+    }
+
+    /// Position of the segment relative to a point on the sweep line: below, through or above
+    fn locate(&self, pt: &Point) -> Ordering {
+        if self.is_vertical() {
+            let y = pt.coords().1;
+            if self.right.coords().1 < y {
+                Ordering::Less
+            } else if self.left.coords().1 > y {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        } else {
+            match direction(self.left, self.right, pt) {
+                Turn::Left => Ordering::Less,
+                Turn::Right => Ordering::Greater,
+                Turn::InLine => Ordering::Equal,
+            }
+        }
+    }
+
+    /// Order of two segments through the same point, just after it: by increasing slope, the
+    /// vertical segments last
+    fn cmp_slope(&self, other: &Self) -> Ordering {
+        let (x1, y1) = self.left.coords();
+        let (x2, y2) = self.right.coords();
+        let (u1, v1) = other.left.coords();
+        let (u2, v2) = other.right.coords();
+        let cross = (x2 - x1) * (v2 - v1) - (y2 - y1) * (u2 - u1);
+        if cross > 0.0 {
+            Ordering::Less
+        } else if cross < 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+/// Node of the status tree, for the segment with the same index
+#[derive(Clone, Copy, Default)]
+struct StatusNode {
+    priority: u64,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// Segments crossing the sweep line, from bottom to top, in a treap: a binary search tree which
+/// is also a heap on random priorities, hence balanced with high probability. The order of the
+/// segments only depends on the sweep, so the tree is split and merged by position instead of
+/// by key.
+struct Status {
+    nodes: Vec<StatusNode>,
+    root: Option<usize>,
+}
+
+impl Status {
+    fn new(total: usize) -> Self {
+        let nodes = (0..total as u64)
+            .map(|i| StatusNode {
+                priority: mix(i),
+                ..Default::default()
+            })
+            .collect();
+        Self { nodes, root: None }
+    }
+
+    /// Split a tree into the segments satisfying `below`, which must be the first ones in
+    /// order, and the others
+    fn split(
+        &mut self,
+        tree: Option<usize>,
+        below: &impl Fn(usize) -> bool,
+    ) -> (Option<usize>, Option<usize>) {
+        let Some(node) = tree else {
+            return (None, None);
+        };
+        if below(node) {
+            let (low, high) = self.split(self.nodes[node].right, below);
+            self.nodes[node].right = low;
+            (Some(node), high)
+        } else {
+            let (low, high) = self.split(self.nodes[node].left, below);
+            self.nodes[node].left = high;
+            (low, Some(node))
+        }
+    }
+
+    /// Merge two trees, with all the segments of `low` below those of `high`
+    fn merge(&mut self, low: Option<usize>, high: Option<usize>) -> Option<usize> {
+        match (low, high) {
+            (None, tree) | (tree, None) => tree,
+            (Some(l), Some(h)) => {
+                if self.nodes[l].priority > self.nodes[h].priority {
+                    self.nodes[l].right = self.merge(self.nodes[l].right, high);
+                    low
+                } else {
+                    self.nodes[h].left = self.merge(low, self.nodes[h].left);
+                    high
+                }
+            }
+        }
+    }
+
+    /// Segments of a tree, from bottom to top
+    fn collect(&self, tree: Option<usize>, out: &mut Vec<usize>) {
+        if let Some(node) = tree {
+            self.collect(self.nodes[node].left, out);
+            out.push(node);
+            self.collect(self.nodes[node].right, out);
+        }
+    }
+
+    /// Bottom or top segment of a tree
+    fn extreme(&self, mut tree: Option<usize>, top: bool) -> Option<usize> {
+        let mut last = None;
+        while let Some(node) = tree {
+            last = tree;
+            tree = if top {
+                self.nodes[node].right
+            } else {
+                self.nodes[node].left
+            };
+        }
+        last
+    }
+
+    /// Tree of the given segments, from bottom to top
+    fn build(&mut self, segments: &[usize]) -> Option<usize> {
+        let mut tree = None;
+        for &s in segments {
+            self.nodes[s].left = None;
+            self.nodes[s].right = None;
+            tree = self.merge(tree, Some(s));
+        }
+        tree
+    }
+}
+
+/// Pseudo-random priority of a status node (SplitMix64 finalizer)
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+struct Sweep<'a> {
+    segments: Vec<Swept<'a>>,
+    /// Event points still to visit, with the segments starting at each
+    queue: BTreeMap<EventKey, Vec<usize>>,
+    /// Segments crossing the sweep line
+    status: Status,
+    crossings: Vec<Crossing>,
+}
+
+impl<'a> Sweep<'a> {
+    fn new(segments: impl IntoIterator<Item = &'a Segment>) -> Self {
+        let mut queue: BTreeMap<EventKey, Vec<usize>> = BTreeMap::new();
+        let segments: Vec<Swept> = segments
+            .into_iter()
+            .map(|seg| {
+                let (a, b) = (&seg.start, &seg.end);
+                let (left, right) = if b.lt_lex(a) { (b, a) } else { (a, b) };
+                Swept { left, right }
+            })
+            .collect();
+        for (i, seg) in segments.iter().enumerate() {
+            queue.entry(EventKey::new(seg.left)).or_default().push(i);
+            queue.entry(EventKey::new(seg.right)).or_default();
+        }
+        Self {
+            status: Status::new(segments.len()),
+            segments,
+            queue,
+            crossings: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<Crossing> {
+        while let Some((key, starts)) = self.queue.pop_first() {
+            self.handle_event(&Point::new(key.0, key.1), starts);
+        }
+        self.crossings
+    }
+
+    fn handle_event(&mut self, pt: &Point, starts: Vec<usize>) {
+        // The segments through the point are contiguous in the status
+        let segments = &self.segments;
+        let root = self.status.root.take();
+        let (below, rest) = self
+            .status
+            .split(root, &|s| segments[s].locate(pt) == Ordering::Less);
+        let (through_tree, above) = self
+            .status
+            .split(rest, &|s| segments[s].locate(pt) == Ordering::Equal);
+        let mut through = Vec::new();
+        self.status.collect(through_tree, &mut through);
+
+        let mut involved: Vec<usize> = starts.iter().chain(&through).copied().collect();
+        involved.sort_unstable();
+        involved.dedup();
+        if involved.len() > 1 {
+            self.crossings.push(Crossing {
+                point: pt.clone(),
+                segments: involved,
+            });
+        }
+
+        // Segments going on after the point take their order just after it
+        let mut continuing: Vec<usize> = starts
+            .into_iter()
+            .chain(through)
+            .filter(|&s| segments[s].right.gt_lex(pt))
+            .collect();
+        continuing.sort_by(|&a, &b| segments[a].cmp_slope(&segments[b]).then(a.cmp(&b)));
+        let inserted = self.status.build(&continuing);
+
+        let lower = self.status.extreme(below, true);
+        let upper = self.status.extreme(above, false);
+        let middle = self.status.merge(below, inserted);
+        self.status.root = self.status.merge(middle, above);
+
+        let neighbours = match (continuing.first(), continuing.last()) {
+            (Some(&bottom), Some(&top)) => [(lower, Some(bottom)), (Some(top), upper)],
+            _ => [(lower, upper), (None, None)],
+        };
+        for pair in neighbours {
+            if let (Some(s1), Some(s2)) = pair {
+                self.find_event(s1, s2, pt);
+            }
+        }
+    }
+
+    /// Schedule the intersection of two neighbouring segments if it is ahead of the sweep.
+    /// Computed points close to a scheduled event are merged with it.
+    fn find_event(&mut self, s1: usize, s2: usize, pt: &Point) {
+        let Some(inter) = crossing_point(&self.segments[s1], &self.segments[s2]) else {
+            return;
+        };
+        if !inter.gt_lex(pt) || inter.is_close(pt) {
+            return;
+        }
+        let key = EventKey::new(&inter);
+        let close = |k: &EventKey| Point::new(k.0, k.1).is_close(&inter);
+        let before = self.queue.range(..key).next_back().map(|(k, _)| *k);
+        let after = self.queue.range(key..).next().map(|(k, _)| *k);
+        if !before.into_iter().chain(after).any(|k| close(&k)) {
+            self.queue.insert(key, Vec::new());
+        }
+    }
+}
+
+/// Both values are non-zero and of the same sign
+fn same_side(u: f64, v: f64) -> bool {
+    (u > 0.0 && v > 0.0) || (u < 0.0 && v < 0.0)
+}
+
+/// Single intersection point of two segments, with the exact predicates. Collinear segments
+/// have none: their overlap starts and ends at endpoints, which are already events.
+fn crossing_point(s1: &Swept, s2: &Swept) -> Option<Point> {
+    let (a, b) = (s1.left, s1.right);
+    let (c, d) = (s2.left, s2.right);
+    let (o1, o2) = (orient2d(a, b, c), orient2d(a, b, d));
+    let (o3, o4) = (orient2d(c, d, a), orient2d(c, d, b));
+    if (o1 == 0.0 && o2 == 0.0) || same_side(o1, o2) || same_side(o3, o4) {
+        return None;
+    }
+    if o3 == 0.0 {
+        Some(a.clone())
+    } else if o4 == 0.0 {
+        Some(b.clone())
+    } else if o1 == 0.0 {
+        Some(c.clone())
+    } else if o2 == 0.0 {
+        Some(d.clone())
+    } else {
+        // Proper crossing, at the ratio of the distances of a and b to the other line
+        let t = o3 / (o3 - o4);
+        let (x1, y1) = a.coords();
+        let (x2, y2) = b.coords();
+        Some(Point::new(x1 + t * (x2 - x1), y1 + t * (y2 - y1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ops::segments_intersect;
+    use super::*;
+    use rand::{Rng, rng};
+    use std::collections::BTreeSet;
+
+    /// Intersecting pairs of segments by brute force
+    fn brute_force(segments: &[Segment]) -> BTreeSet<(usize, usize)> {
+        let mut pairs = BTreeSet::new();
+        for i in 0..segments.len() {
+            for j in i + 1..segments.len() {
+                if segments_intersect(&segments[i], &segments[j]) {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Intersecting pairs of segments from the crossings
+    fn found_pairs(crossings: &[Crossing]) -> BTreeSet<(usize, usize)> {
+        let mut pairs = BTreeSet::new();
+        for crossing in crossings {
+            for (k, &i) in crossing.segments.iter().enumerate() {
+                for &j in &crossing.segments[k + 1..] {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    fn as_segments(pts: &[Point]) -> Vec<Segment> {
+        pts.chunks(2)
+            .map(|p| Segment::new(p[0].clone(), p[1].clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_random_segments() {
+        let mut random = rng();
+        let pts: Vec<Point> = (0..400)
+            .map(|_| Point::new(random.random_range(0.0..10.0), random.random()))
+            .collect();
+        let segments = as_segments(&pts);
+        let crossings = segment_intersections(&segments);
+        assert_eq!(found_pairs(&crossings), brute_force(&segments));
+        for crossing in &crossings {
+            for &s in &crossing.segments {
+                assert!(segments[s].distance_to_point(&crossing.point) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_status_tree() {
+        fn depth(status: &Status, tree: Option<usize>) -> usize {
+            tree.map_or(0, |node| {
+                let StatusNode { left, right, .. } = status.nodes[node];
+                1 + depth(status, left).max(depth(status, right))
+            })
+        }
+
+        let mut status = Status::new(1000);
+        let order: Vec<usize> = (0..1000).rev().collect();
+        let tree = status.build(&order);
+        assert!(depth(&status, tree) < 60);
+        assert_eq!(status.extreme(tree, false), Some(999));
+        assert_eq!(status.extreme(tree, true), Some(0));
+
+        // Take out the segments at positions 100 to 199, and put them back on top
+        let position = |s: usize| 999 - s;
+        let (low, rest) = status.split(tree, &|s| position(s) < 100);
+        let (middle, high) = status.split(rest, &|s| position(s) < 200);
+        let tree = status.merge(low, high);
+        let tree = status.merge(tree, middle);
+        let mut segments = Vec::new();
+        status.collect(tree, &mut segments);
+        let expected: Vec<usize> = order[..100]
+            .iter()
+            .chain(&order[200..])
+            .chain(&order[100..200])
+            .copied()
+            .collect();
+        assert_eq!(segments, expected);
+    }
+
+    #[test]
+    fn test_degenerate_segments() {
+        // Small integer grid: many vertical, horizontal, collinear and touching segments
+        let mut random = rng();
+        for _ in 0..20 {
+            let pts: Vec<Point> = (0..120)
+                .map(|_| {
+                    Point::new(
+                        random.random_range(0..6) as f64,
+                        random.random_range(0..6) as f64,
+                    )
+                })
+                .collect();
+            let segments = as_segments(&pts);
+            let crossings = segment_intersections(&segments);
+            assert_eq!(found_pairs(&crossings), brute_force(&segments));
+        }
+    }
+
+    #[test]
+    fn test_collinear_overlap() {
+        let pts = vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(6.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(3.0, -1.0),
+            Point::new(3.0, 1.0),
+        ];
+        let crossings = segment_intersections(&as_segments(&pts));
+        let found: Vec<((f64, f64), Vec<usize>)> = crossings
+            .iter()
+            .map(|c| (c.point.coords(), c.segments.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ((2.0, 0.0), vec![0, 1]),
+                ((3.0, 0.0), vec![0, 1, 2]),
+                ((4.0, 0.0), vec![0, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_linestring_self_intersections() {
+        // Figure of eight, closed, with a repeated vertex
+        let figure = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let crossings = figure.self_intersections();
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].point.coords(), (1.0, 1.0));
+        assert_eq!(crossings[0].segments, vec![0, 3]);
+
+        let simple = LineString::new(
+            (0..50)
+                .map(|i| Point::new(i as f64, (i % 2) as f64))
+                .collect(),
+        )
+        .unwrap();
+        assert!(simple.self_intersections().is_empty());
+    }
+
+    #[test]
+    fn test_crossings_between() {
+        let roads = [Point::new(0.0, 0.0), Point::new(4.0, 0.0)];
+        let rails = [
+            Point::new(1.0, -1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(3.0, 1.0),
+            Point::new(3.0, 1.0),
+            Point::new(3.0, -1.0),
+        ];
+        let found = crossings_between(&as_segments(&roads), &as_segments(&rails));
+        let found: Vec<(usize, usize, (f64, f64))> =
+            found.iter().map(|(i, j, p)| (*i, *j, p.coords())).collect();
+        assert_eq!(found, vec![(0, 0, (1.0, 0.0)), (0, 2, (3.0, 0.0))]);
+    }
+}
@@ -0,0 +1,27 @@
+use super::points::Point;
+use rand::{Rng, rng};
+use std::ops::Range;
+
+/// Counter-clockwise square ring with the given lower left corner
+pub(crate) fn square(x: f64, y: f64, size: f64) -> Vec<Point> {
+    vec![
+        Point::new(x, y),
+        Point::new(x + size, y),
+        Point::new(x + size, y + size),
+        Point::new(x, y + size),
+        Point::new(x, y),
+    ]
+}
+
+/// Points drawn uniformly at random in the rectangle `x` by `y`
+pub(crate) fn random_points(total: usize, x: Range<f64>, y: Range<f64>) -> Vec<Point> {
+    let mut random = rng();
+    (0..total)
+        .map(|_| {
+            Point::new(
+                random.random_range(x.clone()),
+                random.random_range(y.clone()),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,492 @@
+use super::core::{self, GeometricObject};
+use super::linestring::LineString;
+use super::points::{MultiPoint, Point};
+use super::polygons::{MultiPolygon, Polygon, ring_centroid, shoelace};
+use super::serialization::GeomWrapper;
+
+/// Affine transformation of the plane: a linear map followed by a translation.
+///
+/// A point `(x, y)` is mapped to `(a x + b y + c, d x + e y + f)`. Transformations are built
+/// from the elementary ones and chained in the order they are applied.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{AffineTransform, Point};
+/// use std::f64::consts::FRAC_PI_2;
+/// let transform = AffineTransform::scaling(2.0, 2.0)
+///     .rotate(FRAC_PI_2)
+///     .translate(1.0, 0.0);
+///
+/// let moved = transform.apply(&Point::new(1.0, 0.0));
+/// assert!(moved.is_close(&Point::new(1.0, 2.0)));
+///
+/// let back = transform.invert().unwrap().apply(&moved);
+/// assert!(back.is_close(&Point::new(1.0, 0.0)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AffineTransform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl AffineTransform {
+    /// Instantiate a transformation from its coefficients
+    pub fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self { a, b, c, d, e, f }
+    }
+
+    /// Transformation leaving all points in place
+    pub fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0)
+    }
+
+    /// Translation by the given offsets
+    pub fn translation(dx: f64, dy: f64) -> Self {
+        Self::new(1.0, 0.0, dx, 0.0, 1.0, dy)
+    }
+
+    /// Scaling by the given factors along each axis, about the origin
+    pub fn scaling(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, 0.0, sy, 0.0)
+    }
+
+    /// Counter-clockwise rotation by the given angle (in radians) about the origin
+    pub fn rotation(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+
+    /// Skew (shear) by the given angles (in radians): the vertical axis is tilted by
+    /// `angle_x` towards the x axis, and the horizontal axis by `angle_y` towards the y axis.
+    pub fn skewing(angle_x: f64, angle_y: f64) -> Self {
+        Self::new(1.0, angle_x.tan(), 0.0, angle_y.tan(), 1.0, 0.0)
+    }
+
+    /// Counter-clockwise rotation by the given angle (in radians) about a point
+    pub fn rotation_about(center: &Point, angle: f64) -> Self {
+        let (x, y) = center.coords();
+        Self::translation(-x, -y).rotate(angle).translate(x, y)
+    }
+
+    /// Scaling by the given factors along each axis, about a point
+    pub fn scaling_about(center: &Point, sx: f64, sy: f64) -> Self {
+        let (x, y) = center.coords();
+        Self::translation(-x, -y).scale(sx, sy).translate(x, y)
+    }
+
+    /// Transformation applying this one, then the other one
+    pub fn compose(&self, other: &Self) -> Self {
+        Self::new(
+            other.a * self.a + other.b * self.d,
+            other.a * self.b + other.b * self.e,
+            other.a * self.c + other.b * self.f + other.c,
+            other.d * self.a + other.e * self.d,
+            other.d * self.b + other.e * self.e,
+            other.d * self.c + other.e * self.f + other.f,
+        )
+    }
+
+    /// Follow this transformation with a translation
+    pub fn translate(&self, dx: f64, dy: f64) -> Self {
+        self.compose(&Self::translation(dx, dy))
+    }
+
+    /// Follow this transformation with a scaling about the origin
+    pub fn scale(&self, sx: f64, sy: f64) -> Self {
+        self.compose(&Self::scaling(sx, sy))
+    }
+
+    /// Follow this transformation with a rotation about the origin
+    pub fn rotate(&self, angle: f64) -> Self {
+        self.compose(&Self::rotation(angle))
+    }
+
+    /// Follow this transformation with a skew
+    pub fn skew(&self, angle_x: f64, angle_y: f64) -> Self {
+        self.compose(&Self::skewing(angle_x, angle_y))
+    }
+
+    /// Determinant of the linear part: the factor by which areas are multiplied. It is
+    /// negative if the transformation reverses orientations.
+    pub fn determinant(&self) -> f64 {
+        self.a * self.e - self.b * self.d
+    }
+
+    /// Inverse transformation, if the transformation is not degenerate
+    pub fn invert(&self) -> Option<Self> {
+        // The determinant is not compared to a tolerance: small scalings are invertible
+        let det = self.determinant();
+        if det == 0.0 {
+            return None;
+        }
+        let (a, b, d, e) = (self.e / det, -self.b / det, -self.d / det, self.a / det);
+        let inverse = Self::new(
+            a,
+            b,
+            -(a * self.c + b * self.f),
+            d,
+            e,
+            -(d * self.c + e * self.f),
+        );
+        [
+            inverse.a, inverse.b, inverse.c, inverse.d, inverse.e, inverse.f,
+        ]
+        .iter()
+        .all(|v| v.is_finite())
+        .then_some(inverse)
+    }
+
+    /// Transform a single point
+    pub fn apply(&self, pt: &Point) -> Point {
+        let (x, y) = pt.coords();
+        Point::new(
+            self.a * x + self.b * y + self.c,
+            self.d * x + self.e * y + self.f,
+        )
+    }
+}
+
+/// Geometries which can be mapped by affine transformations.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{AffineTransform, GeometricObject, Point, Polygon, Transform};
+/// let square = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(2.0, 0.0),
+///     Point::new(2.0, 2.0),
+///     Point::new(0.0, 2.0),
+///     Point::new(0.0, 0.0),
+/// ]).unwrap();
+///
+/// let diamond = square.rotate_about_centroid(std::f64::consts::FRAC_PI_4);
+/// assert!(diamond.outer[0].is_close(&Point::new(1.0, 1.0 - 2.0_f64.sqrt())));
+///
+/// let large = square.scale_about_origin(3.0, 2.0);
+/// assert!((large.area() - 24.0).abs() < 1e-9);
+/// ```
+pub trait Transform: Sized {
+    /// Apply the transformation to all the vertices of the geometry
+    fn transform(&self, transform: &AffineTransform) -> Self;
+
+    /// Center of mass of the geometry, if it is not empty: of its area for polygons, of its
+    /// length for linestrings, and of its points otherwise.
+    fn centroid(&self) -> Option<Point>;
+
+    /// Rotate the geometry counter-clockwise by the given angle (in radians) about its centroid
+    fn rotate_about_centroid(&self, angle: f64) -> Self {
+        match self.centroid() {
+            Some(center) => self.transform(&AffineTransform::rotation_about(&center, angle)),
+            None => self.transform(&AffineTransform::identity()),
+        }
+    }
+
+    /// Scale the geometry by the given factors along each axis, about the origin
+    fn scale_about_origin(&self, sx: f64, sy: f64) -> Self {
+        self.transform(&AffineTransform::scaling(sx, sy))
+    }
+
+    /// Translate the geometry by the given offsets
+    fn translate(&self, dx: f64, dy: f64) -> Self {
+        self.transform(&AffineTransform::translation(dx, dy))
+    }
+}
+
+impl Transform for Point {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        transform.apply(self)
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        Some(self.clone())
+    }
+}
+
+impl Transform for MultiPoint {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        MultiPoint::new(self.points.iter().map(|pt| transform.apply(pt)).collect())
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        mean(&self.points)
+    }
+}
+
+impl Transform for LineString {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        LineString {
+            points: self.points.iter().map(|pt| transform.apply(pt)).collect(),
+        }
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        // Midpoints of the segments weighted by their lengths
+        let (mut total, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for (start, end) in self.edges() {
+            let length = start.l2_distance(end);
+            let ((x1, y1), (x2, y2)) = (start.coords(), end.coords());
+            total += length;
+            cx += length * (x1 + x2) / 2.0;
+            cy += length * (y1 + y2) / 2.0;
+        }
+        if total > 0.0 {
+            Some(Point::new(cx / total, cy / total))
+        } else {
+            mean(&self.points)
+        }
+    }
+}
+
+impl Transform for Polygon {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        let map = |ring: &Vec<Point>| ring.iter().map(|pt| transform.apply(pt)).collect();
+        Polygon {
+            outer: map(&self.outer),
+            inner: self.inner.iter().map(map).collect(),
+        }
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        // Centroids of the rings weighted by their areas, relative to the first vertex (for
+        // precision). Holes count negatively.
+        let (ox, oy) = self.outer.first()?.coords();
+        let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for (k, ring) in self.rings().enumerate() {
+            let Some(center) = ring_centroid(ring) else {
+                continue;
+            };
+            let sign = if k == 0 { 1.0 } else { -1.0 };
+            let ring_area = sign * shoelace(ring).abs() / 2.0;
+            let (x, y) = center.coords();
+            area += ring_area;
+            cx += ring_area * (x - ox);
+            cy += ring_area * (y - oy);
+        }
+        if core::approx(area, 0.0) {
+            return mean(&self.outer[1..]);
+        }
+        Some(Point::new(ox + cx / area, oy + cy / area))
+    }
+}
+
+impl Transform for MultiPolygon {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        MultiPolygon::new(
+            self.polygons
+                .iter()
+                .map(|poly| poly.transform(transform))
+                .collect(),
+        )
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        // Centroids of the polygons weighted by their areas
+        let (mut total, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for poly in &self.polygons {
+            let (Some(center), area) = (poly.centroid(), poly.area()) else {
+                continue;
+            };
+            let (x, y) = center.coords();
+            total += area;
+            cx += area * x;
+            cy += area * y;
+        }
+        if total > 0.0 {
+            Some(Point::new(cx / total, cy / total))
+        } else {
+            let centroids: Vec<Point> = self.polygons.iter().filter_map(|p| p.centroid()).collect();
+            mean(&centroids)
+        }
+    }
+}
+
+impl Transform for GeomWrapper {
+    fn transform(&self, transform: &AffineTransform) -> Self {
+        match self {
+            GeomWrapper::Point(pt) => GeomWrapper::Point(pt.transform(transform)),
+            GeomWrapper::MultiPoint(mp) => GeomWrapper::MultiPoint(mp.transform(transform)),
+            GeomWrapper::LineString(ls) => GeomWrapper::LineString(ls.transform(transform)),
+            GeomWrapper::Polygon(poly) => GeomWrapper::Polygon(poly.transform(transform)),
+            GeomWrapper::MultiPolygon(mp) => GeomWrapper::MultiPolygon(mp.transform(transform)),
+        }
+    }
+
+    fn centroid(&self) -> Option<Point> {
+        match self {
+            GeomWrapper::Point(pt) => pt.centroid(),
+            GeomWrapper::MultiPoint(mp) => mp.centroid(),
+            GeomWrapper::LineString(ls) => ls.centroid(),
+            GeomWrapper::Polygon(poly) => poly.centroid(),
+            GeomWrapper::MultiPolygon(mp) => mp.centroid(),
+        }
+    }
+}
+
+/// Mean of a set of points, if there are any
+fn mean(points: &[Point]) -> Option<Point> {
+    if points.is_empty() {
+        return None;
+    }
+    let total = points.len() as f64;
+    let (sx, sy) = points.iter().fold((0.0, 0.0), |(sx, sy), pt| {
+        let (x, y) = pt.coords();
+        (sx + x, sy + y)
+    });
+    Some(Point::new(sx / total, sy / total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_utils::square;
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    #[test]
+    fn test_elementary_transforms() {
+        let pt = Point::new(2.0, 1.0);
+        let moved = AffineTransform::translation(-1.0, 3.0).apply(&pt);
+        assert!(moved.is_close(&Point::new(1.0, 4.0)));
+
+        let scaled = AffineTransform::scaling(2.0, -1.0).apply(&pt);
+        assert!(scaled.is_close(&Point::new(4.0, -1.0)));
+
+        let rotated = AffineTransform::rotation(FRAC_PI_2).apply(&pt);
+        assert!(rotated.is_close(&Point::new(-1.0, 2.0)));
+        let axis = Point::new(1.0, 1.0);
+        let rotated = AffineTransform::rotation_about(&axis, PI).apply(&pt);
+        assert!(rotated.is_close(&pt.rotate_around(&axis, PI)));
+
+        let skewed = AffineTransform::skewing(FRAC_PI_4, 0.0).apply(&pt);
+        assert!(skewed.is_close(&Point::new(3.0, 1.0)));
+        let skewed = AffineTransform::skewing(0.0, FRAC_PI_4).apply(&pt);
+        assert!(skewed.is_close(&Point::new(2.0, 3.0)));
+
+        let scaled = AffineTransform::scaling_about(&axis, 3.0, 3.0).apply(&pt);
+        assert!(scaled.is_close(&Point::new(4.0, 1.0)));
+    }
+
+    #[test]
+    fn test_compose_invert() {
+        let first = AffineTransform::rotation(0.3).skew(0.2, -0.1);
+        let second = AffineTransform::scaling(2.0, 0.5).translate(1.0, -4.0);
+        let both = first.compose(&second);
+        let pt = Point::new(-3.0, 7.0);
+        assert!(both.apply(&pt).is_close(&second.apply(&first.apply(&pt))));
+
+        let inverse = both.invert().unwrap();
+        assert!(inverse.apply(&both.apply(&pt)).is_close(&pt));
+        let identity = both.compose(&inverse);
+        for (coef, expected) in [
+            (identity.a, 1.0),
+            (identity.b, 0.0),
+            (identity.c, 0.0),
+            (identity.d, 0.0),
+            (identity.e, 1.0),
+            (identity.f, 0.0),
+        ] {
+            assert!(core::is_close(coef, expected, 1e-9, 1e-9));
+        }
+        let skew_det = 1.0 + 0.2_f64.tan() * 0.1_f64.tan();
+        assert!(core::approx(both.determinant(), skew_det));
+
+        assert!(AffineTransform::scaling(1.0, 0.0).invert().is_none());
+        assert!(AffineTransform::scaling(1e-300, 1e-300).invert().is_none());
+
+        // Small but invertible scalings
+        let small = AffineTransform::scaling(1e-6, 1e-6).translate(2e-6, 0.0);
+        let inverse = small.invert().unwrap();
+        assert!(core::approx(inverse.a, 1e6));
+        assert!(inverse.apply(&small.apply(&pt)).is_close(&pt));
+        assert_eq!(AffineTransform::default(), AffineTransform::identity());
+    }
+
+    #[test]
+    fn test_transform_geometries() {
+        let transform = AffineTransform::scaling(2.0, 3.0).translate(1.0, 1.0);
+
+        let mp = MultiPoint::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)]);
+        let moved = mp.transform(&transform);
+        assert!(moved.points[1].is_close(&Point::new(3.0, 4.0)));
+
+        let ls = LineString::new(square(0.0, 0.0, 1.0)).unwrap();
+        let moved = ls.transform(&transform);
+        assert_eq!(moved.points.len(), 5);
+        assert!(moved.points[2].is_close(&Point::new(3.0, 4.0)));
+
+        let poly = Polygon::with_holes(square(0.0, 0.0, 4.0), vec![square(1.0, 1.0, 1.0)]).unwrap();
+        let moved = poly.transform(&transform);
+        assert!(core::approx(moved.area(), 6.0 * poly.area()));
+        assert!(moved.inner[0][0].is_close(&Point::new(3.0, 4.0)));
+
+        let multi = MultiPolygon::new(vec![poly.clone(), moved]);
+        let moved = multi.transform(&transform);
+        assert!(core::approx(moved.area(), 6.0 * multi.area()));
+
+        let wrapped = GeomWrapper::Point(Point::new(1.0, 0.0)).translate(0.0, 2.0);
+        match wrapped {
+            GeomWrapper::Point(pt) => assert!(pt.is_close(&Point::new(1.0, 2.0))),
+            _ => panic!("Expected a point"),
+        }
+    }
+
+    #[test]
+    fn test_centroids() {
+        let mp = MultiPoint::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(2.0, 3.0),
+        ]);
+        assert!(mp.centroid().unwrap().is_close(&Point::new(2.0, 1.0)));
+        assert!(MultiPoint::new(Vec::new()).centroid().is_none());
+
+        // Long segment weighs more than the short one
+        let ls = LineString::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(3.0, 1.0),
+        ])
+        .unwrap();
+        assert!(ls.centroid().unwrap().is_close(&Point::new(1.875, 0.125)));
+
+        // Square with an off-center hole, in either orientation
+        let mut poly =
+            Polygon::with_holes(square(0.0, 0.0, 4.0), vec![square(2.0, 2.0, 2.0)]).unwrap();
+        let expected = Point::new(5.0 / 3.0, 5.0 / 3.0);
+        assert!(poly.centroid().unwrap().is_close(&expected));
+        poly.inner[0].reverse();
+        assert!(poly.centroid().unwrap().is_close(&expected));
+
+        let multi = MultiPolygon::new(vec![
+            Polygon::new(square(0.0, 0.0, 1.0)).unwrap(),
+            Polygon::new(square(2.0, 0.0, 1.0)).unwrap(),
+        ]);
+        assert!(multi.centroid().unwrap().is_close(&Point::new(1.5, 0.5)));
+    }
+
+    #[test]
+    fn test_rotate_scale_geometries() {
+        let poly = Polygon::with_holes(square(0.0, 0.0, 4.0), vec![square(2.0, 2.0, 2.0)]).unwrap();
+        let rotated = poly.rotate_about_centroid(1.0);
+        assert!(core::approx(rotated.area(), poly.area()));
+        assert!(
+            rotated
+                .centroid()
+                .unwrap()
+                .is_close(&poly.centroid().unwrap())
+        );
+
+        let scaled = poly.scale_about_origin(-1.0, 1.0);
+        assert!(core::approx(scaled.area(), poly.area()));
+        assert!(scaled.outer[2].is_close(&Point::new(-4.0, 4.0)));
+    }
+}
@@ -0,0 +1,488 @@
+use super::core::{GeomResult, GeometryError};
+use super::points::*;
+use super::polygons::{Polygon, shoelace};
+
+/// Triangulation of a polygon: a set of triangles given as indices into a vertex array.
+///
+/// All triangles are oriented counter-clockwise and have a non-zero area.
+#[derive(Clone, Debug)]
+pub struct Triangulation {
+    pub vertices: Vec<Point>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl Triangulation {
+    /// Get the corners of a triangle
+    pub fn corners(&self, triangle: &[usize; 3]) -> [&Point; 3] {
+        triangle.map(|i| &self.vertices[i])
+    }
+
+    /// Total area covered by the triangles
+    pub fn area(&self) -> f64 {
+        self.triangles
+            .iter()
+            .map(|t| {
+                let [a, b, c] = self.corners(t);
+                let ring = [a.clone(), b.clone(), c.clone(), a.clone()];
+                shoelace(&ring).abs() / 2.0
+            })
+            .sum()
+    }
+}
+
+impl Polygon {
+    /// Get the polygon's vertex array: the vertices of the outer ring followed by those of each
+    /// hole, without the closing vertices.
+    pub fn vertex_array(&self) -> Vec<Point> {
+        self.rings()
+            .flat_map(|ring| ring[..ring.len() - 1].iter().cloned())
+            .collect()
+    }
+
+    /// Triangulate the polygon by ear clipping.
+    ///
+    /// Holes are first connected to the outer ring through bridge edges, which yields a single
+    /// (weakly simple) ring that is then triangulated. The triangles are given as indices into
+    /// the polygon's `vertex_array`. Vertices where the boundary does not turn (`Turn::InLine`
+    /// with their neighbours, such as collinear or repeated vertices) are skipped, so every
+    /// triangle has a non-zero area. Returns an error if the polygon can't be triangulated,
+    /// which may happen when its rings intersect.
+    ///
+    /// Examples
+    /// ```rust
+    /// use geomlib::{GeometricObject, Point, Polygon};
+    /// let square = Polygon::new(vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(0.0, 1.0),
+    ///     Point::new(1.0, 1.0),
+    ///     Point::new(1.0, 0.0),
+    ///     Point::new(0.0, 0.0),
+    /// ]).unwrap();
+    ///
+    /// let triangulation = square.triangulate().unwrap();
+    /// assert_eq!(triangulation.triangles.len(), 2);
+    /// assert_eq!(triangulation.area(), square.area());
+    /// ```
+    pub fn triangulate(&self) -> GeomResult<Triangulation> {
+        let vertices = self.vertex_array();
+
+        // Index lists of each ring: outer counter-clockwise, holes clockwise
+        let mut offset = 0;
+        let mut rings = Vec::new();
+        for (i, ring) in self.rings().enumerate() {
+            let total = ring.len() - 1;
+            let mut indices: Vec<usize> = (offset..offset + total).collect();
+            if (i == 0) == (shoelace(ring) > 0.0) {
+                indices.reverse();
+            }
+            rings.push(indices);
+            offset += total;
+        }
+
+        let mut outer = rings.remove(0);
+        bridge_holes(&vertices, &mut outer, rings)?;
+        let triangles = clip_ears(&vertices, &outer)?;
+        Ok(Triangulation {
+            vertices,
+            triangles,
+        })
+    }
+}
+
+/// Determine whether `pt` lies inside or on the boundary of the counter-clockwise triangle
+fn in_triangle(a: &Point, b: &Point, c: &Point, pt: &Point) -> bool {
+    direction(a, b, pt) != Turn::Right
+        && direction(b, c, pt) != Turn::Right
+        && direction(c, a, pt) != Turn::Right
+}
+
+/// Determine whether the diagonal from the vertex at `pos` to `target` starts inside the
+/// polygon, that is, it lies within the angle formed by the vertex's neighbours.
+fn locally_inside(vertices: &[Point], ring: &[usize], pos: usize, target: &Point) -> bool {
+    let total = ring.len();
+    let prev = &vertices[ring[(pos + total - 1) % total]];
+    let pt = &vertices[ring[pos]];
+    let next = &vertices[ring[(pos + 1) % total]];
+    if direction(prev, pt, next) == Turn::Right {
+        // Reflex vertex: inside unless in the (convex) exterior wedge
+        direction(pt, target, next) == Turn::Left || direction(pt, prev, target) == Turn::Left
+    } else {
+        direction(pt, next, target) == Turn::Left && direction(pt, target, prev) == Turn::Left
+    }
+}
+
+/// Connect the holes to the outer ring through bridge edges, in place.
+///
+/// Holes are processed from right to left. For each one, a ray is cast from its rightmost
+/// vertex towards +x and the outer ring vertex visible from it closest to the ray's direction
+/// is chosen as the other end of the bridge (David Eberly's method). Returns an error if no
+/// such vertex is found, e.g. for a hole outside of the outer ring.
+fn bridge_holes(
+    vertices: &[Point],
+    outer: &mut Vec<usize>,
+    mut holes: Vec<Vec<usize>>,
+) -> GeomResult<()> {
+    let max_x = |hole: &Vec<usize>| {
+        hole.iter()
+            .map(|&i| vertices[i].coords().0)
+            .fold(f64::NEG_INFINITY, f64::max)
+    };
+    holes.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+
+    for hole in holes {
+        // Rightmost vertex of the hole
+        let start = (0..hole.len())
+            .max_by(|&i, &j| {
+                let (xi, yi) = vertices[hole[i]].coords();
+                let (xj, yj) = vertices[hole[j]].coords();
+                xi.total_cmp(&xj).then(yj.total_cmp(&yi))
+            })
+            .unwrap();
+        let Some(pos) = bridge_position(vertices, outer, &vertices[hole[start]]) else {
+            return Err(GeometryError::OperationError(String::from(
+                "Unable to triangulate polygon: a hole can't be bridged to the outer ring, \
+                 the polygon may be invalid",
+            )));
+        };
+
+        // Splice: ..., P, M, (rest of the hole), M, P, ...
+        let bridge_end = outer[pos];
+        let mut spliced: Vec<usize> = Vec::with_capacity(hole.len() + 2);
+        for k in 0..=hole.len() {
+            spliced.push(hole[(start + k) % hole.len()]);
+        }
+        spliced.push(bridge_end);
+        outer.splice(pos + 1..pos + 1, spliced);
+    }
+    Ok(())
+}
+
+/// Find the position in the outer ring of the vertex to bridge the hole vertex `pt` to.
+fn bridge_position(vertices: &[Point], outer: &[usize], pt: &Point) -> Option<usize> {
+    let (mx, my) = pt.coords();
+    let total = outer.len();
+
+    // Closest edge hit by the ray from the point towards +x. Only edges going "up" can be
+    // hit from the inside of a counter-clockwise ring.
+    let mut hit: Option<(f64, usize)> = None;
+    for i in 0..total {
+        let (ax, ay) = vertices[outer[i]].coords();
+        let (bx, by) = vertices[outer[(i + 1) % total]].coords();
+        if !(ay <= my && my <= by && ay < by) {
+            continue;
+        }
+        let x = ax + (my - ay) * (bx - ax) / (by - ay);
+        if x >= mx && hit.is_none_or(|(best, _)| x < best) {
+            hit = Some((x, i));
+        }
+    }
+    let (hit_x, edge) = hit?;
+
+    // Candidate: the endpoint of the edge furthest along the ray
+    let (a_pos, b_pos) = (edge, (edge + 1) % total);
+    let mut candidate = if vertices[outer[a_pos]].coords().0 > vertices[outer[b_pos]].coords().0 {
+        a_pos
+    } else {
+        b_pos
+    };
+    let hit_pt = Point::new(hit_x, my);
+    if hit_pt.is_close(&vertices[outer[a_pos]]) {
+        candidate = a_pos;
+    } else if hit_pt.is_close(&vertices[outer[b_pos]]) {
+        candidate = b_pos;
+    } else {
+        // Reflex vertices inside the triangle (M, I, P) may block the view of P: choose the
+        // one with the smallest angle to the ray instead.
+        let cand_pt = vertices[outer[candidate]].clone();
+        let (tri_a, tri_b) = if my < cand_pt.coords().1 {
+            (hit_pt.clone(), cand_pt.clone())
+        } else {
+            (cand_pt.clone(), hit_pt.clone())
+        };
+        let mut best_tan = f64::INFINITY;
+        for i in 0..total {
+            let vtx = &vertices[outer[i]];
+            let (vx, vy) = vtx.coords();
+            if vx < mx || vtx.is_close(pt) || !in_triangle(pt, &tri_a, &tri_b, vtx) {
+                continue;
+            }
+            let tan = (vy - my).abs() / (vx - mx);
+            let better =
+                tan < best_tan || (tan == best_tan && vx > vertices[outer[candidate]].coords().0);
+            if better && locally_inside(vertices, outer, i, pt) {
+                best_tan = tan;
+                candidate = i;
+            }
+        }
+    }
+
+    // The chosen vertex may appear several times in the ring after previous bridges: use an
+    // occurrence from which the bridge starts inside the polygon.
+    let target = outer[candidate];
+    (0..total)
+        .filter(|&i| outer[i] == target)
+        .find(|&i| locally_inside(vertices, outer, i, pt))
+        .or(Some(candidate))
+}
+
+/// Determine whether the boundary turns left at the vertex at position `pos`
+fn is_convex(
+    vertices: &[Point],
+    ring: &[usize],
+    prev: &[usize],
+    next: &[usize],
+    pos: usize,
+) -> bool {
+    direction(
+        &vertices[ring[prev[pos]]],
+        &vertices[ring[pos]],
+        &vertices[ring[next[pos]]],
+    ) == Turn::Left
+}
+
+/// Triangulate a counter-clockwise (weakly simple) ring given as vertex indices.
+fn clip_ears(vertices: &[Point], ring: &[usize]) -> GeomResult<Vec<[usize; 3]>> {
+    let total = ring.len();
+    let mut triangles = Vec::with_capacity(total.saturating_sub(2));
+    if total < 3 {
+        return Ok(triangles);
+    }
+
+    // Doubly-linked list over the positions in the ring
+    let mut prev: Vec<usize> = (0..total).map(|i| (i + total - 1) % total).collect();
+    let mut next: Vec<usize> = (0..total).map(|i| (i + 1) % total).collect();
+    let mut remaining = total;
+    let mut current = 0;
+    let mut stalled = 0;
+
+    while remaining > 2 {
+        let (p, n) = (prev[current], next[current]);
+        let (a, b, c) = (
+            &vertices[ring[p]],
+            &vertices[ring[current]],
+            &vertices[ring[n]],
+        );
+
+        let turn = direction(a, b, c);
+        let clip =
+            turn == Turn::InLine || (turn == Turn::Left && is_ear(vertices, ring, &prev, &next, p));
+        if clip {
+            if turn == Turn::Left {
+                triangles.push([ring[p], ring[current], ring[n]]);
+            }
+            // Remove the vertex from the ring
+            next[p] = n;
+            prev[n] = p;
+            remaining -= 1;
+            stalled = 0;
+            current = p;
+        } else {
+            stalled += 1;
+            if stalled > remaining {
+                return Err(GeometryError::OperationError(String::from(
+                    "Unable to triangulate polygon: no ear found, the polygon may be invalid",
+                )));
+            }
+            current = n;
+        }
+    }
+    Ok(triangles)
+}
+
+/// Determine whether the convex vertex after position `p` forms an ear: no other reflex
+/// vertex of the ring lies in the triangle it forms with its neighbours. Only reflex vertices
+/// can bring the boundary into the triangle, convex ones may merely touch it.
+fn is_ear(vertices: &[Point], ring: &[usize], prev: &[usize], next: &[usize], p: usize) -> bool {
+    let i = next[p];
+    let n = next[i];
+    let (a, b, c) = (&vertices[ring[p]], &vertices[ring[i]], &vertices[ring[n]]);
+
+    let mut pos = next[n];
+    while pos != p {
+        let pt = &vertices[ring[pos]];
+        let is_corner = pt.is_close(a) || pt.is_close(b) || pt.is_close(c);
+        if !is_corner && !is_convex(vertices, ring, prev, next, pos) && in_triangle(a, b, c, pt) {
+            return false;
+        }
+        pos = next[pos];
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core;
+    use super::super::core::GeometricObject;
+    use super::super::test_utils::square;
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn diamond(x: f64, y: f64, size: f64) -> Vec<Point> {
+        vec![
+            Point::new(x + size, y),
+            Point::new(x, y + size),
+            Point::new(x - size, y),
+            Point::new(x, y - size),
+            Point::new(x + size, y),
+        ]
+    }
+
+    /// Verify that the triangles are counter-clockwise and cover the polygon's area
+    fn check_triangulation(poly: &Polygon, tri: &Triangulation) {
+        for t in &tri.triangles {
+            let [a, b, c] = tri.corners(t);
+            assert_eq!(direction(a, b, c), Turn::Left);
+        }
+        assert!(core::is_close(tri.area(), poly.area(), 1e-9, 1e-12));
+    }
+
+    #[test]
+    fn test_triangulate_convex() {
+        let mut random = rng();
+        let pts: Vec<Point> = (0..100)
+            .map(|_| Point::new(random.random(), random.random()))
+            .collect();
+        let hull = super::super::convex_hull(&pts).unwrap();
+        let tri = hull.triangulate().unwrap();
+        assert_eq!(tri.triangles.len(), hull.outer.len() - 3);
+        check_triangulation(&hull, &tri);
+    }
+
+    #[test]
+    fn test_triangulate_concave() {
+        // Comb shape, given clockwise
+        let mut ring = vec![Point::new(0.0, 0.0), Point::new(0.0, 2.0)];
+        for i in 0..5 {
+            let x = i as f64 * 2.0;
+            ring.push(Point::new(x + 1.0, 2.0));
+            ring.push(Point::new(x + 1.0, 1.0));
+            ring.push(Point::new(x + 2.0, 1.0));
+            ring.push(Point::new(x + 2.0, 2.0));
+        }
+        ring.push(Point::new(11.0, 2.0));
+        ring.push(Point::new(11.0, 0.0));
+        ring.push(Point::new(0.0, 0.0));
+        let comb = Polygon::new(ring).unwrap();
+
+        let tri = comb.triangulate().unwrap();
+        assert_eq!(tri.triangles.len(), comb.outer.len() - 3);
+        check_triangulation(&comb, &tri);
+    }
+
+    #[test]
+    fn test_triangulate_collinear() {
+        // Square with extra vertices along its edges and a repeated vertex
+        let poly = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 1.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let tri = poly.triangulate().unwrap();
+        check_triangulation(&poly, &tri);
+        assert!(tri.triangles.len() <= 6);
+    }
+
+    #[test]
+    fn test_triangulate_holes() {
+        let mut holes = Vec::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                let (x, y) = (i as f64, j as f64);
+                holes.push(diamond(
+                    2.0 + 3.0 * x + 0.2 * y * y,
+                    2.0 + 3.0 * y + 0.3 * x * x,
+                    0.5,
+                ));
+            }
+        }
+        let poly = Polygon::with_holes(square(0.0, 0.0, 10.0), holes).unwrap();
+        let tri = poly.triangulate().unwrap();
+
+        // n + 2h - 2 triangles for n vertices and h holes
+        assert_eq!(tri.vertices.len(), 40);
+        assert_eq!(tri.triangles.len(), 40 + 2 * 9 - 2);
+        check_triangulation(&poly, &tri);
+
+        // No triangle covers a hole's center
+        for t in &tri.triangles {
+            let [a, b, c] = tri.corners(t);
+            assert!(!in_triangle(a, b, c, &Point::new(2.0, 2.0)));
+            assert!(!in_triangle(a, b, c, &Point::new(8.2, 6.2)));
+        }
+    }
+
+    #[test]
+    fn test_triangulate_aligned_holes() {
+        // Holes whose rightmost vertices are aligned with each other and the outer vertices
+        let poly = Polygon::with_holes(
+            square(0.0, 0.0, 6.0),
+            vec![square(1.0, 1.0, 1.0), square(3.0, 1.0, 1.0)],
+        )
+        .unwrap();
+        let tri = poly.triangulate().unwrap();
+        check_triangulation(&poly, &tri);
+
+        // Bridges running through an outer vertex and along the edges of other holes
+        let poly = Polygon::with_holes(
+            diamond(0.0, 0.0, 10.0),
+            vec![
+                diamond(-3.0, 0.0, 0.75),
+                diamond(-1.0, 0.0, 0.5),
+                diamond(2.0, 0.0, 1.0),
+            ],
+        )
+        .unwrap();
+        let tri = poly.triangulate().unwrap();
+        check_triangulation(&poly, &tri);
+    }
+
+    #[test]
+    fn test_triangulate_invalid() {
+        // Bow-tie: self-intersecting
+        let bowtie = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        assert!(matches!(
+            bowtie.triangulate(),
+            Err(GeometryError::OperationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_triangulate_hole_outside() {
+        // The hole lies outside of the outer ring: it can't be bridged
+        let poly = Polygon::with_holes(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(0.0, 2.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 0.0),
+            ],
+            vec![vec![
+                Point::new(3.0, 0.5),
+                Point::new(4.0, 0.5),
+                Point::new(4.0, 1.5),
+                Point::new(3.0, 1.5),
+                Point::new(3.0, 0.5),
+            ]],
+        )
+        .unwrap();
+        assert!(matches!(
+            poly.triangulate(),
+            Err(GeometryError::OperationError(_))
+        ));
+    }
+}
@@ -0,0 +1,417 @@
+use super::core::GeomResult;
+use super::delaunay::TriangleMesh;
+use super::ops::{clip_polygon, convex_hull};
+use super::overlay::positive_region;
+use super::points::*;
+use super::polygons::{MultiPolygon, Polygon, shoelace};
+
+/// Compute the Voronoi diagram of a set of sites, clipped to a bounding polygon.
+///
+/// The Voronoi cell of a site is the region of the plane closer to it than to any other site.
+/// Cells are computed as the dual of the Delaunay triangulation of the sites: the vertices of a
+/// cell are the circumcenters of the triangles around its site, and the cells of sites on the
+/// convex hull are unbounded. Each cell is then intersected with the bounding polygon, which
+/// does not need to be convex and may have holes.
+///
+/// Returns one cell per site, in the same order. A cell is empty if it doesn't intersect the
+/// bounding polygon, and may have several parts if the bounding polygon is not convex.
+/// Duplicate sites get the same cell.
+///
+/// Examples
+/// ```rust
+/// use geomlib::{self, GeometricObject, Point, Polygon};
+/// let bounds = Polygon::new(vec![
+///     Point::new(0.0, 0.0),
+///     Point::new(4.0, 0.0),
+///     Point::new(4.0, 2.0),
+///     Point::new(0.0, 2.0),
+///     Point::new(0.0, 0.0),
+/// ]).unwrap();
+/// let sites = vec![Point::new(1.0, 1.0), Point::new(3.0, 1.0)];
+///
+/// let cells = geomlib::voronoi_cells(&sites, &bounds).unwrap();
+/// assert!(geomlib::approx(cells[0].area(), 4.0));
+/// assert!(cells[1].contains(&Point::new(3.5, 0.5)));
+/// ```
+pub fn voronoi_cells(sites: &[Point], bounds: &Polygon) -> GeomResult<Vec<MultiPolygon>> {
+    if sites.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut mesh = TriangleMesh::new();
+    let ids: Vec<usize> = sites
+        .iter()
+        .filter_map(|site| mesh.insert(site.clone()))
+        .collect();
+
+    // Distance beyond which the unbounded cells can be truncated: cells are convex, so
+    // truncating them far enough from all the sites, bounds and circumcenters changes nothing
+    // within the bounds.
+    let circumcenters: Vec<Point> = (0..mesh.triangles().len())
+        .filter_map(|t| {
+            let [a, b, c] = mesh.corners(t);
+            circumcenter(a, b, c)
+        })
+        .collect();
+    let all_points = || {
+        mesh.vertices()
+            .iter()
+            .chain(&bounds.outer)
+            .chain(&circumcenters)
+    };
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for pt in all_points() {
+        let (x, y) = pt.coords();
+        (min_x, min_y) = (min_x.min(x), min_y.min(y));
+        (max_x, max_y) = (max_x.max(x), max_y.max(y));
+    }
+    let center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+    let radius = all_points()
+        .map(|pt| pt.l2_distance(&center))
+        .fold(0.0, f64::max);
+    let reach = 4.0 * radius + 1.0;
+    let clip = Clip::new(bounds, &center, reach);
+
+    let mut cells = Vec::with_capacity(mesh.vertices().len());
+    for v in 0..mesh.vertices().len() {
+        let cell = if mesh.triangles().is_empty() {
+            collinear_cell(&mesh, v, &center, reach)?
+        } else {
+            dual_cell(&mesh, v, reach)
+        };
+        cells.push(match cell {
+            Some(cell) => clip.apply(&cell)?,
+            None => MultiPolygon::new(Vec::new()),
+        });
+    }
+    Ok(ids.into_iter().map(|v| cells[v].clone()).collect())
+}
+
+/// Intersection of the (convex) cells with the bounding polygon
+enum Clip<'a> {
+    /// Convex bounds without holes, used as the clipping window of `clip_polygon`
+    Convex(&'a Polygon),
+    /// Rings of other bounds for the overlay: the outer ring counter-clockwise, the holes and a
+    /// frame around everything clockwise. Adding a counter-clockwise cell, the winding number
+    /// is only positive inside both the cell and the bounds.
+    Overlay(Vec<Vec<Point>>),
+}
+
+impl<'a> Clip<'a> {
+    /// Prepare the clipping of cells lying within distance `reach` of `center`
+    fn new(bounds: &'a Polygon, center: &Point, reach: f64) -> Self {
+        if bounds.is_convex() && bounds.inner.is_empty() {
+            return Clip::Convex(bounds);
+        }
+        let mut rings: Vec<Vec<Point>> = bounds
+            .rings()
+            .enumerate()
+            .map(|(idx, ring)| {
+                let mut ring = ring.clone();
+                if (idx == 0) == (shoelace(&ring) > 0.0) {
+                    ring.reverse();
+                }
+                ring
+            })
+            .collect();
+        let (cx, cy) = center.coords();
+        let half = 2.0 * reach;
+        rings.push(vec![
+            Point::new(cx - half, cy - half),
+            Point::new(cx - half, cy + half),
+            Point::new(cx + half, cy + half),
+            Point::new(cx + half, cy - half),
+            Point::new(cx - half, cy - half),
+        ]);
+        Clip::Overlay(rings)
+    }
+
+    fn apply(&self, cell: &Polygon) -> GeomResult<MultiPolygon> {
+        match self {
+            Clip::Convex(bounds) => Ok(MultiPolygon::new(
+                clip_polygon(bounds, cell)?.into_iter().collect(),
+            )),
+            Clip::Overlay(rings) => {
+                let mut outer = cell.outer.clone();
+                if shoelace(&outer) > 0.0 {
+                    outer.reverse();
+                }
+                let mut rings = rings.clone();
+                rings.push(outer);
+                Ok(MultiPolygon::new(positive_region(&rings)))
+            }
+        }
+    }
+}
+
+/// Cell of a vertex of a (non-empty) Delaunay mesh, from the circumcenters of the triangles
+/// around it. Unbounded cells are truncated at distance `reach` from the mesh.
+fn dual_cell(mesh: &TriangleMesh, v: usize, reach: f64) -> Option<Polygon> {
+    let around = mesh.vertex_triangles(v);
+    let mut vertices: Vec<Point> = around
+        .iter()
+        .filter_map(|&t| {
+            let [a, b, c] = mesh.corners(t);
+            circumcenter(a, b, c)
+        })
+        .collect();
+
+    let neighbors = mesh.vertex_neighbors(v);
+    if neighbors.len() > around.len() {
+        // Site on the convex hull: the cell extends outwards along the normals of the two
+        // boundary edges around the site, and along their bisector from the site itself.
+        let site = &mesh.vertices()[v];
+        let next = &mesh.vertices()[neighbors[0]];
+        let prev = &mesh.vertices()[neighbors[neighbors.len() - 1]];
+        let out_first = outward_normal(site, next);
+        let out_last = outward_normal(prev, site);
+        let (sx, sy) = (out_first.0 + out_last.0, out_first.1 + out_last.1);
+        let norm = (sx * sx + sy * sy).sqrt();
+
+        let far = |from: &Point, (dx, dy): (f64, f64)| {
+            let (x, y) = from.coords();
+            Point::new(x + reach * dx, y + reach * dy)
+        };
+        let (first, last) = (vertices.first()?.clone(), vertices.last()?.clone());
+        vertices.push(far(&first, out_first));
+        vertices.push(far(&last, out_last));
+        if norm > 0.0 {
+            vertices.push(far(site, (sx / norm, sy / norm)));
+        }
+    }
+    // The cell is convex and all these points lie in it
+    convex_hull(&vertices)
+}
+
+/// Unit normal to the right of the edge from `a` to `b`: outside of the mesh for an edge on
+/// its boundary
+fn outward_normal(a: &Point, b: &Point) -> (f64, f64) {
+    let ((ax, ay), (bx, by)) = (a.coords(), b.coords());
+    let len = a.l2_distance(b);
+    ((by - ay) / len, (ax - bx) / len)
+}
+
+/// Cell of a vertex when all the sites are collinear: a strip between the bisectors with its
+/// neighbours along the line, truncated to a square of half-size `reach` around `center`.
+fn collinear_cell(
+    mesh: &TriangleMesh,
+    v: usize,
+    center: &Point,
+    reach: f64,
+) -> GeomResult<Option<Polygon>> {
+    let (cx, cy) = center.coords();
+    let mut cell = Polygon::new(vec![
+        Point::new(cx - reach, cy - reach),
+        Point::new(cx + reach, cy - reach),
+        Point::new(cx + reach, cy + reach),
+        Point::new(cx - reach, cy + reach),
+        Point::new(cx - reach, cy - reach),
+    ])?;
+
+    // Neighbours along the line: the closest vertices on either side
+    let vertices = mesh.vertices();
+    let site = &vertices[v];
+    let axis = vertices
+        .iter()
+        .max_by(|a, b| a.l2_distance(site).total_cmp(&b.l2_distance(site)))
+        .filter(|far| !far.is_close(site))
+        .map(|far| {
+            let ((sx, sy), (fx, fy)) = (site.coords(), far.coords());
+            (fx - sx, fy - sy)
+        });
+    if let Some((ax, ay)) = axis {
+        let position = |pt: &Point| {
+            let ((sx, sy), (px, py)) = (site.coords(), pt.coords());
+            (px - sx) * ax + (py - sy) * ay
+        };
+        let before = vertices
+            .iter()
+            .filter(|pt| position(pt) < 0.0)
+            .max_by(|a, b| position(a).total_cmp(&position(b)));
+        let after = vertices
+            .iter()
+            .filter(|pt| position(pt) > 0.0)
+            .min_by(|a, b| position(a).total_cmp(&position(b)));
+
+        for other in before.into_iter().chain(after) {
+            cell = match clip_polygon(&cell, &half_plane(site, other, reach)) {
+                Ok(Some(clipped)) => clipped,
+                _ => return Ok(None),
+            };
+        }
+    }
+    Ok(convex_hull(&cell.outer))
+}
+
+/// Large rectangle standing for the half-plane of points closer to `site` than to `other`
+fn half_plane(site: &Point, other: &Point, reach: f64) -> Polygon {
+    let ((sx, sy), (ox, oy)) = (site.coords(), other.coords());
+    let len = site.l2_distance(other);
+    let (dx, dy) = ((ox - sx) / len, (oy - sy) / len);
+    let (mx, my) = ((sx + ox) / 2.0, (sy + oy) / 2.0);
+    let (nx, ny) = (-dy * 4.0 * reach, dx * 4.0 * reach);
+    let (bx, by) = (-dx * 4.0 * reach, -dy * 4.0 * reach);
+    Polygon {
+        outer: vec![
+            Point::new(mx - nx, my - ny),
+            Point::new(mx + nx, my + ny),
+            Point::new(mx + nx + bx, my + ny + by),
+            Point::new(mx - nx + bx, my - ny + by),
+            Point::new(mx - nx, my - ny),
+        ],
+        inner: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::core::{self, GeometricObject};
+    use super::*;
+    use rand::{Rng, rng};
+
+    fn rectangle(width: f64, height: f64) -> Polygon {
+        Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(width, 0.0),
+            Point::new(width, height),
+            Point::new(0.0, height),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap()
+    }
+
+    /// Verify that the cells tile the bounds and are made of the points closest to their site
+    fn check_cells(sites: &[Point], bounds: &Polygon, cells: &[MultiPolygon]) {
+        assert_eq!(cells.len(), sites.len());
+        let total: f64 = cells.iter().map(|cell| cell.area()).sum();
+        assert!(core::is_close(total, bounds.area(), 1e-9, 1e-9));
+
+        let mut random = rng();
+        for _ in 0..200 {
+            let pt = Point::new(random.random(), random.random());
+            if !bounds.contains(&pt) {
+                continue;
+            }
+            let nearest = (0..sites.len())
+                .min_by(|&i, &j| {
+                    sites[i]
+                        .l2_distance(&pt)
+                        .total_cmp(&sites[j].l2_distance(&pt))
+                })
+                .unwrap();
+            assert!(cells[nearest].contains(&pt));
+        }
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let sites = vec![
+            Point::new(0.25, 0.25),
+            Point::new(0.75, 0.25),
+            Point::new(0.75, 0.75),
+            Point::new(0.25, 0.75),
+        ];
+        let bounds = rectangle(1.0, 1.0);
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        for (site, cell) in sites.iter().zip(&cells) {
+            assert_eq!(cell.polygons.len(), 1);
+            assert!(core::approx(cell.area(), 0.25));
+            assert!(cell.contains(site));
+        }
+        check_cells(&sites, &bounds, &cells);
+    }
+
+    #[test]
+    fn test_random_sites() {
+        let mut random = rng();
+        let sites: Vec<Point> = (0..100)
+            .map(|_| Point::new(random.random(), random.random()))
+            .collect();
+        let bounds = rectangle(1.0, 1.0);
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        check_cells(&sites, &bounds, &cells);
+    }
+
+    #[test]
+    fn test_non_convex_bounds() {
+        // L-shaped bounds: the cell of the first site is in two parts
+        let sites = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+        let bounds = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(1.0, 0.5),
+            Point::new(0.5, 0.5),
+            Point::new(0.5, 1.0),
+            Point::new(0.0, 1.0),
+            Point::new(0.0, 0.0),
+        ])
+        .unwrap();
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        check_cells(&sites, &bounds, &cells);
+        assert_eq!(cells[0].polygons.len(), 1);
+        assert_eq!(cells[1].polygons.len(), 2);
+        assert!(core::approx(cells[1].area(), 0.25));
+
+        // Bounds with a hole around which the cell of the first site goes
+        let sites = vec![
+            Point::new(0.3, 0.2),
+            Point::new(0.8, 0.2),
+            Point::new(0.2, 0.8),
+            Point::new(0.4, 0.4),
+            Point::new(0.9, 0.9),
+        ];
+        let bounds = Polygon::with_holes(
+            rectangle(1.0, 1.0).outer,
+            vec![vec![
+                Point::new(0.1, 0.1),
+                Point::new(0.2, 0.1),
+                Point::new(0.2, 0.2),
+                Point::new(0.1, 0.2),
+                Point::new(0.1, 0.1),
+            ]],
+        )
+        .unwrap();
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        check_cells(&sites, &bounds, &cells);
+        assert_eq!(cells[0].polygons.len(), 1);
+        assert_eq!(cells[0].polygons[0].inner.len(), 1);
+    }
+
+    #[test]
+    fn test_degenerate_sites() {
+        let bounds = rectangle(3.0, 1.0);
+
+        // Single site: the whole bounds
+        let cells = voronoi_cells(&[Point::new(0.5, 0.5)], &bounds).unwrap();
+        assert!(core::approx(cells[0].area(), 3.0));
+
+        // Collinear sites: strips
+        let sites: Vec<Point> = (0..3).map(|i| Point::new(i as f64 + 0.5, 0.5)).collect();
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        for cell in &cells {
+            assert!(core::approx(cell.area(), 1.0));
+        }
+
+        // Duplicates get the same cell
+        let sites = vec![
+            Point::new(0.5, 0.5),
+            Point::new(2.5, 0.5),
+            Point::new(0.5, 0.5),
+        ];
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        assert!(core::approx(cells[2].area(), 1.5));
+
+        // Site far outside of the bounds
+        let sites = vec![
+            Point::new(0.5, 0.5),
+            Point::new(2.5, 0.5),
+            Point::new(1.5, 0.8),
+            Point::new(1.5, 50.0),
+        ];
+        let cells = voronoi_cells(&sites, &bounds).unwrap();
+        assert!(cells[3].polygons.is_empty());
+        check_cells(&sites, &bounds, &cells);
+
+        assert!(voronoi_cells(&[], &bounds).unwrap().is_empty());
+    }
+}